@@ -45,6 +45,7 @@ fn main() {
     let engine_options = EngineOptions {
         window_size: WindowSize::new(500.0, 500.0),
         app_name: "lxy 2d mipmap demo",
+        ..Default::default()
     };
     let mut engine = Engine::new(engine_options);
     let color_texture_handle = engine.texture_sampler_manager.create_texture_from_image(
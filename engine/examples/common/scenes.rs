@@ -0,0 +1,170 @@
+//! Scene builders shared by the examples and the golden-image tests.
+//!
+//! Examples include this file by `#[path = "common/scenes.rs"] mod scenes;`, and `tests/golden_images.rs` includes it
+//! by `#[path = "../examples/common/scenes.rs"] mod scenes;`, so that the tests render the same scenes as the examples.
+//! Interactive parts, e.g., camera controllers and GUI behaviors, are added by the examples themselves.
+
+// Every example only uses some of the builders.
+#![allow(dead_code)]
+
+use imagic::prelude::*;
+
+/// The nodes of the scene shared by `primitives_demo` and `spot_light_demo`.
+pub struct PrimitivesScene {
+    pub skybox: NodeHandle,
+    pub camera: NodeHandle,
+    /// The ground quad, the cuboid and the sphere.
+    pub primitives: Vec<NodeHandle>,
+}
+
+/// The nodes of the scene of `spot_light_demo`.
+pub struct SpotLightScene {
+    pub primitives_scene: PrimitivesScene,
+    pub spot_light: NodeHandle,
+}
+
+/// The nodes of the scene of `unlit_demo`.
+pub struct UnlitScene {
+    pub quad: NodeHandle,
+    pub camera: NodeHandle,
+}
+
+/// The nodes and the material of the scene of `ibl_demo`.
+pub struct IblScene {
+    pub skybox: NodeHandle,
+    pub camera: NodeHandle,
+    pub sphere: NodeHandle,
+    pub material: MaterialHandle,
+}
+
+pub fn create_skybox(engine: &mut Engine, hdr: &[u8]) -> NodeHandle {
+    SkyboxBuilder::create_skybox(
+        engine,
+        vec![hdr.to_vec()],
+        TextureFormat::Rgba32Float,
+        SkyboxBuilderOptions::default(),
+    )
+}
+
+pub fn create_camera(engine: &mut Engine, position: Vec3, fov: Option<f32>) -> NodeHandle {
+    let scene = engine.world.current_scene_mut();
+    let camera_node = scene.create_node("Main Camera");
+    let mut camera = Camera::default();
+    camera.clear_color = Some(Color::scalar(0.3));
+    if let Some(fov) = fov {
+        camera.fov = fov;
+    }
+    scene.add_component(&camera_node, camera);
+    scene.get_node_mut_forcely(&camera_node).transform.set_position(position);
+    scene.add(camera_node);
+    camera_node
+}
+
+/// A white, non-metallic and fully rough PBR material.
+pub fn create_white_pbr_material(engine: &mut Engine) -> MaterialHandle {
+    let pbr_material = engine.create_pbr_material();
+    let pbr_material_mut_ref = engine.material_manager.get_material_mut_forcely(&pbr_material);
+    pbr_material_mut_ref.set_albedo_color(Color::WHITE);
+    pbr_material_mut_ref.set_vec4f(BuiltinShaderUniformNames::_METALLIC_ROUGHNESS_AO, Vec4::new(0.0, 1.0, 1.0, 1.0));
+    pbr_material
+}
+
+pub fn create_mesh_node(engine: &mut Engine, name: &str, mesh: Mesh, material: MaterialHandle) -> NodeHandle {
+    let mesh_handle = engine.mesh_manager.add_mesh(mesh);
+    let scene = engine.world.current_scene_mut();
+    let node = scene.create_node(name);
+    scene.add_component(&node, MeshRenderer::new(mesh_handle, vec![material]));
+    scene.add(node);
+    node
+}
+
+/// Creates the ground quad, the cuboid and the sphere.
+pub fn create_primitives(engine: &mut Engine, material: MaterialHandle) -> Vec<NodeHandle> {
+    let quad_node = create_mesh_node(engine, "quad", Quad::new(2.0, 1.0, 2, 1).into(), material);
+    {
+        let transform = &mut engine.world.current_scene_mut().get_node_mut_forcely(&quad_node).transform;
+        transform.set_position_y(-0.5);
+        transform.set_rotation_euler(Vec3::new(-90.0f32.to_radians(), 0.0, 0.0));
+        transform.set_uniform_scale(5.0);
+    }
+    let cuboid_node = create_mesh_node(engine, "Cuboid", Cuboid::default().into(), material);
+    let sphere_node = create_mesh_node(engine, "UVSphere", UVSphere::default().into(), material);
+    engine.world.current_scene_mut().get_node_mut_forcely(&sphere_node).transform.set_position_x(1.5);
+    vec![quad_node, cuboid_node, sphere_node]
+}
+
+/// Builds the scene of `primitives_demo`, all primitives use `material`.
+pub fn build_primitives_scene(engine: &mut Engine, skybox_hdr: &[u8], material: MaterialHandle) -> PrimitivesScene {
+    let skybox = create_skybox(engine, skybox_hdr);
+    let primitives = create_primitives(engine, material);
+    let camera = create_camera(engine, Vec3::new(0.0, 8.0, 12.0), None);
+    PrimitivesScene { skybox, camera, primitives }
+}
+
+/// Builds the scene of `spot_light_demo`: the primitives with a white PBR material, lit by a spot light facing down.
+pub fn build_spot_light_scene(engine: &mut Engine, skybox_hdr: &[u8]) -> SpotLightScene {
+    let material = create_white_pbr_material(engine);
+    let primitives_scene = build_primitives_scene(engine, skybox_hdr, material);
+
+    let scene = engine.world.current_scene_mut();
+    let spot_light = scene.create_node("Spot Light");
+    scene.add_component(
+        &spot_light,
+        Light::new_spot_light(10.0, 45.0f32.to_radians(), 50.0f32.to_radians(), Color::WHITE, 10.0, true),
+    );
+    scene.add(spot_light);
+    let transform = &mut scene.get_node_mut_forcely(&spot_light).transform;
+    transform.set_position(Vec3::new(0.0, 1.0, 0.0));
+    transform.set_rotation_euler(Vec3::new(-90.0f32.to_radians(), 0.0, 0.0));
+    SpotLightScene { primitives_scene, spot_light }
+}
+
+/// Builds the scene of `unlit_demo`: a quad textured by `image` in front of the camera.
+pub fn build_unlit_scene(engine: &mut Engine, image: &[u8]) -> UnlitScene {
+    let color_texture = engine.texture_sampler_manager.create_texture_from_image(
+        vec![image.to_vec()],
+        TextureDimension::D2,
+        TextureFormat::Rgba8UnormSrgb,
+        true,
+        // 没有填充 mipmaps 时, jpg 图片可能是黑色，显示了非 lod 0 的内容。
+        true,
+    );
+    let color_sampler = engine.texture_sampler_manager.create_sampler(
+        AddressMode::ClampToEdge,
+        AddressMode::ClampToEdge,
+        AddressMode::ClampToEdge,
+        FilterMode::Linear,
+        FilterMode::Linear,
+        FilterMode::Linear,
+    );
+    let (_, shader) = engine.shader_manager.get_builtin_unlit_shader();
+    let unlit_material = engine.material_manager.create_material(*shader, &mut engine.shader_manager);
+    {
+        let unlit_material_mut_ref = engine.material_manager.get_material_mut_forcely(&unlit_material);
+        unlit_material_mut_ref.set_albedo_color(Color::WHITE);
+        unlit_material_mut_ref.set_albedo_map(color_texture);
+        unlit_material_mut_ref.set_albedo_map_sampler(color_sampler);
+    }
+    let quad = create_mesh_node(engine, "quad", Quad::default().into(), unlit_material);
+    let camera = create_camera(engine, Vec3::new(0.0, 0.0, 2.5), None);
+    UnlitScene { quad, camera }
+}
+
+/// Builds the scene of `ibl_demo`: a sphere lit by the image based lighting of the skybox.
+pub fn build_ibl_scene(engine: &mut Engine, skybox_hdr: &[u8]) -> IblScene {
+    let material = create_white_pbr_material(engine);
+    let reflection_cube_sampler = engine.texture_sampler_manager.create_sampler(
+        AddressMode::ClampToEdge,
+        AddressMode::ClampToEdge,
+        AddressMode::ClampToEdge,
+        FilterMode::Linear,
+        FilterMode::Linear,
+        FilterMode::Nearest,
+    );
+    engine.material_manager.get_material_mut_forcely(&material)
+        .set_sampler("_reflection_cube_sampler", reflection_cube_sampler);
+    let sphere = create_mesh_node(engine, "UVSphere", UVSphere::default().into(), material);
+    let camera = create_camera(engine, Vec3::new(0.0, 1.5, 1.5), Some(std::f32::consts::FRAC_PI_4));
+    let skybox = create_skybox(engine, skybox_hdr);
+    IblScene { skybox, camera, sphere, material }
+}
@@ -65,6 +65,7 @@ impl Game {
         let engine_options = EngineOptions {
             window_size: WindowSize::new(800.0, 500.0),
             app_name: "lxy cube_mipmap demo",
+            ..Default::default()
         };
         let engine = Engine::new(engine_options);
         Self { engine }
@@ -71,6 +71,7 @@ pub fn main() {
     let engine_options = EngineOptions {
         window_size: WindowSize::new(500.0, 500.0),
         app_name: "lxy custom shader demo",
+        ..Default::default()
     };
     let mut engine = Engine::new(engine_options);
     let shader = engine.create_shader(
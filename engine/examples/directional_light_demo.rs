@@ -5,6 +5,7 @@ struct GameBehavior {
     directional_light_node_handle: NodeHandle,
     enable_skybox: bool,
     enable_directional_light: bool,
+    enable_shadow: bool,
     light_intensity: f32,
     light_rotation_x: f32,
     light_rotation_y: f32,
@@ -25,6 +26,12 @@ impl Behavior for GameBehavior {
                 node.enabled = self.enable_directional_light;
             }
 
+            if ui.checkbox(&mut self.enable_shadow, "Enable Shadow").changed() {
+                if let Some(light) = logic_context.world.current_scene_mut().get_component_mut::<Light>(&self.directional_light_node_handle) {
+                    light.cast_shadow = self.enable_shadow;
+                }
+            }
+
             if ui.add(egui::Slider::new(&mut self.light_intensity, 0.0..=10.0).text("Intensity")).changed() {
                 if let Some(light) = logic_context.world.current_scene_mut().get_component_mut::<Light>(&self.directional_light_node_handle) {
                     light.intensity = self.light_intensity;
@@ -175,6 +182,7 @@ fn init(engine: &mut Engine) {
         directional_light_node_handle: _directional_light_node,
         enable_skybox: true,
         enable_directional_light: true,
+        enable_shadow: true,
         light_intensity: 3.0,
         light_rotation_x: -45.0f32,
         light_rotation_y: 0.0f32,
@@ -190,6 +198,7 @@ fn main() {
     let engine_options = EngineOptions {
         window_size: WindowSize::new(1000.0, 500.0),
         app_name: "lxy directional light demo",
+        ..Default::default()
     };
     let mut engine = Engine::new(engine_options);
     init(&mut engine);
@@ -116,6 +116,7 @@ pub fn main() {
     let options = EngineOptions {
         window_size: WindowSize::new(800.0, 500.0),
         app_name: "lxy gltf demo",
+        ..Default::default()
     };
     let mut engine = Engine::new(options);
     load_model(&mut engine);
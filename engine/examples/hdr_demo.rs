@@ -7,6 +7,7 @@ fn main() {
     let engine_options = EngineOptions {
         window_size: WindowSize::new(1000.0, 500.0),
         app_name: "lxy hdr demo",
+        ..Default::default()
     };
     let mut engine = Engine::new(engine_options);
     let world = &mut engine.world;
@@ -9,6 +9,7 @@ impl Game {
         let engine_options = EngineOptions {
             window_size: WindowSize::new(500.0, 500.0),
             app_name: "lxy primitives demo",
+            ..Default::default()
         };
         let engine = Engine::new(engine_options);
         Self { engine }
@@ -0,0 +1,67 @@
+use std::path::PathBuf;
+
+use imagic::prelude::*;
+
+/// Render a lit scene without window and save the last frame to `headless_demo.png`.
+///
+/// Pass `--hdr` to save a linear `headless_demo.hdr` instead, and `--fallback` to use the software adapter.
+fn main() {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    log::info!("headless demo.");
+    let args: Vec<String> = std::env::args().collect();
+    let is_hdr = args.iter().any(|arg| arg == "--hdr");
+    let force_fallback_adapter = args.iter().any(|arg| arg == "--fallback");
+
+    let (color_format, capture_path) = if is_hdr {
+        (TextureFormat::Rgba32Float, "headless_demo.hdr")
+    } else {
+        (TextureFormat::Rgba8UnormSrgb, "headless_demo.png")
+    };
+    let engine_options = EngineOptions {
+        window_size: WindowSize::new(512.0, 512.0),
+        app_name: "lxy headless demo",
+        headless: Some(HeadlessOptions {
+            frame_count: 3,
+            color_format,
+            capture_path: Some(PathBuf::from(capture_path)),
+            force_fallback_adapter,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let mut engine = Engine::new(engine_options);
+    {
+        let (_, shader_handle) = engine.shader_manager.get_builtin_pbr_shader();
+        let pbr_material = engine.material_manager.create_material(*shader_handle, &mut engine.shader_manager);
+        {
+            let pbr_material_mut_ref = engine.material_manager.get_material_mut_forcely(&pbr_material);
+            pbr_material_mut_ref.set_albedo_color(Color::new(0.8, 0.3, 0.2, 1.0));
+            pbr_material_mut_ref.set_vec4f(BuiltinShaderUniformNames::_METALLIC_ROUGHNESS_AO, Vec4::new(0.0, 0.5, 1.0, 1.0));
+        }
+
+        let sphere_mesh = engine.mesh_manager.add_mesh(UVSphere::default().into());
+        let scene = engine.world.current_scene_mut();
+        let sphere_node = scene.create_node("UVSphere");
+        scene.add_component(&sphere_node, MeshRenderer::new(sphere_mesh, vec![pbr_material]));
+        scene.add(sphere_node);
+
+        let light_node = scene.create_node("Directional Light");
+        scene.add_component(&light_node, Light::new_directional_light(Color::WHITE, 3.0, true));
+        scene.get_node_mut_forcely(&light_node).transform.set_rotation_euler(Vec3::new(
+            -45.0f32.to_radians(),
+            30.0f32.to_radians(),
+            0.0,
+        ));
+        scene.add(light_node);
+
+        let camera_node = scene.create_node("Main Camera");
+        let mut camera = Camera::default();
+        camera.clear_color = Some(Color::scalar(0.3));
+        scene.add_component(&camera_node, camera);
+        scene.get_node_mut_forcely(&camera_node).transform.set_position(Vec3::new(0.0, 0.0, 3.0));
+        scene.add(camera_node);
+    }
+
+    engine.run();
+    log::info!("headless demo finished.");
+}
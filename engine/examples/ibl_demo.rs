@@ -1,8 +1,10 @@
 use imagic::prelude::*;
 
+#[path = "common/scenes.rs"]
+mod scenes;
+
 struct Game {
     engine: Box<Engine>,
-    _sphere: NodeHandle,
 }
 
 struct GameBehavior {
@@ -58,78 +60,24 @@ impl Game {
         let engine_options = EngineOptions {
             window_size: WindowSize::new(800.0, 500.0),
             app_name: "lxy ibl demo",
+            ..Default::default()
         };
-        let mut engine = Engine::new(engine_options);
-        let material = Self::create_material(&mut engine);
-        let sphere = Self::create_sphere(&mut engine, material);
-        let game_behavior = GameBehavior { material: material, metallic_roughness_ao: Vec4::new(0.0, 1.0, 1.0, 1.0) };
-        engine.add_behavior(game_behavior);
-        Self { engine, _sphere: sphere }
-    }
-
-    fn create_material(engine: &mut Engine) -> MaterialHandle {
-        // let pbr_shader = engine.shader_manager.get_builtin_pbr_shader();
-        let pbr_material = engine.create_pbr_material();
-        {
-            let pbr_material_mut_ref = engine.material_manager.get_material_mut_forcely(&pbr_material);
-            pbr_material_mut_ref.set_vec4f(BuiltinShaderUniformNames::_ALBEDO_COLOR, Vec4::new(1.0, 1.0, 1.0, 1.0));
-            pbr_material_mut_ref.set_vec4f(BuiltinShaderUniformNames::_METALLIC_ROUGHNESS_AO, Vec4::new(0.0, 1.0, 1.0, 1.0));
-            // _reflection_cube_sampler
-            let reflection_cube_sampler = engine.texture_sampler_manager.create_sampler(
-                AddressMode::ClampToEdge,
-                AddressMode::ClampToEdge,
-                AddressMode::ClampToEdge,
-                FilterMode::Linear,
-                FilterMode::Linear,
-                FilterMode::Nearest,
-            );
-            pbr_material_mut_ref.set_sampler("_reflection_cube_sampler", reflection_cube_sampler);
-        }
-        pbr_material
-    }
-
-    fn create_sphere(engine: &mut Engine, material: MaterialHandle) -> NodeHandle {
-        let scene = engine.world.current_scene_mut();
-        let uv_sphere_node = scene.create_node("UVSphere");
-        {
-            let mesh: Mesh = UVSphere::default().into();
-            let mesh_handle = engine.mesh_manager.add_mesh(mesh);
-            let mesh_renderer = MeshRenderer::new(mesh_handle, vec![material]);
-            scene.add_component::<MeshRenderer>(&uv_sphere_node, mesh_renderer);
-        }
-        scene.add(uv_sphere_node.clone());
-        uv_sphere_node
+        let engine = Engine::new(engine_options);
+        Self { engine }
     }
 
     fn init(&mut self) {
-        self.create_camera();
-        let _skybox_node = SkyboxBuilder::create_skybox(
+        let ibl_scene = scenes::build_ibl_scene(
             &mut self.engine,
-            // vec![include_bytes!("./assets/images/hdri/resting_place_2_1k.hdr").to_vec()],
-            vec![include_bytes!("./assets/images/hdri/spruit_sunrise_2k.hdr").to_vec()],
-            // vec![include_bytes!("./assets/images/hdri/newport_loft.hdr").to_vec()],
-            TextureFormat::Rgba32Float,
-            SkyboxBuilderOptions::default(),
+            // include_bytes!("./assets/images/hdri/resting_place_2_1k.hdr"),
+            include_bytes!("./assets/images/hdri/spruit_sunrise_2k.hdr"),
+            // include_bytes!("./assets/images/hdri/newport_loft.hdr"),
         );
-    }
-
-    fn create_camera(&mut self) {
-        let current_scene = self.engine.world.current_scene_mut();
-        let camera_node_id = current_scene.create_node("Main Camera");
-        let mut camera = Camera::default();
-        camera.clear_color = Some(Color::scalar(0.3));
-        camera.fov = std::f32::consts::FRAC_PI_4;
-        current_scene.add_component(&camera_node_id, camera);
-        {
-            current_scene.get_node_mut_forcely(&camera_node_id)
-                .transform
-                .set_position(Vec3::new(0.0, 1.5, 1.5)); // front
-        }
-
-        current_scene.add(camera_node_id);
         let camera_controller =
-            CameraController::new(camera_node_id, CameraTarget::Position(Vec3::ZERO));
+            CameraController::new(ibl_scene.camera, CameraTarget::Position(Vec3::ZERO));
         self.engine.add_behavior(camera_controller);
+        let game_behavior = GameBehavior { material: ibl_scene.material, metallic_roughness_ao: Vec4::new(0.0, 1.0, 1.0, 1.0) };
+        self.engine.add_behavior(game_behavior);
     }
 
     pub fn run(&mut self) {
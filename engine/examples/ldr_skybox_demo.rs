@@ -45,6 +45,7 @@ impl Game {
         let engine_options = EngineOptions {
             window_size: WindowSize::new(800.0, 500.0),
             app_name: "lxy LDR skybox demo",
+            ..Default::default()
         };
         let mut engine = Engine::new(engine_options);
         let material = Self::create_material(&mut engine);
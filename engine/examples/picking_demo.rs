@@ -0,0 +1,123 @@
+use imagic::prelude::*;
+
+/// Click a cube to select it, or click the ground to move the marker there.
+struct PickingBehavior {
+    camera_node: NodeHandle,
+    marker_node: NodeHandle,
+    material: MaterialHandle,
+    selected_material: MaterialHandle,
+    selected_node: Option<NodeHandle>,
+}
+
+impl Behavior for PickingBehavior {
+    impl_as_any!();
+    fn on_update(&mut self, logic_context: &mut LogicContext) {
+        if !logic_context.input_manager.action_just_pressed("select") {
+            return;
+        }
+        let cursor_pos = logic_context.input_manager.mouse_data.cur_mouse_pos;
+        let scene = logic_context.world.current_scene_mut();
+        let camera_pos = scene.get_node_forcely(&self.camera_node).transform.position;
+        let Some(camera) = scene.get_component::<Camera>(&self.camera_node) else {
+            return;
+        };
+        let ray = camera.screen_point_to_ray(&camera_pos, cursor_pos);
+        // The marker is in the Gizmo layer, so it is never hit.
+        let layer_mask = Layer::Default | Layer::Custom1;
+        let Some(hit) = scene.raycast_meshes(&ray, 100.0, layer_mask, logic_context.mesh_manager).first().copied() else {
+            return;
+        };
+
+        if scene.get_node_forcely(&hit.node).layer == Layer::Custom1 {
+            log::info!("Place the marker at {}", hit.point);
+            scene.get_node_mut_forcely(&self.marker_node).transform.set_position(hit.point);
+            return;
+        }
+        if let Some(selected_node) = self.selected_node.take()
+            && let Some(mesh_renderer) = scene.get_component_mut::<MeshRenderer>(&selected_node)
+        {
+            mesh_renderer.materials[0] = self.material;
+        }
+        log::info!("Select {} at distance {}", scene.get_node_forcely(&hit.node).name, hit.distance);
+        if let Some(mesh_renderer) = scene.get_component_mut::<MeshRenderer>(&hit.node) {
+            mesh_renderer.materials[0] = self.selected_material;
+        }
+        self.selected_node = Some(hit.node);
+    }
+}
+
+fn create_unlit_material(engine: &mut Engine, color: Color) -> MaterialHandle {
+    let (_, shader) = engine.shader_manager.get_builtin_unlit_shader();
+    let material = engine.material_manager.create_material(*shader, &mut engine.shader_manager);
+    engine.material_manager.get_material_mut_forcely(&material).set_albedo_color(color);
+    material
+}
+
+fn create_mesh_node(engine: &mut Engine, name: &str, mesh: Mesh, material: MaterialHandle, layer: Layer) -> NodeHandle {
+    let mesh_handle = engine.mesh_manager.add_mesh(mesh);
+    let scene = engine.world.current_scene_mut();
+    let node = scene.create_node(name);
+    scene.get_node_mut_forcely(&node).layer = layer;
+    scene.add_component(&node, MeshRenderer::new(mesh_handle, vec![material]));
+    scene.add(node);
+    node
+}
+
+fn main() {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    log::info!("picking demo.");
+    let engine_options = EngineOptions {
+        window_size: WindowSize::new(800.0, 600.0),
+        app_name: "lxy picking demo",
+        ..Default::default()
+    };
+    let mut engine = Engine::new(engine_options);
+    engine.input_manager.action_map.bind_action("select", ButtonBinding::Mouse(MouseButtonCode::Left));
+
+    let material = create_unlit_material(&mut engine, Color::new(0.8, 0.8, 0.8, 1.0));
+    let selected_material = create_unlit_material(&mut engine, Color::new(1.0, 0.5, 0.0, 1.0));
+    let ground_material = create_unlit_material(&mut engine, Color::new(0.2, 0.3, 0.2, 1.0));
+    let marker_material = create_unlit_material(&mut engine, Color::new(0.2, 0.6, 1.0, 1.0));
+
+    let ground_node = create_mesh_node(&mut engine, "Ground", Quad::default().into(), ground_material, Layer::Custom1);
+    {
+        let transform = &mut engine.world.current_scene_mut().get_node_mut_forcely(&ground_node).transform;
+        transform.set_position_y(-0.5);
+        transform.set_rotation_euler(Vec3::new(-90.0f32.to_radians(), 0.0, 0.0));
+        transform.set_uniform_scale(12.0);
+    }
+    for x in -2..=2 {
+        for z in -2..=2 {
+            let cube_node = create_mesh_node(
+                &mut engine,
+                &format!("Cube ({}, {})", x, z),
+                Cuboid::default().into(),
+                material,
+                Layer::Default,
+            );
+            let transform = &mut engine.world.current_scene_mut().get_node_mut_forcely(&cube_node).transform;
+            transform.set_position(Vec3::new(x as f32 * 2.0, 0.0, z as f32 * 2.0));
+        }
+    }
+    let marker_node = create_mesh_node(&mut engine, "Marker", UVSphere::default().into(), marker_material, Layer::Gizmo);
+    engine.world.current_scene_mut().get_node_mut_forcely(&marker_node).transform.set_uniform_scale(0.3);
+
+    let scene = engine.world.current_scene_mut();
+    let camera_node = scene.create_node("Main Camera");
+    let mut camera = Camera::default();
+    camera.clear_color = Some(Color::scalar(0.3));
+    camera.visible_layers = Layer::Default | Layer::Custom1;
+    camera.visible_layers.insert(Layer::Gizmo);
+    scene.get_node_mut_forcely(&camera_node).transform.set_position(Vec3::new(0.0, 8.0, 10.0));
+    scene.add_component(&camera_node, camera);
+    scene.add(camera_node);
+
+    engine.add_behavior(PickingBehavior {
+        camera_node,
+        marker_node,
+        material,
+        selected_material,
+        selected_node: None,
+    });
+    engine.run();
+}
@@ -1,5 +1,8 @@
 use imagic::prelude::*;
 
+#[path = "common/scenes.rs"]
+mod scenes;
+
 struct Game {
     engine: Box<Engine>,
     // material: RR<Material>,
@@ -103,6 +106,7 @@ impl Game {
         let engine_options = EngineOptions {
             window_size: WindowSize::new(500.0, 500.0),
             app_name: "lxy primitives demo",
+            ..Default::default()
         };
         let engine = Engine::new(engine_options);
                 
@@ -150,31 +154,23 @@ impl Game {
     }
 
     fn init(&mut self) {
-        let _skybox_node = SkyboxBuilder::create_skybox(
-            &mut self.engine,
-            vec![include_bytes!("./assets/images/hdri/spruit_sunrise_2k.hdr").to_vec()],
-            TextureFormat::Rgba32Float,
-            SkyboxBuilderOptions::default(),
-        );
-
         let material_type = MaterialType::Pbr;
         let materials = Self::create_material(&mut self.engine);
 
         let cur_material_handle = materials[material_type as usize];
 
-        let _camera_node = self.create_camera();
-        let quad_node = self.create_quad(cur_material_handle);
-        let cuboid_node = self.create_cuboid(cur_material_handle);
-        let sphere_node = self.create_sphere(cur_material_handle);
-        let mut primitive_ids = Vec::new();
-        primitive_ids.push(quad_node);
-        primitive_ids.push(cuboid_node);
-        primitive_ids.push(sphere_node);
+        let primitives_scene = scenes::build_primitives_scene(
+            &mut self.engine,
+            include_bytes!("./assets/images/hdri/spruit_sunrise_2k.hdr"),
+            cur_material_handle,
+        );
+        let camera_controller =
+            CameraController::new(primitives_scene.camera, CameraTarget::Position(Vec3::ZERO));
+        self.engine.add_behavior(camera_controller);
 
-        
         self.engine.add_behavior(GameBehavior::new(
             materials,
-            primitive_ids,
+            primitives_scene.primitives,
             material_type,
         ));
 
@@ -184,74 +180,6 @@ impl Game {
         self.init();
         self.engine.run();
     }
-
-    fn create_camera(&mut self) -> NodeHandle {
-        let current_scene = self.engine.world.current_scene_mut();
-        let camera_node = current_scene.create_node("Main Camera");
-        let mut camera = Camera::default();
-        // let mut camera = Camera::new_orthogonal(-10.0, 10.0, -10.0, 10.0, 0.1, 100.0);
-        camera.clear_color = Some(Color::scalar(0.3));
-        current_scene.get_node_mut_forcely(&camera_node).transform
-            .set_position(Vec3::new(0.0, 8.0, 12.0)); // front
-        current_scene.add_component(&camera_node, camera);
-
-        current_scene.add(camera_node);
-        let camera_controller =
-            CameraController::new(camera_node, CameraTarget::Position(Vec3::ZERO));
-        self.engine.add_behavior(camera_controller);
-        camera_node
-    }
-
-    fn create_quad(&mut self, cur_material: MaterialHandle) -> NodeHandle {
-        let scene = self.engine.world.current_scene_mut();
-        let quad_node = scene.create_node("quad");
-        {
-            let transform = &mut scene.get_node_mut_forcely(&quad_node).transform;
-            transform.set_position_y(-0.5);
-            transform.set_rotation_euler(Vec3::new(
-                -90.0f32.to_radians(),
-                0.0,
-                0.0,
-            ));
-            transform.set_uniform_scale(5.0);
-            let mesh: Mesh = Quad::new(2.0, 1.0, 2, 1).into();
-            let mesh_handle = self.engine.mesh_manager.add_mesh(mesh);
-            let mesh_renderer = MeshRenderer::new(mesh_handle, vec![cur_material]);
-            scene.add_component(&quad_node, mesh_renderer);
-        }
-        scene.add(quad_node);
-        quad_node
-    }
-
-    fn create_cuboid(&mut self, cur_material: MaterialHandle) -> NodeHandle {
-        let scene = self.engine.world.current_scene_mut();
-        let cuboid_node =
-            scene.create_node("Cuboid");
-        {
-            let mesh: Mesh = Cuboid::default().into();
-            // let mesh: Mesh = Cuboid::new(2.0, 1.0, 1.0, 2, 1, 1).into();
-            let mesh_handle = self.engine.mesh_manager.add_mesh(mesh);
-            let mesh_renderer = MeshRenderer::new(mesh_handle, vec![cur_material]);
-            scene.add_component(&cuboid_node, mesh_renderer);
-        }
-        scene.add(cuboid_node);
-        cuboid_node
-    }
-
-    fn create_sphere(&mut self, cur_material: MaterialHandle) -> NodeHandle {
-        let scene = self.engine.world.current_scene_mut();
-        let uv_sphere_node =
-            scene.create_node("UVSphere");
-        {
-            scene.get_node_mut_forcely(&uv_sphere_node).transform.set_position_x(1.5);
-            let mesh: Mesh = UVSphere::default().into();
-            let mesh_handle = self.engine.mesh_manager.add_mesh(mesh);
-            let mesh_renderer = MeshRenderer::new(mesh_handle, vec![cur_material]);
-            scene.add_component(&uv_sphere_node, mesh_renderer);
-        }
-        scene.add(uv_sphere_node);
-        uv_sphere_node
-    }
 }
 
 fn main() {
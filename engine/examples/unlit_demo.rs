@@ -1,5 +1,8 @@
 use imagic::prelude::*;
 
+#[path = "common/scenes.rs"]
+mod scenes;
+
 struct MoveBehavior {
     node_id: NodeHandle,
     cur_pos: Vec3,
@@ -43,59 +46,11 @@ fn main() {
     let engine_options = EngineOptions {
         window_size: WindowSize::new(500.0, 500.0),
         app_name: "lxy unlit demo",
+        ..Default::default()
     };
     let mut engine = Engine::new(engine_options);
-    {
-        let color_texture_handle = engine.texture_sampler_manager.create_texture_from_image(
-            vec![include_bytes!("./assets/images/lena.png").to_vec()],
-            TextureDimension::D2,
-            TextureFormat::Rgba8UnormSrgb,
-            true,
-            // 没有填充 mipmaps 时, jpg 图片可能是黑色，显示了非 lod 0 的内容。
-            true,
-        );
-        let color_map_sampler = engine.texture_sampler_manager.create_sampler(
-            AddressMode::ClampToEdge,
-            AddressMode::ClampToEdge,
-            AddressMode::ClampToEdge,
-            FilterMode::Linear,
-            FilterMode::Linear,
-            FilterMode::Linear,
-        );
-
-        let (_, shader) = engine.shader_manager.get_builtin_unlit_shader();
-        let quad_node =engine.world.current_scene_mut().create_node("quad");
-        {
-            let mesh: Mesh = Quad::default().into();
-
-            let unlit_material = engine.material_manager.create_material(*shader, &mut engine.shader_manager);
-            {
-                let unlit_material_mut_ref = engine.material_manager.get_material_mut_forcely(&unlit_material);
-                unlit_material_mut_ref.set_albedo_color(Color::new(1.0, 1.0, 1.0, 1.0));
-                unlit_material_mut_ref.set_albedo_map(color_texture_handle);
-                unlit_material_mut_ref.set_albedo_map_sampler(color_map_sampler);
-            }
-            let mesh_handle = engine.mesh_manager.add_mesh(mesh);
-            let mesh_renderer = MeshRenderer::new(mesh_handle, vec![unlit_material]);
-            // quad_node_mut_ref.add_component(mesh_renderer);
-            engine.world.current_scene_mut().add_component(&quad_node, mesh_renderer);
-        }
-
-        let camera_node = engine.world.current_scene_mut().create_node("Main Camera");
-        let mut camera = Camera::default();
-        camera.clear_color = Some(Color::scalar(0.3));
-        {
-            engine.world.current_scene_mut().add_component(&camera_node, camera);
-            engine.world.current_scene_mut().get_node_mut_forcely(&camera_node)
-                .transform
-                .set_position(Vec3::new(0.0, 0.0, 2.5));
-        }
-
-        engine.add_behavior(MoveBehavior::new(quad_node));
-        let scene = engine.world.current_scene_mut();
-        scene.add(quad_node);
-        scene.add(camera_node);
-    }
+    let unlit_scene = scenes::build_unlit_scene(&mut engine, include_bytes!("./assets/images/lena.png"));
+    engine.add_behavior(MoveBehavior::new(unlit_scene.quad));
 
     engine.run();
 }
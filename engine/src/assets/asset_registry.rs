@@ -0,0 +1,134 @@
+use std::hash::Hash;
+
+use ahash::AHashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    assets::{MaterialHandle, MaterialTag, MeshHandle, MeshTag, TextureHandle, TextureTag},
+    types::Handle,
+};
+
+/// A stable reference to an asset, which is written to files such as scenes instead of runtime handles.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AssetRef {
+    /// The path of the asset file, e.g., `models/helmet.glb`.
+    Path(String),
+    /// A globally unique id assigned by the app or an asset pipeline.
+    Guid(String),
+}
+
+impl std::fmt::Display for AssetRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AssetRef::Path(path) => write!(f, "Path({})", path),
+            AssetRef::Guid(guid) => write!(f, "Guid({})", guid),
+        }
+    }
+}
+
+/// A two way map between asset references and runtime handles of one asset type.
+struct AssetRefMap<T> {
+    handles: AHashMap<AssetRef, Handle<T>>,
+    refs: AHashMap<Handle<T>, AssetRef>,
+}
+
+impl<T: Copy + Eq + Hash> AssetRefMap<T> {
+    fn new() -> Self {
+        Self {
+            handles: AHashMap::new(),
+            refs: AHashMap::new(),
+        }
+    }
+
+    fn register(&mut self, asset_ref: AssetRef, handle: Handle<T>) {
+        if let Some(old_handle) = self.handles.insert(asset_ref.clone(), handle) {
+            self.refs.remove(&old_handle);
+        }
+        if let Some(old_ref) = self.refs.insert(handle, asset_ref) {
+            self.handles.remove(&old_ref);
+        }
+    }
+
+    fn unregister(&mut self, asset_ref: &AssetRef) -> Option<Handle<T>> {
+        let handle = self.handles.remove(asset_ref)?;
+        self.refs.remove(&handle);
+        Some(handle)
+    }
+}
+
+/// Maps the runtime handles of meshes, materials and textures to stable [`AssetRef`]s.
+///
+/// Assets are created at runtime and identified by handles which change between runs. Register the assets which
+/// are referenced by saved scenes, so that the handles can be written as references and resolved back when loading.
+pub struct AssetRegistry {
+    meshes: AssetRefMap<MeshTag>,
+    materials: AssetRefMap<MaterialTag>,
+    textures: AssetRefMap<TextureTag>,
+}
+
+impl Default for AssetRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AssetRegistry {
+    pub fn new() -> Self {
+        Self {
+            meshes: AssetRefMap::new(),
+            materials: AssetRefMap::new(),
+            textures: AssetRefMap::new(),
+        }
+    }
+
+    /// Register a mesh. An old registration of the same reference or handle is replaced.
+    pub fn register_mesh(&mut self, asset_ref: AssetRef, handle: MeshHandle) {
+        self.meshes.register(asset_ref, handle);
+    }
+
+    /// Register a material. An old registration of the same reference or handle is replaced.
+    pub fn register_material(&mut self, asset_ref: AssetRef, handle: MaterialHandle) {
+        self.materials.register(asset_ref, handle);
+    }
+
+    /// Register a texture. An old registration of the same reference or handle is replaced.
+    pub fn register_texture(&mut self, asset_ref: AssetRef, handle: TextureHandle) {
+        self.textures.register(asset_ref, handle);
+    }
+
+    pub fn unregister_mesh(&mut self, asset_ref: &AssetRef) -> Option<MeshHandle> {
+        self.meshes.unregister(asset_ref)
+    }
+
+    pub fn unregister_material(&mut self, asset_ref: &AssetRef) -> Option<MaterialHandle> {
+        self.materials.unregister(asset_ref)
+    }
+
+    pub fn unregister_texture(&mut self, asset_ref: &AssetRef) -> Option<TextureHandle> {
+        self.textures.unregister(asset_ref)
+    }
+
+    pub fn get_mesh(&self, asset_ref: &AssetRef) -> Option<MeshHandle> {
+        self.meshes.handles.get(asset_ref).copied()
+    }
+
+    pub fn get_material(&self, asset_ref: &AssetRef) -> Option<MaterialHandle> {
+        self.materials.handles.get(asset_ref).copied()
+    }
+
+    pub fn get_texture(&self, asset_ref: &AssetRef) -> Option<TextureHandle> {
+        self.textures.handles.get(asset_ref).copied()
+    }
+
+    pub fn get_mesh_ref(&self, handle: &MeshHandle) -> Option<&AssetRef> {
+        self.meshes.refs.get(handle)
+    }
+
+    pub fn get_material_ref(&self, handle: &MaterialHandle) -> Option<&AssetRef> {
+        self.materials.refs.get(handle)
+    }
+
+    pub fn get_texture_ref(&self, handle: &TextureHandle) -> Option<&AssetRef> {
+        self.textures.refs.get(handle)
+    }
+}
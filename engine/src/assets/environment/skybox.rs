@@ -5,10 +5,10 @@ use crate::{
         meshes::{mesh::Mesh, primitives::cuboid::Cuboid}, sampler::{AddressMode, FilterMode},
         texture_view::TextureView
     },
-    components::camera::Camera, graphics::{
+    components::{camera::Camera, component::Component}, core::{NodeHandle, Scene}, graphics::{
         bind_group::BindGroupID, graphics_context::GraphicsContext, render_api::RenderAPI,
         render_states::CullMode,
-    }, impl_component, math::{Mat4, Vec3, Vec4, color::Color},
+    }, math::{Mat4, Vec3, Vec4, color::Color},
     prelude::render_pipeline::INVALID_PIPELINE_HASH, renderer::frame_data::ItemRenderData
 };
 
@@ -23,7 +23,18 @@ pub struct Skybox {
     pub sh: [Color; 9] 
 }
 
-impl_component!(Skybox);
+/// The scene renders with the last added skybox.
+impl Component for Skybox {
+    fn on_added(scene: &mut Scene, node: &NodeHandle) {
+        scene.cached_skybox_ = *node;
+    }
+
+    fn on_removed(scene: &mut Scene, node: &NodeHandle) {
+        if scene.cached_skybox_ == *node {
+            scene.cached_skybox_ = NodeHandle::INVALID;
+        }
+    }
+}
 
 impl Skybox {
     const DEFAULT_DEPTH_ATTACHMENT_FORMAT: TextureFormat = TextureFormat::Depth32Float;
@@ -1,4 +1,4 @@
-use gltf::mesh::Mode;
+use gltf::{material::AlphaMode, mesh::Mode};
 
 use crate::{
     assets::{
@@ -8,7 +8,7 @@ use crate::{
     },
     core::{LogicContext, NodeHandle},
     math::{Color, Vec2, Vec3, Vec4},
-    prelude::{MeshRenderer, PolygonMode},
+    prelude::{BlendMode, MeshRenderer, PolygonMode, RenderQueue},
 };
 
 /// A loader for GLTF models.
@@ -172,6 +172,13 @@ impl GLTFLoader {
                     Mode::Points => PolygonMode::Point,
                     Mode::Lines | Mode::LineLoop | Mode::LineStrip => PolygonMode::Line,
                 };
+                if gltf_material.alpha_mode() == AlphaMode::Blend {
+                    material.render_state.render_queue = RenderQueue::Transparent;
+                    material.render_state.blend_mode = BlendMode::Alpha;
+                    material.render_state.depth_write = false;
+                }
+                // render state changed, refresh the material hash.
+                material.mark_dirty();
 
                 if base_color_texture != TextureHandle::INVALID {
                     material.set_albedo_map(base_color_texture);
@@ -224,7 +231,8 @@ impl GLTFLoader {
 
         let mesh = Mesh::new(vertex_attributes, index_data, sub_meshes);
         let mesh_handle = logic_context.mesh_manager.add_mesh(mesh);
-        let mesh_render = MeshRenderer::new(mesh_handle, materials);
+        let mut mesh_render = MeshRenderer::new(mesh_handle, materials);
+        mesh_render.sync_bounds(logic_context.mesh_manager.get_mesh_forcely(&mesh_handle));
         logic_context.world.current_scene_mut().add_component::<MeshRenderer>(node, mesh_render);
     }
 
@@ -1,3 +1,5 @@
+use std::rc::Rc;
+
 use crate::core::{LogicContext, NodeHandle};
 
 /// Trait for loading models from various formats, for example, .gltf, .glb, .fbx.
@@ -15,23 +17,57 @@ pub trait ModelLoaderTrait {
 }
 
 /// A model loader that can load models from different formats.
-/// Currently, it supports GLTF format.
+/// It supports GLTF format by default, and loaders of other formats can be registered by [`ModelLoader::register_loader`].
 pub struct ModelLoader {
+    /// The lowercase extensions and their loaders. Later registered loaders take precedence.
+    loaders: Vec<(String, Rc<dyn ModelLoaderTrait>)>,
 }
 
 impl ModelLoaderTrait for ModelLoader {
     fn load(&self, logic_context: &mut LogicContext<'_>, path: &str) -> Result<NodeHandle, Box<dyn std::error::Error>> {
-        if path.ends_with(".gltf") || path.ends_with(".glb") {
-            let gltf_loader = crate::assets::loaders::gltf_loader::GLTFLoader::new();
-            return gltf_loader.load(logic_context, path);
-        }
-        Err("ModelLoaderTrait not implemented".to_string().into())
+        self.find_loader(path)?.load(logic_context, path)
+    }
+}
+
+impl Default for ModelLoader {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl ModelLoader {
     /// Create a new ModelLoader instance.
     pub fn new() -> Self {
-        ModelLoader {}
+        let mut model_loader = ModelLoader { loaders: Vec::new() };
+        model_loader.register_loader(&["gltf", "glb"], crate::assets::loaders::gltf_loader::GLTFLoader::new());
+        model_loader
     }
-}
\ No newline at end of file
+
+    /// Register a loader for model files with the extensions, e.g., `&["fbx"]`. It replaces the loader registered
+    /// before for the same extensions.
+    pub fn register_loader<T: ModelLoaderTrait + 'static>(&mut self, extensions: &[&str], loader: T) {
+        let loader: Rc<dyn ModelLoaderTrait> = Rc::new(loader);
+        for extension in extensions {
+            self.loaders.push((extension.trim_start_matches('.').to_lowercase(), loader.clone()));
+        }
+    }
+
+    /// Whether there is a loader for the extension of the path.
+    pub fn supports(&self, path: &str) -> bool {
+        self.find_loader(path).is_ok()
+    }
+
+    /// Find the loader by the extension of the path.
+    pub(crate) fn find_loader(&self, path: &str) -> Result<Rc<dyn ModelLoaderTrait>, Box<dyn std::error::Error>> {
+        let extension = std::path::Path::new(path)
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        self.loaders
+            .iter()
+            .rev()
+            .find(|(loader_extension, _)| *loader_extension == extension)
+            .map(|(_, loader)| loader.clone())
+            .ok_or_else(|| format!("No model loader is registered for {}", path).into())
+    }
+}
@@ -18,7 +18,7 @@ use crate::{
     }, graphics::{
         bind_group::BindGroupID,
         graphics_context::GraphicsContext,
-        render_states::RenderState, uniform::Uniforms,
+        render_states::{BlendMode, RenderState}, uniform::Uniforms,
     }, math::{IVec4, Mat3, Mat4, UVec4, Vec4, color::Color}, prelude::bind_group::INVALID_BINDGROUP_ID, types::HashID
 };
 
@@ -101,6 +101,23 @@ impl Material {
         }
     }
 
+    /// Enable [`BuiltinMaterialShaderFeatures::FEATURE_FLAG_ALPHA_BLEND`] if the render state blends.
+    fn sync_alpha_blend_feature(&mut self) {
+        if self.render_state.blend_mode == BlendMode::Opaque {
+            self.disable_feature(BuiltinMaterialShaderFeatures::FEATURE_FLAG_ALPHA_BLEND);
+        } else {
+            self.enable_feature(BuiltinMaterialShaderFeatures::FEATURE_FLAG_ALPHA_BLEND);
+        }
+    }
+
+    /// Create a copy of the material which has its own GPU resources, so that it can be changed independently,
+    /// e.g., to override the properties of one prefab instance.
+    pub fn duplicate(&self) -> Self {
+        let mut material = self.clone();
+        material.uniforms = self.uniforms.clone_without_gpu_resources();
+        material
+    }
+
     pub fn set_float(&mut self, property_name: &str, value: f32) {
         self.uniforms.set_float(property_name, value);
     }
@@ -171,6 +188,7 @@ impl Material {
     pub(crate) fn on_update(&mut self, graphics_context: &mut GraphicsContext, texture_sampler_manager: &mut TextureSamplerManager, shader_manager: &mut ShaderManager) {
         if self.is_dirty {
             self.refresh_hash(shader_manager);
+            self.sync_alpha_blend_feature();
             self.is_dirty = false;
         }
         if !self.is_inited {
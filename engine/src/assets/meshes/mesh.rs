@@ -3,7 +3,7 @@ use crate::{
         asset::IAsset,
         meshes::{sub_mesh::SubMesh, vertex_attribute::VertexAttributes}, vertex_index::IndexData,
     },
-    graphics::{buffer_view::BufferView, graphics_context::GraphicsContext}, types::Handle,
+    graphics::{buffer_view::BufferView, graphics_context::GraphicsContext}, math::{Aabb, BoundingSphere, Ray}, types::Handle,
 };
 
 /// The tag of the mesh used to define MeshHandle.
@@ -28,6 +28,10 @@ pub struct Mesh {
 
     /// When is_dirty is true, mesh data shoud be upload to GPU.
     pub(crate) is_dirty: bool,
+    /// The local bounds of the positions, which are None if there is no vertex.
+    bounds: Option<(Aabb, BoundingSphere)>,
+    /// Increased whenever the bounds are recomputed, so that mesh renderers only copy changed bounds.
+    bounds_version: u64,
 }
 
 impl IAsset for Mesh {}
@@ -44,14 +48,72 @@ impl Mesh {
     /// * `Mesh` - The new mesh.
     pub fn new(mut vertex_attributes: VertexAttributes, index_data: IndexData, sub_meshes: Vec<SubMesh>) -> Mesh {
         vertex_attributes.compute_vertex_attributes();
-        Self {
+        let mut mesh = Self {
             vertex_buffer: BufferView::INVALID,
             vertex_attributes,
             index_data,
             index_buffer: BufferView::INVALID,
             sub_meshes,
             is_dirty: true,
+            bounds: None,
+            bounds_version: 0,
+        };
+        mesh.compute_bounds();
+        mesh
+    }
+
+    /// Mark the mesh as modified after `vertex_attributes` or `index_data` are changed in place, so that it is
+    /// uploaded to GPU again and its bounds are recomputed.
+    pub fn mark_modified(&mut self) {
+        self.is_dirty = true;
+        self.compute_bounds();
+    }
+
+    fn compute_bounds(&mut self) {
+        let positions = &self.vertex_attributes.position;
+        self.bounds = Aabb::from_points(positions).zip(BoundingSphere::from_points(positions));
+        self.bounds_version += 1;
+    }
+
+    /// The version of the bounds, see [`crate::prelude::MeshRenderer::needs_bounds_sync`].
+    pub(crate) fn bounds_version(&self) -> u64 {
+        self.bounds_version
+    }
+
+    /// The axis-aligned bounding box in local space, or None if the mesh has no vertex.
+    pub fn bounds(&self) -> Option<Aabb> {
+        self.bounds.map(|(aabb, _)| aabb)
+    }
+
+    /// The bounding sphere in local space, or None if the mesh has no vertex.
+    pub fn bounding_sphere(&self) -> Option<BoundingSphere> {
+        self.bounds.map(|(_, bounding_sphere)| bounding_sphere)
+    }
+
+    /// The distance to the nearest triangle hit by a ray in the local space of the mesh, or None if no triangle is
+    /// hit. Both sides of triangles are hit, and the index data are treated as triangle lists.
+    pub fn raycast(&self, ray: &Ray) -> Option<f32> {
+        self.bounds().and_then(|aabb| ray.intersect_aabb(&aabb))?;
+        let positions = &self.vertex_attributes.position;
+        let index = |i: u32| match &self.index_data {
+            IndexData::U16(indices) => indices.get(i as usize).map(|index| *index as u32),
+            IndexData::U32(indices) => indices.get(i as usize).copied(),
+        };
+        let mut nearest: Option<f32> = None;
+        for sub_mesh in &self.sub_meshes {
+            for triangle_start in (sub_mesh.index_start..sub_mesh.index_start + sub_mesh.index_count).step_by(3) {
+                let vertex = |offset: u32| {
+                    index(triangle_start + offset).and_then(|index| positions.get((sub_mesh.base_vertex + index) as usize))
+                };
+                if let (Some(a), Some(b), Some(c)) = (vertex(0), vertex(1), vertex(2))
+                    && let Some(distance) = ray.intersect_triangle(*a, *b, *c)
+                    && nearest.is_none_or(|nearest| distance < nearest)
+                {
+                    nearest = Some(distance);
+                }
+            }
         }
+        nearest
     }
 
     /// Upload mesh data to GPU when it is dirty.
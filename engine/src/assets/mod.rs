@@ -1,4 +1,5 @@
 pub mod asset;
+pub mod asset_registry;
 pub mod materials;
 pub mod meshes;
 pub mod shaders;
@@ -7,6 +8,7 @@ pub mod textures;
 pub mod loaders;
 
 pub use asset::*;
+pub use asset_registry::*;
 pub use materials::*;
 pub use meshes::*;
 pub use shaders::*;
@@ -331,6 +331,16 @@ fn construct_image_shader_property(
             );
             shader_property
         }
+        ImageClass::Depth{multi} => {
+            // e.g., texture_depth_2d used by shadow maps.
+            ShaderProperty::new(
+                name,
+                resouce_binding.into(),
+                ShaderPropertyType::Image(view_dimension, wgpu::TextureSampleType::Depth, *multi),
+                stages,
+                space,
+            )
+        }
         _ => {unimplemented!()}
     }
 }
@@ -113,14 +113,19 @@ impl ShaderPropertyPacket {
                     };
                     bind_group_layout_entries.push(bind_group_layout_entry);
                 }
-                ShaderPropertyType::Sampler(_comparison) => {
+                ShaderPropertyType::Sampler(comparison) => {
+                    // This should match the filterable field of the
+                    // corresponding Texture entry.
+                    let sampler_binding_type = if *comparison {
+                        wgpu::SamplerBindingType::Comparison
+                    } else {
+                        wgpu::SamplerBindingType::Filtering
+                    };
                     let bind_group_layout_entry = wgpu::BindGroupLayoutEntry {
                         // sampler
                         binding,
                         visibility,
-                        // This should match the filterable field of the
-                        // corresponding Texture entry.
-                        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                        ty: wgpu::BindingType::Sampler(sampler_binding_type),
                         count: None,
                     };
                     bind_group_layout_entries.push(bind_group_layout_entry);
@@ -156,6 +161,7 @@ pub(crate) struct BuilinUniformFlags {
     pub(crate) has_normal_matrix: bool,
     pub(crate) has_m_v_p_matrices: bool,
     pub(crate) has_m_v_p_n_matrices: bool,
+    pub(crate) has_layer_mask: bool,
     pub(crate) has_camera_position: bool,
     pub(crate) has_time: bool,
     pub(crate) has_sh: bool,
@@ -164,6 +170,7 @@ pub(crate) struct BuilinUniformFlags {
     pub(crate) has_reflection_cube_sampler: bool,
     pub(crate) has_brdf_lut: bool,
     pub(crate) has_lights: bool,
+    pub(crate) has_shadows: bool,
 }
 
 impl BuilinUniformFlags {
@@ -179,6 +186,7 @@ impl BuilinUniformFlags {
             has_normal_matrix: false,
             has_m_v_p_matrices: false,
             has_m_v_p_n_matrices: false,
+            has_layer_mask: false,
             has_camera_position: false,
             has_time: false,
             has_sh: false,
@@ -187,6 +195,7 @@ impl BuilinUniformFlags {
             has_reflection_cube_sampler: false,
             has_brdf_lut: false,
             has_lights: false,
+            has_shadows: false,
         }
     }
 
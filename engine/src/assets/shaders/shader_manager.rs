@@ -1,3 +1,5 @@
+use ahash::AHashMap;
+
 use crate::{
     assets::{ShaderHandle, shaders::shader::Shader}, core::arena::Arena
 };
@@ -9,6 +11,7 @@ pub(crate) struct BuiltinShaderHandles {
     pub blit: ShaderHandle,
     pub skybox: ShaderHandle,
     pub equirect_to_cube: ShaderHandle,
+    pub shadow_caster: ShaderHandle,
 }
 
 impl BuiltinShaderHandles {
@@ -19,6 +22,7 @@ impl BuiltinShaderHandles {
             blit: ShaderHandle::INVALID,
             skybox: ShaderHandle::INVALID,
             equirect_to_cube: ShaderHandle::INVALID,
+            shadow_caster: ShaderHandle::INVALID,
         }
     }
 }
@@ -27,6 +31,8 @@ impl BuiltinShaderHandles {
 pub struct ShaderManager {
     pub(crate) shaders: Arena<Shader>,
     pub(crate) builtin_shader_handles: BuiltinShaderHandles,
+    /// The shaders registered by name, e.g., by plugins.
+    registered_shaders: AHashMap<String, ShaderHandle>,
 }
 
 impl ShaderManager {
@@ -36,6 +42,7 @@ impl ShaderManager {
         ShaderManager {
             shaders: Arena::new(),
             builtin_shader_handles: BuiltinShaderHandles::new(),
+            registered_shaders: AHashMap::new(),
         }
     }
 
@@ -114,6 +121,28 @@ impl ShaderManager {
         self.shaders.remove(handle).is_some()
     }
 
+    /// Register a shader by name, so that it can be shared like the builtin shaders, e.g., a shader provided by a plugin.
+    /// The shader registered before with the same name is replaced but not destroyed.
+    /// # Arguments
+    /// 
+    /// * `name` - The unique name of the shader.
+    /// * `source` - The shader source code.
+    /// # Returns
+    /// 
+    /// * `ShaderHandle` - The shader handle.
+    pub fn register_shader(&mut self, name: &str, source: &str) -> ShaderHandle {
+        let handle = self.create_shader(source, name.to_owned());
+        if self.registered_shaders.insert(name.to_owned(), handle).is_some() {
+            log::warn!("Shader {} has been registered, it is replaced.", name);
+        }
+        handle
+    }
+
+    /// Get a shader registered by [`ShaderManager::register_shader`].
+    pub fn get_registered_shader(&self, name: &str) -> Option<ShaderHandle> {
+        self.registered_shaders.get(name).copied()
+    }
+
     /// Get the builtin PBR shader.
     /// # Returns
     /// 
@@ -174,4 +203,16 @@ impl ShaderManager {
         }
         (self.shaders.get_forcely(&self.builtin_shader_handles.equirect_to_cube), &self.builtin_shader_handles.equirect_to_cube)
     }
+
+    /// Get the builtin shadow caster shader, which renders meshes into the shadow atlas.
+    /// # Returns
+    /// 
+    /// * `&Shader` - The shadow caster shader.
+    pub(crate) fn get_builtin_shadow_caster_shader(&mut self) -> (&Shader, &ShaderHandle) {
+        if self.builtin_shader_handles.shadow_caster == ShaderHandle::INVALID {
+            let shadow_caster_shader = Shader::new(include_str!("wgsl/shadow_caster.wgsl"), "shadow_caster".into());
+            self.builtin_shader_handles.shadow_caster = self.shaders.add(shadow_caster_shader);
+        }
+        (self.shaders.get_forcely(&self.builtin_shader_handles.shadow_caster), &self.builtin_shader_handles.shadow_caster)
+    }
 }
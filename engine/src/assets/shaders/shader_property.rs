@@ -103,6 +103,9 @@ impl BuiltinShaderUniformNames {
     pub const _M_V_P_MATRICES: &'static str = "_m_v_p_matrices";
     /// The struct uniform name of transform matrices, which contains model, view, projection, normal matrices.
     pub const _M_V_P_N_MATRICES: &'static str = "_m_v_p_n_matrices";
+    /// The uniform name of the layer bit of the object, which is a uvec4 whose x is the bit of the layer of the node.
+    /// It is tested against the culling masks of lights.
+    pub const _LAYER_MASK: &'static str = "_layer_mask";
     // end per object uniforms}}
 
     // {{begin per camera uniforms
@@ -133,6 +136,12 @@ impl BuiltinShaderUniformNames {
     pub const _SH: &'static str = "_sh";
     /// The uniform name of the lighting infos.
     pub const _LIGHTING_INFOS: &'static str = "_lighting_infos";
+    /// The uniform name of the shadow infos, which contains view projection matrices and atlas rects of all shadow views.
+    pub const _SHADOW_INFOS: &'static str = "_shadow_infos";
+    /// The uniform name of the shadow atlas, which is a depth texture containing all shadow maps.
+    pub const _SHADOW_ATLAS: &'static str = "_shadow_atlas";
+    /// The uniform name of the comparison sampler of the shadow atlas.
+    pub const _SHADOW_SAMPLER: &'static str = "_shadow_sampler";
     // end per scene uniforms}}
 
     /// The uniform name of the albedo color.
@@ -191,6 +200,10 @@ impl BuiltinShaderUniformNames {
                 builtin_uniform_flags.has_m_v_p_n_matrices = true;
                 true
             },
+            Self::_LAYER_MASK => {
+                builtin_uniform_flags.has_layer_mask = true;
+                true
+            }
             _ => false,
         }
     }
@@ -253,6 +266,10 @@ impl BuiltinShaderUniformNames {
                 builtin_uniform_flags.has_lights = true;
                 true
             }
+            Self::_SHADOW_INFOS | Self::_SHADOW_ATLAS | Self::_SHADOW_SAMPLER => {
+                builtin_uniform_flags.has_shadows = true;
+                true
+            }
             _ => false,
         }
     }
@@ -270,6 +287,9 @@ impl BuiltinMaterialShaderFeatures {
     pub const FEATURE_FLAG_METALLIC_ROUGHNESS_MAP: u32 = 2;
     pub const FEATURE_FLAG_AO_MAP: u32 = 3;
     pub const FEATURE_FLAG_EMISSIVE_MAP: u32 = 4;
+    /// The feature index which is enabled when [`crate::prelude::RenderState::blend_mode`] is not opaque. Shaders only
+    /// output the surface alpha when it is enabled, otherwise they output 1.0.
+    pub const FEATURE_FLAG_ALPHA_BLEND: u32 = 5;
 }
 
 /// The global features supported by builtin shaders.
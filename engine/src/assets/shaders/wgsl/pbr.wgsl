@@ -14,6 +14,9 @@ struct VSOutput {
 
 @group(0) @binding(0)
 var<uniform> _model_matrix: mat4x4<f32>;
+// x is the bit of the layer of the object.
+@group(0) @binding(1)
+var<uniform> _layer_mask: vec4<u32>;
 
 @group(1) @binding(0)
 var<uniform> _vp_matrix: mat4x4<f32>;
@@ -35,6 +38,8 @@ fn vs_main(
 struct LightData {
     /// flags.x is light type, 0: directional, 1: point, 2: spot, 3: area.
     flags: vec4<u32>,
+    // culling.x is the culling mask of the light.
+    culling: vec4<u32>,
     // color.w is range or max distance for spot or point light.
     color: vec4f,
     // For spot light: direction.w is inner cone cosin.
@@ -92,6 +97,26 @@ var<uniform> _global_features: vec4<u32>;
 @group(3) @binding(5)
 var<storage, read> _lighting_infos: LightsInfo;
 
+struct ShadowViewData {
+    view_projection: mat4x4f,
+    // xy: uv offset of the tile in the shadow atlas, zw: uv scale of the tile.
+    atlas_rect: vec4f,
+    // x: depth bias, y: normal offset, z: texel size of the shadow atlas in uv,
+    // w: 1.0 if the normal offset is scaled by the distance to the light.
+    params: vec4f,
+}
+
+struct ShadowsInfo {
+    views: array<ShadowViewData, 16>,
+}
+
+@group(3) @binding(6)
+var<uniform> _shadow_infos: ShadowsInfo;
+@group(3) @binding(7)
+var _shadow_atlas: texture_depth_2d;
+@group(3) @binding(8)
+var _shadow_sampler: sampler_comparison;
+
 const PI = 3.1415926;
 
 struct SurfaceProps {
@@ -127,6 +152,7 @@ const FEATURE_FLAG_NORMAL_MAP: u32 = 1u << 1u;
 const FEATURE_FLAG_METALLIC_ROUGHNESS_MAP: u32 = 1u << 2u;
 const FEATURE_FLAG_AO_MAP: u32 = 1u << 3u;
 const FEATURE_FLAG_EMISSIVE_MAP: u32 = 1u << 4u;
+const FEATURE_FLAG_ALPHA_BLEND: u32 = 1u << 5u;
 
 // Global features
 const FEATURE_FLAG_IBL: u32 = 1u << 0u;
@@ -151,6 +177,10 @@ fn is_emissive_map_enabled() -> bool {
     return (_material_features.x & FEATURE_FLAG_EMISSIVE_MAP) != 0u;
 }
 
+fn is_alpha_blend_enabled() -> bool {
+    return (_material_features.x & FEATURE_FLAG_ALPHA_BLEND) != 0u;
+}
+
 fn is_ibl_enabled() -> bool {
     return (_global_features.x & FEATURE_FLAG_IBL) != 0u;
 }
@@ -276,14 +306,92 @@ fn ambient_lighting(surface_props: SurfaceProps, camera_props: CameraProps) -> v
     return ambient;
 }
 
+// Returns the visibility in [0, 1] of world_pos in the shadow view, or -1.0 if world_pos is outside of the view.
+fn sample_shadow_view(view_index: u32, world_pos: vec3f, world_normal: vec3f, light_pos: vec3f, pcf_radius: u32) -> f32 {
+    let shadow_view = _shadow_infos.views[view_index];
+    var normal_offset = shadow_view.params.y;
+    if shadow_view.params.w > 0.5 {
+        normal_offset *= length(light_pos - world_pos);
+    }
+    let offset_pos = world_pos + world_normal * normal_offset;
+    let clip_pos = shadow_view.view_projection * vec4f(offset_pos, 1.0);
+    if clip_pos.w <= 0.0 {
+        return -1.0;
+    }
+    let ndc = clip_pos.xyz / clip_pos.w;
+    let local_uv = vec2f(ndc.x * 0.5 + 0.5, 0.5 - ndc.y * 0.5);
+    if any(local_uv < vec2f(0.0)) || any(local_uv > vec2f(1.0)) || ndc.z < 0.0 || ndc.z > 1.0 {
+        return -1.0;
+    }
+    let depth = ndc.z - shadow_view.params.x;
+    let texel_size = shadow_view.params.z;
+    // keep the pcf taps inside of the tile.
+    let min_uv = shadow_view.atlas_rect.xy + vec2f(texel_size * 0.5);
+    let max_uv = shadow_view.atlas_rect.xy + shadow_view.atlas_rect.zw - vec2f(texel_size * 0.5);
+    let uv = shadow_view.atlas_rect.xy + local_uv * shadow_view.atlas_rect.zw;
+    let radius = i32(pcf_radius);
+    var visibility = 0.0;
+    var sample_count = 0.0;
+    for (var y = -radius; y <= radius; y = y + 1) {
+        for (var x = -radius; x <= radius; x = x + 1) {
+            let sample_uv = clamp(uv + vec2f(f32(x), f32(y)) * texel_size, min_uv, max_uv);
+            visibility += textureSampleCompareLevel(_shadow_atlas, _shadow_sampler, sample_uv, depth);
+            sample_count += 1.0;
+        }
+    }
+    return visibility / sample_count;
+}
+
+// Returns the shadow factor of the light, 1.0 means not in shadow.
+// light_data.flags.y is the count of shadow views, flags.z is the index of the first shadow view, flags.w is the pcf radius.
+fn shadow_factor(light_data: LightData, world_pos: vec3f, world_normal: vec3f) -> f32 {
+    let view_count = light_data.flags.y;
+    if view_count == 0u {
+        return 1.0;
+    }
+    let first_view = light_data.flags.z;
+    let pcf_radius = light_data.flags.w;
+    let light_pos = light_data.position.xyz;
+    if light_data.flags.x == 0u {
+        // directional light: use the first cascade which contains world_pos.
+        for (var i = 0u; i < view_count; i = i + 1u) {
+            let visibility = sample_shadow_view(first_view + i, world_pos, world_normal, light_pos, pcf_radius);
+            if visibility >= 0.0 {
+                return visibility;
+            }
+        }
+        return 1.0;
+    } else if light_data.flags.x == 1u {
+        // point light: six faces in the order of +X, -X, +Y, -Y, +Z, -Z.
+        let to_pos = world_pos - light_pos;
+        let abs_to_pos = abs(to_pos);
+        var face = 0u;
+        if abs_to_pos.x >= abs_to_pos.y && abs_to_pos.x >= abs_to_pos.z {
+            face = select(1u, 0u, to_pos.x > 0.0);
+        } else if abs_to_pos.y >= abs_to_pos.z {
+            face = select(3u, 2u, to_pos.y > 0.0);
+        } else {
+            face = select(5u, 4u, to_pos.z > 0.0);
+        }
+        let visibility = sample_shadow_view(first_view + face, world_pos, world_normal, light_pos, pcf_radius);
+        return select(1.0, visibility, visibility >= 0.0);
+    }
+    let visibility = sample_shadow_view(first_view, world_pos, world_normal, light_pos, pcf_radius);
+    return select(1.0, visibility, visibility >= 0.0);
+}
+
 fn lighting(surface_props: SurfaceProps, camera_props: CameraProps, surface_emissive: vec3f) -> vec3f {
     var lo = vec3f(0.0);
     for (var i = 0u; i < _lighting_infos.light_count.x; i = i + 1u) {
         let cur_light_data = _lighting_infos.lights_info[i];
+        if (cur_light_data.culling.x & _layer_mask.x) == 0u {
+            continue;
+        }
         if cur_light_data.flags.x == 0u {
             // directional light
             let light_dir = cur_light_data.direction.xyz;
-            let lighting_props = LightingProps(light_dir, cur_light_data.color.rgb);
+            let shadow = shadow_factor(cur_light_data, surface_props.world_pos, surface_props.world_normal);
+            let lighting_props = LightingProps(light_dir, cur_light_data.color.rgb * shadow);
             lo += brdf(lighting_props, surface_props, camera_props);
         } else if cur_light_data.flags.x == 1u {
             // point light
@@ -297,7 +405,8 @@ fn lighting(surface_props: SurfaceProps, camera_props: CameraProps, surface_emis
             let light_dir = normalize(to_light);
             let fade = saturate(1.0 - distance / max_distance);
             let attenuation = fade * fade / max(distance * distance, 0.0001);
-            let radiance = cur_light_data.color.rgb * attenuation;
+            let shadow = shadow_factor(cur_light_data, surface_props.world_pos, surface_props.world_normal);
+            let radiance = cur_light_data.color.rgb * attenuation * shadow;
             let lighting_props = LightingProps(light_dir, radiance);
             lo += brdf(lighting_props, surface_props, camera_props);
         } else if cur_light_data.flags.x == 2u {
@@ -319,7 +428,8 @@ fn lighting(surface_props: SurfaceProps, camera_props: CameraProps, surface_emis
             let fade = saturate(1.0 - distance / max_distance);
             let spotlight_attenuation = spotlight_effect * fade;
             let attenuation = spotlight_attenuation * fade * fade / max(distance * distance, 0.0001);
-            let radiance = cur_light_data.color.rgb * attenuation;
+            let shadow = shadow_factor(cur_light_data, surface_props.world_pos, surface_props.world_normal);
+            let radiance = cur_light_data.color.rgb * attenuation * shadow;
             let lighting_props = LightingProps(light_dir, radiance);
             lo += brdf(lighting_props, surface_props, camera_props);
         } else if cur_light_data.flags.x == 3u {
@@ -364,10 +474,12 @@ fn fs_main(fs_in: FSIn) -> @location(0) vec4f {
     }
 
     var surface_albedo = _albedo_color.rgb;
+    var surface_alpha = _albedo_color.a;
     if is_albedo_map_enabled() {
         // Note: albedo texture has format of Rgba8UnormSrgb, which will convert sRGB color to linear space automatically.
         let albedo_texl = textureSample(_albedo_map, _albedo_map_sampler, fs_in.uv0);
         surface_albedo *= albedo_texl.rgb;
+        surface_alpha *= albedo_texl.a;
     }
 
     var surface_emissive = _emissive_color.rgb;
@@ -400,6 +512,7 @@ fn fs_main(fs_in: FSIn) -> @location(0) vec4f {
     // gamma correction
     // color = pow(color, vec3f(1.0/2.2));
 
-    let frag_color = vec4f(color, 1.0);
+    // alpha is only output by materials whose render state enables blending, opaque materials are fully opaque.
+    let frag_color = vec4f(color, select(1.0, surface_alpha, is_alpha_blend_enabled()));
     return frag_color;
 }
\ No newline at end of file
@@ -0,0 +1,16 @@
+// Depth only shader which renders shadow casters into the shadow atlas.
+struct VSInput {
+    @location(0) position: vec3f,
+}
+
+@group(0) @binding(0)
+var<uniform> _model_matrix: mat4x4<f32>;
+
+// The view projection matrix of the current shadow view.
+@group(1) @binding(0)
+var<uniform> _vp_matrix: mat4x4<f32>;
+
+@vertex
+fn vs_main(vs_in: VSInput) -> @builtin(position) vec4f {
+    return _vp_matrix * _model_matrix * vec4f(vs_in.position, 1.0);
+}
@@ -56,16 +56,24 @@ var _albedo_map_sampler: sampler;
 var<uniform> _material_features: vec4<u32>;
 
 const FEATURE_FLAG_ALBEDO_MAP: u32 = 1u;
+const FEATURE_FLAG_ALPHA_BLEND: u32 = 1u << 5u;
 
 fn is_albedo_map_enabled() -> bool {
     return (_material_features.x & FEATURE_FLAG_ALBEDO_MAP) != 0u;
 }
 
+fn is_alpha_blend_enabled() -> bool {
+    return (_material_features.x & FEATURE_FLAG_ALPHA_BLEND) != 0u;
+}
+
 @fragment
 fn fs_main(fs_in: FSIn) -> @location(0) vec4f {
     var frag_color = _albedo_color;
     if (is_albedo_map_enabled()) {
         frag_color *= textureSample(_albedo_map, _albedo_map_sampler, fs_in.uv0);
     }
+    if (!is_alpha_blend_enabled()) {
+        frag_color.a = 1.0;
+    }
     return frag_color;
 }
\ No newline at end of file
@@ -8,6 +8,7 @@ pub type SamplerHandle = crate::types::Handle<SamplerTag>;
 
 pub type AddressMode = wgpu::AddressMode;
 pub type FilterMode = wgpu::FilterMode;
+pub type CompareFunction = wgpu::CompareFunction;
 
 pub struct Sampler {
     pub(crate) gpu_sampler: Option<wgpu::Sampler>,
@@ -17,6 +18,8 @@ pub struct Sampler {
     pub mag_filter: FilterMode,
     pub min_filter: FilterMode,
     pub mipmap_filter: FilterMode,
+    /// The comparison function of a comparison sampler, e.g., the sampler used to sample shadow maps.
+    pub compare: Option<CompareFunction>,
     pub handle: SamplerHandle,
 }
 
@@ -28,6 +31,7 @@ impl Sampler {
         mag_filter: FilterMode,
         min_filter: FilterMode,
         mipmap_filter: FilterMode,
+        compare: Option<CompareFunction>,
     ) -> SamplerHandle {
         let mut hasher = AHasher::default();
         address_mode_u.hash(&mut hasher);
@@ -36,6 +40,7 @@ impl Sampler {
         mag_filter.hash(&mut hasher);
         min_filter.hash(&mut hasher);
         mipmap_filter.hash(&mut hasher);
+        compare.hash(&mut hasher);
         let id = hasher.finish();
         SamplerHandle::new(id)
     }
@@ -49,7 +54,7 @@ impl Sampler {
         min_filter: FilterMode,
         mipmap_filter: FilterMode,
     ) -> Self {
-        let handle = Self::compute_sampler_handle(address_mode_u, address_mode_v, address_mode_w, mag_filter, min_filter, mipmap_filter);
+        let handle = Self::compute_sampler_handle(address_mode_u, address_mode_v, address_mode_w, mag_filter, min_filter, mipmap_filter, None);
         Self {
             gpu_sampler: None,
             address_mode_u,
@@ -58,10 +63,12 @@ impl Sampler {
             mag_filter,
             min_filter,
             mipmap_filter,
+            compare: None,
             handle
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new_with_handle(
         address_mode_u: AddressMode,
         address_mode_v: AddressMode,
@@ -69,6 +76,7 @@ impl Sampler {
         mag_filter: FilterMode,
         min_filter: FilterMode,
         mipmap_filter: FilterMode,
+        compare: Option<CompareFunction>,
         handle: SamplerHandle,
     ) -> Self {
         Self {
@@ -79,6 +87,7 @@ impl Sampler {
             mag_filter,
             min_filter,
             mipmap_filter,
+            compare,
             handle
         }
     }
@@ -96,10 +105,31 @@ impl Sampler {
                 FilterMode::Linear,
                 FilterMode::Linear,
                 FilterMode::Linear,
+                None,
             );
             unsafe { _SAMPLER_HANDLE = handle };
         }
 
         unsafe { _SAMPLER_HANDLE }
     }
+
+    /// The comparison sampler used to sample shadow maps, which is linear filter, address mode of clamp to edge and compare function of less equal.
+    pub fn shadow_sampler() -> SamplerHandle {
+        static mut _SHADOW_SAMPLER_HANDLE: SamplerHandle = SamplerHandle::INVALID;
+        if unsafe { _SHADOW_SAMPLER_HANDLE } == SamplerHandle::INVALID {
+            // Note: here we just compute its handle. The init function of [`TextureSamplerManager`] will really create this sampler.
+            let handle = Self::compute_sampler_handle(
+                AddressMode::ClampToEdge,
+                AddressMode::ClampToEdge,
+                AddressMode::ClampToEdge,
+                FilterMode::Linear,
+                FilterMode::Linear,
+                FilterMode::Nearest,
+                Some(CompareFunction::LessEqual),
+            );
+            unsafe { _SHADOW_SAMPLER_HANDLE = handle };
+        }
+
+        unsafe { _SHADOW_SAMPLER_HANDLE }
+    }
 }
\ No newline at end of file
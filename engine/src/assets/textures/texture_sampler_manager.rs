@@ -6,7 +6,7 @@ use wgpu::{Device, Queue};
 
 use crate::
     assets::{mipmap_generator::MipmapGenerator, textures::{
-        sampler::{AddressMode, FilterMode, Sampler, SamplerHandle},
+        sampler::{AddressMode, CompareFunction, FilterMode, Sampler, SamplerHandle},
         texture::{
             Extent3d, SourceType, Texture, TextureDimension, TextureFormat, TextureHandle,
             TextureUsages,
@@ -593,7 +593,23 @@ impl TextureSamplerManager {
         min_filter: FilterMode,
         mipmap_filter: FilterMode,
     ) -> SamplerHandle {
-        let handle = Sampler::compute_sampler_handle(address_mode_u, address_mode_v, address_mode_w, mag_filter, min_filter, mipmap_filter);
+        self.create_sampler_with_compare(address_mode_u, address_mode_v, address_mode_w, mag_filter, min_filter, mipmap_filter, None)
+    }
+
+    /// Create a sampler with an optional compare function.
+    /// A sampler with compare function is a comparison sampler, which is used to sample depth textures, e.g., shadow maps.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_sampler_with_compare(
+        &mut self,
+        address_mode_u: AddressMode,
+        address_mode_v: AddressMode,
+        address_mode_w: AddressMode,
+        mag_filter: FilterMode,
+        min_filter: FilterMode,
+        mipmap_filter: FilterMode,
+        compare: Option<CompareFunction>,
+    ) -> SamplerHandle {
+        let handle = Sampler::compute_sampler_handle(address_mode_u, address_mode_v, address_mode_w, mag_filter, min_filter, mipmap_filter, compare);
         if !self.samplers.contains_key(&handle) {
             let sampler = Sampler::new_with_handle(
                 address_mode_u,
@@ -602,6 +618,7 @@ impl TextureSamplerManager {
                 mag_filter,
                 min_filter,
                 mipmap_filter,
+                compare,
                 handle,
             );
             self.samplers.insert(handle, sampler);
@@ -622,6 +639,7 @@ impl TextureSamplerManager {
                 mag_filter: sampler.mag_filter,
                 min_filter: sampler.min_filter,
                 mipmap_filter: sampler.mipmap_filter,
+                compare: sampler.compare,
                 ..Default::default()
             });
             sampler.gpu_sampler = Some(gpu_sampler);
@@ -643,6 +661,20 @@ impl TextureSamplerManager {
         }
 
         self.create_gpu_sampler(&default_sampler_handle);
+
+        let shadow_sampler_handle = Sampler::shadow_sampler();
+        if !self.samplers.contains_key(&shadow_sampler_handle) {
+            self.create_sampler_with_compare(
+                AddressMode::ClampToEdge,
+                AddressMode::ClampToEdge,
+                AddressMode::ClampToEdge,
+                FilterMode::Linear,
+                FilterMode::Linear,
+                FilterMode::Nearest,
+                Some(CompareFunction::LessEqual),
+            );
+        }
+        self.create_gpu_sampler(&shadow_sampler_handle);
     }
 
     pub fn get_sampler(&self, sampler_handle: &SamplerHandle) -> Option<&Sampler> {
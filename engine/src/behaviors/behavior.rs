@@ -13,6 +13,11 @@ pub trait Behavior : Any {
     }
     fn on_update(&mut self, logic_context: &mut LogicContext) {
 
+    }
+    /// Called at the fixed rate of [`crate::time::Time::fixed_timestep`], zero or more times per frame before
+    /// `on_update`. Use the fixed timestep instead of the frame delta here, e.g., for physics.
+    fn on_fixed_update(&mut self, logic_context: &mut LogicContext) {
+
     }
     fn on_destroy(&mut self, logic_context: &mut LogicContext) {
         
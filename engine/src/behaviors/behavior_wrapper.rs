@@ -24,6 +24,9 @@ impl BehaviorWrapper {
     pub(crate) fn on_update(&mut self, logic_context: &mut LogicContext) {
         self.behavior.on_update(logic_context);
     }
+    pub(crate) fn on_fixed_update(&mut self, logic_context: &mut LogicContext) {
+        self.behavior.on_fixed_update(logic_context);
+    }
     pub(crate) fn on_destroy(&mut self, logic_context: &mut LogicContext) {
         self.behavior.on_destroy(logic_context);
     }
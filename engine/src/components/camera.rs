@@ -1,16 +1,18 @@
 use std::cell::RefCell;
 
+use serde::{Deserialize, Serialize};
+
 use crate::{
     assets::{TextureDimension, TextureFormat, TextureHandle, TextureSamplerManager},
-    core::layer::LayerMask,
+    components::{component::Component, transform::Transform},
+    core::{NodeHandle, Scene, layer::LayerMask},
     graphics::uniform::BuiltinUniforms,
-    impl_component,
-    math::{Mat4, Vec3, Vec4, color::Color},
+    math::{Mat4, Ray, Vec2, Vec3, Vec4, color::Color},
     window::window_size::WindowSize,
 };
 
 /// The camera projection mode.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CameraMode {
     /// Perspective projection.
     Perspective,
@@ -30,9 +32,9 @@ pub struct Camera {
     pub near: f32,
     /// Far plane of frustum.
     pub far: f32,
-    /// The target postion that the camera looks at.
+    /// The target postion that the camera looks at, in the space of the parent of the camera node.
     pub target_pos: Vec3,
-    /// The up vector.
+    /// The up vector, in the space of the parent of the camera node.
     pub up: Vec3,
     /// The clear color which is used to clear the color attachment.
     pub clear_color: Option<Color>,
@@ -78,7 +80,45 @@ pub struct Camera {
     logical_size: WindowSize,
 }
 
-impl_component!(Camera);
+/// The duplicate renders into the same attachments, and its uniforms are created again.
+impl Component for Camera {
+    fn clone_component(&self) -> Option<Self> {
+        Some(Self {
+            mode: self.mode,
+            fov: self.fov,
+            aspect: self.aspect,
+            near: self.near,
+            far: self.far,
+            target_pos: self.target_pos,
+            up: self.up,
+            clear_color: self.clear_color,
+            visible_layers: self.visible_layers,
+            priority: self.priority,
+            orthogonal_frustum_size: self.orthogonal_frustum_size,
+            left: self.left,
+            right: self.right,
+            top: self.top,
+            bottom: self.bottom,
+            color_attachment: self.color_attachment,
+            depth_attachment: self.depth_attachment,
+            depth_format: self.depth_format,
+            view_port: self.view_port,
+            logical_view_port: self.logical_view_port,
+            physical_view_port: self.physical_view_port,
+            per_camera_uniforms: RefCell::new(BuiltinUniforms::new("Camera".to_owned())),
+            physical_size: self.physical_size,
+            logical_size: self.logical_size,
+        })
+    }
+
+    fn on_added(scene: &mut Scene, node: &NodeHandle) {
+        scene.cached_cameras.push(*node);
+    }
+
+    fn on_removed(scene: &mut Scene, node: &NodeHandle) {
+        scene.cached_cameras.retain(|camera| camera != node);
+    }
+}
 
 impl Default for Camera {
     fn default() -> Self {
@@ -238,6 +278,7 @@ impl Camera {
         self.logical_view_port.x = self.view_port.x * self.logical_size.width;
         self.logical_view_port.y = self.view_port.y * self.logical_size.height;
         self.logical_view_port.z = self.view_port.z * self.logical_size.width;
+        self.logical_view_port.w = self.view_port.w * self.logical_size.height;
 
         if self.mode == CameraMode::Orthogonal {
             self.left = -self.orthogonal_frustum_size * self.aspect;
@@ -250,11 +291,49 @@ impl Camera {
         view
     }
 
+    /// Get the view matrix of the camera from the transform of its node.
+    ///
+    /// [`Camera::target_pos`] and [`Camera::up`] are in the space of the parent of the camera node, so that a camera
+    /// parented under a moving rig moves and turns with it. For a camera without parent, it equals
+    /// [`Camera::get_view_matrix`] with the position of the node.
+    pub fn get_world_view_matrix(&self, transform: &Transform) -> Mat4 {
+        // The inverse of the parent model matrix is `local_matrix * model_matrix^-1`.
+        self.get_view_matrix(&transform.position) * transform.local_matrix() * transform.model_matrix.inverse()
+    }
+
+    /// Get the ray from the camera through a point on screen, e.g., the cursor position to pick nodes by
+    /// [`Scene::raycast_meshes`].
+    ///
+    /// # Arguments
+    ///
+    /// * `camera_pos` - The position of the camera node.
+    /// * `screen_point` - The point in logical pixels from the top left corner of the window, like
+    ///   [`crate::input::input_manager::MouseData::cur_mouse_pos`].
+    ///
+    /// # Returns
+    ///
+    /// * `Ray` - The ray in world space, which starts on the near plane.
+    pub fn screen_point_to_ray(&self, camera_pos: &Vec3, screen_point: Vec2) -> Ray {
+        let view_port = self.logical_view_port;
+        let ndc_x = (screen_point.x - view_port.x) / view_port.z * 2.0 - 1.0;
+        let ndc_y = 1.0 - (screen_point.y - view_port.y) / view_port.w * 2.0;
+        let inverse_view_projection = (self.get_projection_matrix() * self.get_view_matrix(camera_pos)).inverse();
+        let near_point = inverse_view_projection.project_point3(Vec3::new(ndc_x, ndc_y, 0.0));
+        let far_point = inverse_view_projection.project_point3(Vec3::new(ndc_x, ndc_y, 1.0));
+        Ray::new(near_point, far_point - near_point)
+    }
+
     pub fn get_projection_matrix(&self) -> Mat4 {
+        self.get_projection_matrix_with_range(self.near, self.far)
+    }
+
+    /// Get the projection matrix whose near and far planes are replaced by the given ones.
+    /// It is used to compute the sub frustums of cascaded shadow maps.
+    pub(crate) fn get_projection_matrix_with_range(&self, near: f32, far: f32) -> Mat4 {
         let projection = if self.mode == CameraMode::Perspective {
-            Mat4::perspective_rh(self.fov, self.aspect, self.near, self.far)
+            Mat4::perspective_rh(self.fov, self.aspect, near, far)
         } else {
-            let m = Mat4::orthographic_rh(self.left, self.right, self.bottom, self.top, self.near, self.far);
+            let m = Mat4::orthographic_rh(self.left, self.right, self.bottom, self.top, near, far);
             // dbg!(m);
             m
         };
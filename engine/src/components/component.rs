@@ -1,24 +1,68 @@
+use crate::{core::{NodeHandle, Scene}, utils::sparse_set::SparseKey};
 
 /// Component is a trait that all components must implement.
 pub trait Component : 'static {
+    /// The clone hook used by [`crate::core::Scene::duplicate_node`]. Components returning None, e.g., [`crate::prelude::Script`],
+    /// are not copied to the duplicates.
+    /// 
+    /// Use `impl_component!(MyComponent, Clone)` to implement it by [`Clone`].
+    fn clone_component(&self) -> Option<Self> where Self: Sized {
+        None
+    }
+
+    /// Called after the component is added to a node, or replaces the component of the node.
+    fn on_added(_scene: &mut Scene, _node: &NodeHandle) where Self: Sized {}
+
+    /// Called before the component is removed from a node, so it can still be got from the scene. It is also called
+    /// when the node is destroyed or the component is replaced.
+    fn on_removed(_scene: &mut Scene, _node: &NodeHandle) where Self: Sized {}
+
+    /// Called when the node becomes enabled in hierarchy, after the transforms are propagated.
+    fn on_enable(_scene: &mut Scene, _node: &NodeHandle) where Self: Sized {}
+
+    /// Called when the node becomes disabled in hierarchy, after the transforms are propagated.
+    fn on_disable(_scene: &mut Scene, _node: &NodeHandle) where Self: Sized {}
 }
 
-pub(crate) type ComponentTypeId = std::any::TypeId;
+type ComponentHook = fn(&mut Scene, &NodeHandle);
 
-type ComponentIdType = u32;
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
-pub(crate) struct ComponentId(pub(crate) ComponentIdType);
+/// The lifecycle hooks of a component type, which are called where the type is not known, e.g., when a node is
+/// destroyed.
+#[derive(Clone, Copy)]
+pub(crate) struct ComponentHooks {
+    pub(crate) on_added: ComponentHook,
+    pub(crate) on_removed: ComponentHook,
+    pub(crate) on_enable: ComponentHook,
+    pub(crate) on_disable: ComponentHook,
+}
 
-impl ComponentId {
-    pub fn new(id: ComponentIdType) -> Self {
-        Self(id)
+impl ComponentHooks {
+    pub(crate) fn of<T: Component>() -> Self {
+        Self {
+            on_added: T::on_added,
+            on_removed: T::on_removed,
+            on_enable: T::on_enable,
+            on_disable: T::on_disable,
+        }
     }
 }
 
-impl std::fmt::Display for ComponentId {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0)
+pub(crate) type ComponentTypeId = std::any::TypeId;
+
+/// The generational id of a component, which is the index and generation of the node owning it.
+///
+/// A node has at most one component of each type, so its components are found from the node handle directly, and
+/// the components left by a destroyed node never match a new node which reuses its slot.
+pub(crate) type ComponentId = SparseKey;
+
+impl From<&NodeHandle> for ComponentId {
+    fn from(node_handle: &NodeHandle) -> Self {
+        Self::new(node_handle.id as u32, node_handle.generation)
     }
 }
 
-pub(crate) const INVALID_COMPONENT_ID: ComponentId = ComponentId(u32::MAX);
\ No newline at end of file
+impl From<&ComponentId> for NodeHandle {
+    fn from(component_id: &ComponentId) -> Self {
+        Self::new_with_generation(component_id.index as u64, component_id.generation)
+    }
+}
@@ -1,17 +1,25 @@
 use std::any::Any;
 
 use ahash::AHashMap;
-use crate::prelude::{Component, ComponentId, ComponentTypeId, INVALID_COMPONENT_ID, component_arena::ComponentArena};
+use crate::{components::component::ComponentHooks, prelude::{Component, ComponentId, ComponentTypeId}, utils::sparse_set::SparseSet};
 
 /// ComponentStorage is a trait that all component storages must implement.
 pub(crate) trait AbstractComponentStorage  : Any{
     fn as_any(&self) -> &dyn Any;
     fn as_any_mut(&mut self) -> &mut dyn Any;
-    fn remove_component_internal(&mut self, componente_id: &ComponentId);
+    fn contains(&self, component_id: &ComponentId) -> bool;
+    fn component_ids(&self) -> &[ComponentId];
+    fn remove_component_internal(&mut self, componente_id: &ComponentId) -> bool;
+    /// Move the component into the storage of the same type in `target` with a new id.
+    fn move_component_to(&mut self, component_id: &ComponentId, target: &mut ComponentStorages, target_component_id: ComponentId) -> bool;
+    /// Clone the component by [`Component::clone_component`] and add the clone with a new id.
+    fn clone_component(&mut self, component_id: &ComponentId, target_component_id: ComponentId) -> bool;
+    fn hooks(&self) -> ComponentHooks;
 }
 
+/// Components of one type packed in a [`SparseSet`].
 pub(crate) struct ConcreteComponentStarage<T: Component> {
-    component_arena:ComponentArena<T>,
+    pub(crate) components: SparseSet<T>,
 }
 
 impl<T: Component> AbstractComponentStorage for ConcreteComponentStarage<T> {
@@ -23,28 +31,46 @@ impl<T: Component> AbstractComponentStorage for ConcreteComponentStarage<T> {
         self
     }
 
-    fn remove_component_internal(&mut self, componente_id: &ComponentId) {
-        self.component_arena.remove_component(componente_id);
+    fn contains(&self, component_id: &ComponentId) -> bool {
+        self.components.contains(component_id)
     }
-}
 
-impl<T: Component> ConcreteComponentStarage<T> {
-    pub fn new() -> Self {
-        Self {
-            component_arena: ComponentArena::new(),
+    fn component_ids(&self) -> &[ComponentId] {
+        self.components.keys()
+    }
+
+    fn remove_component_internal(&mut self, componente_id: &ComponentId) -> bool {
+        self.components.remove(componente_id).is_some()
+    }
+
+    fn move_component_to(&mut self, component_id: &ComponentId, target: &mut ComponentStorages, target_component_id: ComponentId) -> bool {
+        if let Some(component) = self.components.remove(component_id) {
+            target.add_component(target_component_id, component);
+            true
+        } else {
+            false
         }
     }
 
-    pub fn remove_component(&mut self, component_id: &ComponentId) -> Option<T> {
-        self.component_arena.remove_component(component_id)
+    fn clone_component(&mut self, component_id: &ComponentId, target_component_id: ComponentId) -> bool {
+        if let Some(component) = self.components.get(component_id).and_then(T::clone_component) {
+            self.components.insert(target_component_id, component);
+            true
+        } else {
+            false
+        }
     }
 
-    pub fn add_component(&mut self, component: T) -> ComponentId {
-        self.component_arena.add_component(component)
+    fn hooks(&self) -> ComponentHooks {
+        ComponentHooks::of::<T>()
     }
+}
 
-    pub fn replace_component(&mut self, component_id: &ComponentId, new_component: T) -> Option<T> {
-        self.component_arena.replace_component(component_id, new_component)
+impl<T: Component> ConcreteComponentStarage<T> {
+    pub fn new() -> Self {
+        Self {
+            components: SparseSet::new(),
+        }
     }
 }
 
@@ -57,92 +83,107 @@ impl ComponentStorages {
         Self { component_storages: AHashMap::new() }
     }
 
-    pub fn add_component<T: Component>(&mut self, component: T, component_type_id: ComponentTypeId) -> ComponentId {
-        let component_storage = self.component_storages.entry(component_type_id).or_insert_with(|| {
+    /// Get the storage of components of type `T`.
+    pub(crate) fn get_storage<T: Component>(&self) -> Option<&ConcreteComponentStarage<T>> {
+        self.component_storages
+            .get(&std::any::TypeId::of::<T>())?
+            .as_any()
+            .downcast_ref::<ConcreteComponentStarage<T>>()
+    }
+
+    /// Get the storage of components of type `T` mutably.
+    pub(crate) fn get_storage_mut<T: Component>(&mut self) -> Option<&mut ConcreteComponentStarage<T>> {
+        self.component_storages
+            .get_mut(&std::any::TypeId::of::<T>())?
+            .as_any_mut()
+            .downcast_mut::<ConcreteComponentStarage<T>>()
+    }
+
+    /// Add a component, or replace the old one with the same id.
+    pub fn add_component<T: Component>(&mut self, component_id: ComponentId, component: T) -> Option<T> {
+        let component_storage = self.component_storages.entry(std::any::TypeId::of::<T>()).or_insert_with(|| {
             Box::new(ConcreteComponentStarage::<T>::new())
         });
         if let Some(concrete_component_storage) = component_storage.as_any_mut().downcast_mut::<ConcreteComponentStarage<T>>() {
-            concrete_component_storage.add_component(component)
+            concrete_component_storage.components.insert(component_id, component)
         } else {
-            log::error!("Failed to add a component with component type id {:?}", component_type_id);
-            INVALID_COMPONENT_ID
+            log::error!("Failed to add a component with component type id {:?}", std::any::TypeId::of::<T>());
+            None
         }
     }
 
-    pub fn remove_component<T: Component>(&mut self, component_id: &ComponentId, component_type_id: ComponentTypeId) -> Option<T> {
-        if let Some(component_storage) = self.component_storages.get_mut(&component_type_id) {
-            if let Some(concrete_component_storage) = component_storage.as_any_mut().downcast_mut::<ConcreteComponentStarage<T>>() {
-                concrete_component_storage.remove_component(component_id)
-            } else {
-                log::error!("Failed to remove a component with component type id {:?}", component_type_id);
-                None
-            }
-        } else {
-            None
-        }
+    pub fn remove_component<T: Component>(&mut self, component_id: &ComponentId) -> Option<T> {
+        self.get_storage_mut::<T>()?.components.remove(component_id)
     }
 
-    pub fn replace_component<T: Component>(&mut self, component_id: &ComponentId, component_type_id: ComponentTypeId, new_component: T) -> Option<T> {
-        if let Some(component_storage) = self.component_storages.get_mut(&component_type_id) {
-            if let Some(concrete_component_storage) = component_storage.as_any_mut().downcast_mut::<ConcreteComponentStarage<T>>() {
-                concrete_component_storage.replace_component(component_id, new_component)
-            } else {
-                log::error!("Failed to replace a component with component type id {:?}", component_type_id);
-                None
-            }
-        } else {
-            None
-        }
+    /// Remove all components with the id, e.g., when their node is destroyed.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<ComponentTypeId>` - The types of the removed components.
+    pub(crate) fn remove_components(&mut self, component_id: &ComponentId) -> Vec<ComponentTypeId> {
+        self.component_storages
+            .iter_mut()
+            .filter_map(|(component_type_id, component_storage)| {
+                component_storage.remove_component_internal(component_id).then_some(*component_type_id)
+            })
+            .collect()
     }
 
-    pub(crate) fn remove_component_internal(&mut self, component_id: &ComponentId, component_type_id: &ComponentTypeId) {
-        if let Some(component_storage) = self.component_storages.get_mut(component_type_id) {
-            component_storage.remove_component_internal(component_id);
-        }
+    /// The types of all components with the id.
+    pub(crate) fn component_types(&self, component_id: &ComponentId) -> Vec<ComponentTypeId> {
+        self.component_storages
+            .iter()
+            .filter_map(|(component_type_id, component_storage)| {
+                component_storage.contains(component_id).then_some(*component_type_id)
+            })
+            .collect()
+    }
+
+    /// The lifecycle hooks of a component type, or None if no component of the type has been added.
+    pub(crate) fn hooks(&self, component_type_id: &ComponentTypeId) -> Option<ComponentHooks> {
+        self.component_storages.get(component_type_id).map(|component_storage| component_storage.hooks())
+    }
+
+    pub(crate) fn contains(&self, component_id: &ComponentId, component_type_id: &ComponentTypeId) -> bool {
+        self.component_storages
+            .get(component_type_id)
+            .is_some_and(|component_storage| component_storage.contains(component_id))
+    }
+
+    /// The ids of all components of a type in dense order, or None if there is no storage of the type.
+    pub(crate) fn component_ids(&self, component_type_id: &ComponentTypeId) -> Option<&[ComponentId]> {
+        self.component_storages
+            .get(component_type_id)
+            .map(|component_storage| component_storage.component_ids())
+    }
+
+    /// Move a component of any type into `target`, e.g., when a node is moved to another scene.
+    pub(crate) fn move_component(&mut self, component_id: &ComponentId, component_type_id: &ComponentTypeId, target: &mut ComponentStorages, target_component_id: ComponentId) -> bool {
+        self.component_storages
+            .get_mut(component_type_id)
+            .is_some_and(|component_storage| component_storage.move_component_to(component_id, target, target_component_id))
+    }
+
+    /// Clone all components with the id which can be cloned, e.g., when their node is duplicated.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<ComponentTypeId>` - The types of the cloned components.
+    pub(crate) fn clone_components(&mut self, component_id: &ComponentId, target_component_id: ComponentId) -> Vec<ComponentTypeId> {
+        self.component_storages
+            .iter_mut()
+            .filter_map(|(component_type_id, component_storage)| {
+                component_storage.clone_component(component_id, target_component_id).then_some(*component_type_id)
+            })
+            .collect()
     }
 
     pub fn get_component<T: Component>(&self, component_id: &ComponentId) -> Option<&T> {
-        let component_type_id = std::any::TypeId::of::<T>();
-        let storage = self.component_storages.get(&component_type_id);
-        if let Some(component_storage) = storage {
-            if let Some(concrete_component_storage) = component_storage.as_any().downcast_ref::<ConcreteComponentStarage<T>>() {
-                concrete_component_storage.component_arena.get(&component_id)
-            } else {
-                #[cfg(debug_assertions)]
-                {
-                    log::warn!("Failed to get a component with component type id {:?}, component_id: {}", component_type_id, component_id);
-                }
-                None
-            }
-        } else {
-            #[cfg(debug_assertions)]
-            {
-                log::warn!("Failed to get a component with component type id {:?}, component_id: {}", component_type_id, component_id);
-            }
-            None
-        }
+        self.get_storage::<T>()?.components.get(component_id)
     }
 
     pub fn get_component_mut<T: Component>(&mut self, component_id: &ComponentId) -> Option<&mut T> {
-        let component_type_id = std::any::TypeId::of::<T>();
-        let storage = self.component_storages.get_mut(&component_type_id);
-        if let Some(component_storage) = storage {
-            if let Some(concrete_component_storage) = component_storage.as_any_mut().downcast_mut::<ConcreteComponentStarage<T>>() {
-                concrete_component_storage.component_arena.get_mut(&component_id)
-            } else {
-                #[cfg(debug_assertions)]
-                {
-                    log::warn!("Failed to get a component with component type id {:?}, component_id: {}", component_type_id, component_id);
-                }
-                None
-            }
-        } else {
-            #[cfg(debug_assertions)]
-            {
-                log::warn!("Failed to get a component with component type id {:?}, component_id: {}", component_type_id, component_id);
-            }
-            None
-        }
+        self.get_storage_mut::<T>()?.components.get_mut(component_id)
     }
 }
-
@@ -1,9 +1,10 @@
 use bytemuck::{Pod, Zeroable};
+use serde::{Deserialize, Serialize};
 
-use crate::{impl_component, math::Color};
+use crate::{components::component::Component, core::{LayerMask, NodeHandle, Scene}, math::Color};
 
 /// The shape of the area light.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum AreaLightShape {
     Rectangle { width: f32, height: f32 },
     Disk { radius: f32 },
@@ -11,7 +12,7 @@ pub enum AreaLightShape {
 }
 
 /// The type of light.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub(crate) enum LightType {
     /// A light that emits light in a specific direction.
     Directional {
@@ -54,7 +55,49 @@ impl LightType {
     }
 }
 
+/// Per-light shadow settings. It only takes effect when [`Light::cast_shadow`] is true.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ShadowSettings {
+    /// The resolution in pixels of one shadow map tile in the shadow atlas.
+    ///
+    /// A directional light allocates one tile per cascade, a spot light allocates one tile
+    /// and a point light allocates six tiles which are the faces of its cube shadow map.
+    /// It is rounded up to a power of two.
+    pub resolution: u32,
+    /// The constant depth bias in normalized depth, used to avoid shadow acne. It is subtracted from the depth of the
+    /// shaded surface when it is compared with the shadow map.
+    pub depth_bias: f32,
+    /// The normal offset in shadow map texels, which pushes the sampling position along the surface normal.
+    pub normal_offset: f32,
+    /// The PCF kernel radius in texels. 0 means hard shadows, 1 means 3x3 PCF, 2 means 5x5 PCF, and so on.
+    pub pcf_radius: u32,
+    /// The cascade count of a directional light, which is in [1, 4]. It is ignored by other light types.
+    pub cascade_count: u32,
+    /// The blend factor between uniform (0.0) and logarithmic (1.0) cascade splits.
+    pub cascade_split_lambda: f32,
+    /// The max distance from the camera covered by the cascades of a directional light.
+    pub max_shadow_distance: f32,
+    /// The near plane of the spot light or point light shadow projection.
+    pub near_plane: f32,
+}
+
+impl Default for ShadowSettings {
+    fn default() -> Self {
+        Self {
+            resolution: 1024,
+            depth_bias: 0.0005,
+            normal_offset: 1.5,
+            pcf_radius: 1,
+            cascade_count: 4,
+            cascade_split_lambda: 0.75,
+            max_shadow_distance: 50.0,
+            near_plane: 0.05,
+        }
+    }
+}
+
 /// A light source in the scene. It is a component that can be attached to a node.
+#[derive(Clone)]
 pub struct Light {
     /// Whether the light is enabled.
     pub enabled: bool,
@@ -64,6 +107,10 @@ pub struct Light {
     pub intensity: f32,
     /// Whether the light casts shadow.
     pub cast_shadow: bool,
+    /// The shadow settings of the light.
+    pub shadow_settings: ShadowSettings,
+    /// The layers of the nodes which are lit by the light and cast its shadow. All layers by default.
+    pub culling_mask: LayerMask,
     /// The type of the light.
     pub(crate) light_type: LightType,
     /// The cached spot light outer cos.
@@ -72,7 +119,20 @@ pub struct Light {
     pub(crate) cached_inner_cos: f32,
 }
 
-impl_component!(Light);
+/// Lights are cached by the scene to collect the lights data.
+impl Component for Light {
+    fn clone_component(&self) -> Option<Self> {
+        Some(self.clone())
+    }
+
+    fn on_added(scene: &mut Scene, node: &NodeHandle) {
+        scene.cached_lights.push(*node);
+    }
+
+    fn on_removed(scene: &mut Scene, node: &NodeHandle) {
+        scene.cached_lights.retain(|light| light != node);
+    }
+}
 
 impl Light {
     /// Creates a new light with the given parameters.
@@ -94,6 +154,8 @@ impl Light {
             color,
             intensity,
             cast_shadow,
+            shadow_settings: ShadowSettings::default(),
+            culling_mask: LayerMask::ALL,
             light_type,
             cached_outer_cos: 0.0,
             cached_inner_cos: 0.0,
@@ -290,7 +352,12 @@ impl Light {
 #[derive(Debug, Clone, Copy, PartialEq, Pod, Zeroable)]
 pub(crate) struct GPULightData {
     /// flags.x is light type, 0: directional, 1: point, 2: spot, 3: area.
+    /// flags.y is the shadow view count, 0 means no shadow.
+    /// flags.z is the index of the first shadow view.
+    /// flags.w is the PCF kernel radius.
     pub(crate) flags: [u32; 4],
+    /// culling.x is the culling mask of the light.
+    pub(crate) culling: [u32; 4],
     /// color.w is range or max distance for spot or point light.
     pub(crate) color: [f32;4],
     /// For spot light: direction.w is inner cone cosin.
@@ -303,6 +370,7 @@ impl Default for GPULightData {
     fn default() -> Self {
         Self {
             flags: [0; 4],
+            culling: [0; 4],
             color: [0.0; 4],
             direction: [0.0; 4],
             position: [0.0; 4],
@@ -327,7 +395,7 @@ impl Default for LightsGPUData {
 impl LightsGPUData {
     pub(crate) fn to_vec_u8(&self) -> Vec<u8> {
         let header_size = std::mem::size_of::<[u32; 4]>(); // 16
-        let stride = std::mem::size_of::<GPULightData>();  // 64
+        let stride = std::mem::size_of::<GPULightData>();  // 80
 
         let total_size = header_size + stride * self.lights_info.len();
 
@@ -1,8 +1,10 @@
 
 use crate::{
-    assets::{MaterialHandle, MeshHandle},
+    assets::{MaterialHandle, Mesh, MeshHandle},
+    components::component::Component,
+    core::{NodeHandle, Scene},
     graphics::uniform::BuiltinUniforms,
-    impl_component,
+    math::{Aabb, BoundingSphere},
 };
 
 
@@ -11,12 +13,40 @@ pub struct MeshRenderer {
     /// One sub mesh has a seperate material.
     // pub materials: Vec<Material>,
     pub materials: Vec<MaterialHandle>,
+    /// Whether the mesh is rendered into the shadow maps of lights which cast shadow.
+    pub cast_shadow: bool,
+    /// Whether the mesh is skipped by cameras whose frustums do not contain its bounds. Disable it for meshes which
+    /// are moved by shaders, like the skybox.
+    pub frustum_culling: bool,
 
     /// Per object builtin uniforms, like model matrix.
     pub(crate) per_object_uniforms: BuiltinUniforms,
+    /// The local bounds of the mesh, which are synced from the mesh when it is assigned or modified.
+    pub(crate) local_bounds: Option<(Aabb, BoundingSphere)>,
+    /// The mesh and the version of its bounds which `local_bounds` are copied from.
+    synced_bounds_version: Option<(MeshHandle, u64)>,
 }
 
-impl_component!(MeshRenderer);
+impl Component for MeshRenderer {
+    fn clone_component(&self) -> Option<Self> {
+        let mut mesh_renderer = Self::new(self.mesh, self.materials.clone());
+        mesh_renderer.cast_shadow = self.cast_shadow;
+        mesh_renderer.frustum_culling = self.frustum_culling;
+        mesh_renderer.local_bounds = self.local_bounds;
+        mesh_renderer.synced_bounds_version = self.synced_bounds_version;
+        Some(mesh_renderer)
+    }
+
+    fn on_added(scene: &mut Scene, node: &NodeHandle) {
+        scene.cached_renderables.push(*node);
+        scene.spatial_index_changes.push(*node);
+    }
+
+    fn on_removed(scene: &mut Scene, node: &NodeHandle) {
+        scene.cached_renderables.retain(|renderable| renderable != node);
+        scene.spatial_index.remove(node);
+    }
+}
 
 impl MeshRenderer {
     /// Create a new mesh renderer component.
@@ -29,7 +59,34 @@ impl MeshRenderer {
         Self {
             materials,
             mesh,
+            cast_shadow: true,
+            frustum_culling: true,
             per_object_uniforms: BuiltinUniforms::new("MeshRenderer".to_owned()),
+            local_bounds: None,
+            synced_bounds_version: None,
         }
     }
+
+    /// Whether the bounds of the mesh, which is rendered by this mesh renderer, have to be copied again because
+    /// another mesh is assigned or the mesh is modified.
+    pub(crate) fn needs_bounds_sync(&self, mesh: &Mesh) -> bool {
+        self.synced_bounds_version != Some((self.mesh, mesh.bounds_version()))
+    }
+
+    /// Copy the bounds of the mesh, which is rendered by this mesh renderer.
+    pub(crate) fn sync_bounds(&mut self, mesh: &Mesh) {
+        self.local_bounds = mesh.bounds().zip(mesh.bounding_sphere());
+        self.synced_bounds_version = Some((self.mesh, mesh.bounds_version()));
+    }
+
+    /// The bounding box of the mesh in local space. It is None if the mesh has no vertex, or if the mesh renderer is
+    /// created by hand and has not been updated by the engine yet.
+    pub fn local_bounds(&self) -> Option<Aabb> {
+        self.local_bounds.map(|(aabb, _)| aabb)
+    }
+
+    /// The bounding sphere of the mesh in local space, see [`MeshRenderer::local_bounds`].
+    pub fn local_bounding_sphere(&self) -> Option<BoundingSphere> {
+        self.local_bounds.map(|(_, bounding_sphere)| bounding_sphere)
+    }
 }
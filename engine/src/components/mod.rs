@@ -5,8 +5,8 @@ pub mod camera;
 pub mod mesh_renderer;
 pub mod text_renderer;
 pub mod sprite_renderer;
-pub mod component_arena;
 pub mod light;
+pub mod script;
 
 pub use component::*;
 pub use transform::*;
@@ -14,4 +14,5 @@ pub use camera::*;
 pub use mesh_renderer::*;
 pub use text_renderer::*;
 pub use sprite_renderer::*;
-pub use light::*;
\ No newline at end of file
+pub use light::*;
+pub use script::*;
\ No newline at end of file
@@ -0,0 +1,121 @@
+use crate::{behaviors::behavior::Behavior, impl_component};
+
+/// A component which attaches a [`Behavior`] to its node, like a script in Unity.
+///
+/// The behaviors of scripts run after the behaviors added by [`crate::core::Engine::add_behavior`], and
+/// [`crate::core::LogicContext::node`] is the owning node while their methods are called.
+/// A script is skipped when its node is not enabled in hierarchy.
+///
+/// When the node of a started script is destroyed, `on_destroy` is called at the next sync point of the engine loop,
+/// i.e., after the stage, the behaviors or the fixed update which destroyed it, or before its scene is unloaded or the
+/// engine stops. A script which is destroyed before its `on_start` is dropped without `on_destroy`.
+pub struct Script {
+    /// It is None while the behavior is running.
+    pub(crate) behavior: Option<Box<dyn Behavior>>,
+    pub(crate) is_started: bool,
+}
+
+impl_component!(Script);
+
+impl Script {
+    /// Create a new script component.
+    ///
+    /// # Arguments
+    ///
+    /// * `behavior` - The behavior run by the script.
+    pub fn new<T: Behavior>(behavior: T) -> Self {
+        Self {
+            behavior: Some(Box::new(behavior)),
+            is_started: false,
+        }
+    }
+
+    /// Get the behavior if it is of type `T`.
+    pub fn behavior<T: Behavior>(&self) -> Option<&T> {
+        self.behavior.as_ref()?.as_any().downcast_ref::<T>()
+    }
+
+    /// Get the behavior mutably if it is of type `T`.
+    pub fn behavior_mut<T: Behavior>(&mut self) -> Option<&mut T> {
+        self.behavior.as_mut()?.as_any_mut().downcast_mut::<T>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::*;
+    use crate::{core::{Engine, EngineOptions, LogicContext, NodeHandle}, impl_as_any};
+
+    struct Recorder {
+        calls: Rc<RefCell<Vec<(&'static str, Option<NodeHandle>)>>>,
+    }
+
+    impl Behavior for Recorder {
+        impl_as_any!();
+
+        fn on_start(&mut self, logic_context: &mut LogicContext) {
+            self.calls.borrow_mut().push(("start", logic_context.node));
+        }
+        fn on_update(&mut self, logic_context: &mut LogicContext) {
+            self.calls.borrow_mut().push(("update", logic_context.node));
+        }
+        fn on_destroy(&mut self, logic_context: &mut LogicContext) {
+            self.calls.borrow_mut().push(("destroy", logic_context.node));
+        }
+    }
+
+    #[test]
+    fn test_script_lifecycle() {
+        let mut engine = Engine::new(EngineOptions::default());
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let scene = engine.world.current_scene_mut();
+        let parent = scene.create_node("Parent");
+        let node = scene.create_node("Scripted");
+        scene.attach_to_parent(&node, parent);
+        scene.add(parent);
+        scene.add_component(&node, Script::new(Recorder { calls: calls.clone() }));
+
+        engine.execute_behaviors();
+        engine.execute_behaviors();
+        assert_eq!(*calls.borrow(), vec![("start", Some(node)), ("update", Some(node))]);
+
+        // Skipped when the node is not enabled in hierarchy.
+        engine.world.current_scene_mut().get_node_mut_forcely(&parent).enabled = false;
+        engine.world.updpate(&mut engine.time);
+        engine.execute_behaviors();
+        assert_eq!(calls.borrow().len(), 2);
+
+        engine.world.current_scene_mut().destroy_node(&parent);
+        engine.execute_behaviors();
+        assert_eq!(calls.borrow().last(), Some(&("destroy", Some(node))));
+        assert_eq!(calls.borrow().len(), 3);
+    }
+
+    #[test]
+    fn test_on_destroy_before_unload() {
+        let mut engine = Engine::new(EngineOptions::default());
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let scene_index = engine.world.add_scene(crate::core::Scene::new());
+        assert!(engine.load_scene_additive(scene_index));
+        let scene = engine.world.get_scene_mut(scene_index).unwrap();
+        let node = scene.create_node("Scripted");
+        scene.add(node);
+        scene.add_component(&node, Script::new(Recorder { calls: calls.clone() }));
+        let unstarted_node = scene.create_node("Unstarted");
+        scene.add(unstarted_node);
+
+        engine.execute_behaviors();
+        assert_eq!(*calls.borrow(), vec![("start", Some(node))]);
+
+        // Scripts which have not started are dropped without on_destroy.
+        let scene = engine.world.get_scene_mut(scene_index).unwrap();
+        scene.add_component(&unstarted_node, Script::new(Recorder { calls: calls.clone() }));
+        scene.destroy_node(&unstarted_node);
+        // The scene is unloaded before its scripts run again.
+        engine.world.get_scene_mut(scene_index).unwrap().destroy_node(&node);
+        assert!(engine.unload_scene(scene_index));
+        assert_eq!(*calls.borrow(), vec![("start", Some(node)), ("destroy", Some(node))]);
+    }
+}
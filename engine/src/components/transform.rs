@@ -24,9 +24,16 @@ pub struct Transform {
     pub normal_matrix: Mat3,
     
     /// A flag that indicates the Transform is dirty.
-    /// Engine will recompute [`model_matrix`](Self::model_matrix) when it is true and reset it to be false.
-    /// You need to make this field be true when you change other fields, e.g., [`position`](Self::position).
+    /// Engine will recompute [`model_matrix`](Self::model_matrix) of this transform and its descendants when it is true
+    /// and reset it to be false. The setters, e.g., [`Transform::set_position`], set it, so use them instead of changing
+    /// other fields directly, e.g., [`position`](Self::position).
     pub(crate) is_dirty: bool,
+
+    /// Whether the rendered transform is interpolated between the states of the last two fixed updates, which smooths
+    /// the motion driven by [`crate::prelude::Behavior::on_fixed_update`] when the frame rate differs from the fixed rate.
+    pub interpolate: bool,
+    /// The position, rotation and scale saved before the last fixed update.
+    pub(crate) previous_state: Option<(Vec3, Quat, Vec3)>,
 }
 
 impl_component!(Transform);
@@ -48,6 +55,8 @@ impl Default for Transform {
             is_dirty: true,
             model_matrix: Mat4::IDENTITY,
             normal_matrix: Mat3::IDENTITY,
+            interpolate: false,
+            previous_state: None,
         }
     }
 }
@@ -230,20 +239,77 @@ impl Transform {
         self.is_dirty = true;
     }
 
-    /// Update the model matrix of the transform.
+    /// Stop interpolating from the state of the last fixed update, e.g., after teleporting the transform, so that
+    /// the next frame is rendered at the new state instead of being blended from the old one. The interpolation starts
+    /// again at the next fixed update.
+    pub fn reset_interpolation(&mut self) {
+        if self.previous_state.take().is_some() {
+            self.is_dirty = true;
+        }
+    }
+
+    /// Save the current position, rotation and scale as the start of the interpolation, before a fixed update.
+    pub(crate) fn save_previous_state(&mut self) {
+        self.previous_state = Some((self.position, self.quat, self.scale));
+    }
+
+    /// The matrix from the local space of the transform to the space of its parent.
+    pub fn local_matrix(&self) -> Mat4 {
+        Mat4::from_scale_rotation_translation(self.scale, self.quat, self.position)
+    }
+
+    /// Whether the model matrix has to be recomputed.
     /// 
     /// # Arguments
     /// 
-    /// * `parent` - The parent matrix of the transform.
-    pub(crate) fn update_model_matrix(&mut self, parent: Option<Mat4>) {
-        if self.is_dirty {
-            self.model_matrix = Mat4::from_scale_rotation_translation(self.scale, self.quat, self.position);
-            self.is_dirty = false;
-        }
-        if let Some(parent_matrix) = parent {
-            self.model_matrix = parent_matrix * self.model_matrix;
-        }
+    /// * `is_parent_changed` - Whether the model matrix of the parent has been recomputed in this frame.
+    pub(crate) fn needs_update(&self, is_parent_changed: bool) -> bool {
+        self.is_dirty || is_parent_changed || (self.interpolate && self.previous_state.is_some())
+    }
+
+    /// Update the model matrix of the transform. It should only be called when [`Transform::needs_update`] is true.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `parent` - The model matrix of the parent, if any.
+    /// * `interpolation_alpha` - How far the frame is between the last two fixed updates, see [`Transform::interpolate`].
+    pub(crate) fn update_model_matrix(&mut self, parent: Option<Mat4>, interpolation_alpha: f32) {
+        let local_matrix = match self.previous_state {
+            Some((position, quat, scale)) if self.interpolate => Mat4::from_scale_rotation_translation(
+                scale.lerp(self.scale, interpolation_alpha),
+                quat.slerp(self.quat, interpolation_alpha),
+                position.lerp(self.position, interpolation_alpha),
+            ),
+            _ => self.local_matrix(),
+        };
+        self.model_matrix = match parent {
+            Some(parent_matrix) => parent_matrix * local_matrix,
+            None => local_matrix,
+        };
         self.normal_matrix = Mat3::from_mat4(self.model_matrix.inverse().transpose());
+        self.is_dirty = false;
     }
 
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reset_interpolation() {
+        let mut transform = Transform { interpolate: true, ..Default::default() };
+        transform.save_previous_state();
+        transform.set_position(Vec3::new(2.0, 0.0, 0.0));
+        transform.update_model_matrix(None, 0.5);
+        assert_eq!(transform.model_matrix.w_axis.truncate(), Vec3::new(1.0, 0.0, 0.0));
+
+        // A teleport is rendered at once.
+        transform.set_position(Vec3::new(100.0, 0.0, 0.0));
+        transform.reset_interpolation();
+        assert!(transform.needs_update(false));
+        transform.update_model_matrix(None, 0.5);
+        assert_eq!(transform.model_matrix.w_axis.truncate(), Vec3::new(100.0, 0.0, 0.0));
+        assert!(!transform.needs_update(false));
+    }
+}
@@ -0,0 +1,163 @@
+use crate::{
+    core::{NodeHandle, Scene, SceneEvent, World},
+    prelude::Component,
+};
+
+type AddComponentFn = Box<dyn FnOnce(&mut Scene, &NodeHandle)>;
+
+enum Command {
+    Spawn(String),
+    Destroy,
+    AddComponent(AddComponentFn),
+    RemoveComponent(fn(&mut Scene, &NodeHandle)),
+    SetParent(Option<NodeHandle>),
+}
+
+/// Structural changes queued by [`Commands`], which are applied in order by [`World::apply_commands`].
+#[derive(Default)]
+pub(crate) struct CommandBuffer {
+    /// The scene index, the target node and the command.
+    commands: Vec<(usize, NodeHandle, Command)>,
+}
+
+/// Queues structural changes of the current scene: spawning and destroying nodes, adding and removing components
+/// and reparenting.
+///
+/// Behaviors should use it instead of changing the scene directly while the engine iterates over nodes. The
+/// commands are applied after all behaviors and scripts have run in a frame, and before the scenes are updated.
+///
+/// # Examples
+///
+/// ```ignore
+/// let mut commands = logic_context.commands();
+/// let bullet = commands.spawn("Bullet");
+/// commands.add_component(&bullet, MeshRenderer::new(mesh, vec![material]));
+/// commands.set_parent(&bullet, Some(gun));
+/// ```
+pub struct Commands<'w> {
+    world: &'w mut World,
+}
+
+impl<'w> Commands<'w> {
+    pub(crate) fn new(world: &'w mut World) -> Self {
+        Self { world }
+    }
+
+    fn push(&mut self, node: &NodeHandle, command: Command) {
+        let scene_index = self.world.current_scene_index;
+        self.world.command_buffer.commands.push((scene_index, *node, command));
+    }
+
+    /// Spawn a root node.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the node.
+    ///
+    /// # Returns
+    ///
+    /// * `NodeHandle` - The handle of the node, which is reserved immediately so that it can be used by other
+    ///   commands and stored, but the node does not exist until the commands are applied.
+    pub fn spawn(&mut self, name: impl Into<String>) -> NodeHandle {
+        let node = self.world.current_scene_mut().node_arena.reserve_node();
+        self.push(&node, Command::Spawn(name.into()));
+        node
+    }
+
+    /// Destroy a node, see [`Scene::destroy_node`].
+    pub fn destroy(&mut self, node: &NodeHandle) {
+        self.push(node, Command::Destroy);
+    }
+
+    /// Add a component to a node, see [`Scene::add_component`].
+    pub fn add_component<T: Component>(&mut self, node: &NodeHandle, component: T) {
+        self.push(node, Command::AddComponent(Box::new(move |scene, node| {
+            scene.add_component(node, component);
+        })));
+    }
+
+    /// Remove the component of type `T` from a node, see [`Scene::remove_component`].
+    pub fn remove_component<T: Component>(&mut self, node: &NodeHandle) {
+        self.push(node, Command::RemoveComponent(|scene, node| {
+            scene.remove_component::<T>(node);
+        }));
+    }
+
+    /// Attach a node to a parent, or make it a root node if `parent` is None, see [`Scene::set_parent`].
+    pub fn set_parent(&mut self, node: &NodeHandle, parent: Option<NodeHandle>) {
+        self.push(node, Command::SetParent(parent));
+    }
+}
+
+impl World {
+    /// Get the [`Commands`] which queue structural changes of the current scene.
+    pub fn commands(&mut self) -> Commands<'_> {
+        Commands::new(self)
+    }
+
+    /// Apply the queued commands in order. It is the sync point of structural changes in the engine loop.
+    pub(crate) fn apply_commands(&mut self) {
+        let commands = std::mem::take(&mut self.command_buffer.commands);
+        for (scene_index, node, command) in commands {
+            let Some(scene) = self.scenes.get_mut(scene_index) else {
+                continue;
+            };
+            match command {
+                Command::Spawn(name) => {
+                    if scene.node_arena.create_reserved_node(&node, name) {
+                        scene.publish_event(SceneEvent::NodeCreated(node));
+                        scene.add(node);
+                    }
+                }
+                Command::Destroy => scene.destroy_node(&node),
+                Command::AddComponent(add_component) => add_component(scene, &node),
+                Command::RemoveComponent(remove_component) => remove_component(scene, &node),
+                Command::SetParent(parent) => {
+                    scene.set_parent(&node, parent, false);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{impl_component, math::color::Color, prelude::Light};
+
+    struct Health(i32);
+    impl_component!(Health);
+
+    #[test]
+    fn test_commands() {
+        let mut world = World::new();
+        let mut commands = world.commands();
+        let gun = commands.spawn("Gun");
+        let bullet = commands.spawn("Bullet");
+        commands.add_component(&bullet, Health(1));
+        commands.add_component(&bullet, Light::new_point_light(5.0, Color::WHITE, 1.0, false));
+        commands.set_parent(&bullet, Some(gun));
+        assert_ne!(gun, bullet);
+        assert!(world.current_scene().get_node(&bullet).is_none());
+        // Nodes created directly do not take the reserved slots.
+        let other = world.current_scene_mut().create_node("Other");
+        assert!(other != gun && other != bullet);
+
+        world.apply_commands();
+        let scene = world.current_scene_mut();
+        assert_eq!(scene.get_node_forcely(&bullet).parent, Some(gun));
+        assert_eq!(scene.root_nodes, vec![gun]);
+        assert_eq!(scene.get_component::<Health>(&bullet).unwrap().0, 1);
+        assert_eq!(scene.cached_lights, vec![bullet]);
+
+        let mut commands = world.commands();
+        commands.remove_component::<Health>(&bullet);
+        commands.set_parent(&bullet, None);
+        commands.destroy(&gun);
+        world.apply_commands();
+        let scene = world.current_scene_mut();
+        assert!(scene.get_component::<Health>(&bullet).is_none());
+        assert!(scene.get_node(&gun).is_none());
+        assert_eq!(scene.root_nodes, vec![bullet]);
+    }
+}
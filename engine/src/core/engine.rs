@@ -1,14 +1,20 @@
+use std::path::PathBuf;
+
 use crate::{
-    assets::{MaterialManager, MeshManager, ShaderManager, TextureSamplerManager},
-    core::World,
+    assets::{MaterialManager, MeshManager, ModelLoader, ShaderManager, TextureDimension, TextureFormat, TextureHandle, TextureSamplerManager},
+    core::{
+        BuiltinSystem, Commands, EnginePlugin, NodeHandle, Prefab, PrefabOverrides, Schedule, ScheduleError, Scene, SceneSerializeError,
+        SceneSerializer, Stage, System, SystemKind, World,
+    },
     event::{event_dispatcher::EventDispatcher, events::Events},
     graphics::graphics_context::GraphicsContext,
     input::input_manager::InputManager,
     prelude::{
-        Behavior,
+        Behavior, Script,
         behavior_wrapper::{BehaviorStatus, BehaviorWrapper}, uniform::BuiltinUniforms,
     },
-    renderer::frame_renderer::FrameRenderer,
+    graphics::texture_buffer_converter::read_texture_2d,
+    renderer::{frame_capture::{CapturedFrame, FrameCaptureError}, frame_renderer::FrameRenderer},
     time::Time,
     types::RR,
     utils::PerformanceTracker,
@@ -18,11 +24,19 @@ use crate::{
 /// The options of the engine.
 pub struct EngineOptions {
     /// The window size of the engine.
+    /// 
+    /// In headless mode, it is the size (in pixels) of the offscreen color target.
     pub window_size: WindowSize,
     /// The name of the engine.
     pub app_name: &'static str,
     // /// Whether the engine is fullscreen.
     // pub is_fullscreen: bool,
+    /// If it is Some, the engine runs in headless mode: no window is opened and the World is rendered into an offscreen color target.
+    pub headless: Option<HeadlessOptions>,
+    /// Whether to exit the app when Escape is released. It is false by default, so that Escape can be used by the game.
+    pub exit_on_escape: bool,
+    /// The plugins which are built in order by [`Engine::new`], see [`EnginePlugin`].
+    pub plugins: Vec<Box<dyn EnginePlugin>>,
 }
 
 impl Default for EngineOptions {
@@ -31,6 +45,41 @@ impl Default for EngineOptions {
             window_size: WindowSize::default(),
             app_name: "Imagic Engine",
             // is_fullscreen: false,
+            headless: None,
+            exit_on_escape: false,
+            plugins: Vec::new(),
+        }
+    }
+}
+
+/// The options of headless mode, in which the engine renders without window and surface.
+/// 
+/// It is useful to render golden images on CI machines or to batch-render thumbnails on servers without display.
+/// Behaviors still run every frame, but `Behavior::on_gui` is not called because there is no UI in headless mode.
+#[derive(Clone)]
+pub struct HeadlessOptions {
+    /// The number of frames to render before [`Engine::run`] returns.
+    pub frame_count: u32,
+    /// The format of the offscreen color target. Use a float format, e.g., `TextureFormat::Rgba32Float`, to capture HDR images.
+    pub color_format: TextureFormat,
+    /// The fixed delta time (in seconds) of each frame, which makes rendering deterministic.
+    /// If it is None, the wall clock time is used.
+    pub fixed_delta_time: Option<f32>,
+    /// If it is Some, the last frame is saved to this path when [`Engine::run`] finishes.
+    /// The image format is decided by the extension, `.png` or `.hdr`.
+    pub capture_path: Option<PathBuf>,
+    /// Whether to use the software fallback adapter, e.g., on CI machines without GPU.
+    pub force_fallback_adapter: bool,
+}
+
+impl Default for HeadlessOptions {
+    fn default() -> Self {
+        Self {
+            frame_count: 1,
+            color_format: TextureFormat::Rgba8UnormSrgb,
+            fixed_delta_time: Some(1.0 / 60.0),
+            capture_path: None,
+            force_fallback_adapter: false,
         }
     }
 }
@@ -53,6 +102,19 @@ pub struct LogicContext<'a> {
     pub texture_sampler_manager: &'a mut TextureSamplerManager,
     /// The input manager of the engine.
     pub input_manager: &'a mut InputManager,
+    /// The model loader of the engine, which finds the loader by file extension.
+    pub model_loader: &'a mut ModelLoader,
+    /// The node which owns the running behavior if it is a [`crate::prelude::Script`], or None for behaviors added by
+    /// [`Engine::add_behavior`].
+    pub node: Option<NodeHandle>,
+}
+
+impl LogicContext<'_> {
+    /// Get the [`Commands`] which queue structural changes of the current scene. They are applied after all behaviors
+    /// and scripts have run in the frame.
+    pub fn commands(&mut self) -> Commands<'_> {
+        self.world.commands()
+    }
 }
 
 /// The engine instance which contains the essential components of the engine and runs the game loop.
@@ -79,11 +141,18 @@ pub struct Engine {
     pub mesh_manager: MeshManager,
     /// The texture sampler manager of the engine.
     pub texture_sampler_manager: TextureSamplerManager,
+    /// The model loader used by [`Engine::load_model`]. Register loaders of other formats in it.
+    pub model_loader: ModelLoader,
+    /// The serializer used by [`Engine::save_scene`] and [`Engine::load_scene`].
+    /// Register the assets and user components referenced by scene files in it.
+    pub scene_serializer: SceneSerializer,
     pub(crate) frame_renderer: FrameRenderer,
     pub(crate) global_uniforms: BuiltinUniforms,
     pub(crate) _is_inited: bool,
     pub(crate) _window_input_processor: WindowInputProcessor,
     pub(crate) _graphics_context: Option<GraphicsContext>,
+    pub(crate) schedule: Schedule,
+    pub(crate) plugin_names: Vec<String>,
     _behavior_wrappers: Vec<BehaviorWrapper>,
 }
 
@@ -96,7 +165,7 @@ impl Engine {
 
     /// Create an Engine instance, which is allocated in Heap memory.
     ///
-    /// You can access current World by Engine.
+    /// You can access current World by Engine. The plugins in the options are built here.
     pub fn new(options: EngineOptions) -> Box<Engine> {
         let engine = Engine {
             options,
@@ -109,24 +178,118 @@ impl Engine {
             material_manager: MaterialManager::new(),
             mesh_manager: MeshManager::new(),
             texture_sampler_manager: TextureSamplerManager::new(),
+            model_loader: ModelLoader::new(),
+            scene_serializer: SceneSerializer::new(),
             frame_renderer: FrameRenderer::new(),
             global_uniforms: BuiltinUniforms::new("Global".to_owned()),
             _graphics_context: None,
             _window_input_processor: WindowInputProcessor::new(),
+            schedule: Schedule::new(),
+            plugin_names: Vec::new(),
             _behavior_wrappers: vec![],
             _is_inited: false,
         };
-        let engine_in_heap = Box::new(engine);
+        let mut engine_in_heap = Box::new(engine);
+        for plugin in std::mem::take(&mut engine_in_heap.options.plugins) {
+            engine_in_heap.add_boxed_plugin(plugin);
+        }
         engine_in_heap
     }
 
     /// Launch the Engine instance and run the game loop.
+    /// 
+    /// In headless mode, it renders [`HeadlessOptions::frame_count`] frames, saves the last frame if
+    /// [`HeadlessOptions::capture_path`] is set, and then returns.
     pub fn run(&mut self) {
+        if self.options.headless.is_some() {
+            self.run_headless();
+            return;
+        }
         let event_loop = winit::event_loop::EventLoop::new().unwrap();
         event_loop.set_control_flow(winit::event_loop::ControlFlow::Poll);
         let _ = event_loop.run_app(self);
     }
 
+    fn run_headless(&mut self) {
+        let headless_options = self.options.headless.clone().unwrap_or_default();
+        self.init_headless();
+        for _ in 0..headless_options.frame_count {
+            self.on_update();
+        }
+        if let Some(capture_path) = &headless_options.capture_path {
+            match self.capture_frame() {
+                Ok(captured_frame) => {
+                    if let Err(error) = captured_frame.save(capture_path) {
+                        log::error!("Failed to save captured frame to {}: {}", capture_path.display(), error);
+                    } else {
+                        log::info!("Captured frame is saved to {}", capture_path.display());
+                    }
+                }
+                Err(error) => log::error!("Failed to capture frame: {}", error),
+            }
+        }
+        self.stop();
+    }
+
+    /// Initialize the engine in headless mode without running any frame.
+    /// 
+    /// It is called by [`Engine::run`] automatically. Call it directly to drive frames by [`Engine::render_frame`] yourself, e.g., in tests.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if [`EngineOptions::headless`] is None.
+    pub fn init_headless(&mut self) {
+        if self._is_inited {
+            return;
+        }
+        let headless_options = self.options.headless.clone().expect("EngineOptions::headless must be set in headless mode");
+        self.time.set_frame_delta_override(headless_options.fixed_delta_time);
+        let (width, height) = self.options.window_size.get();
+        let (width, height) = ((width as u32).max(1), (height as u32).max(1));
+        let graphics_context = GraphicsContext::new_headless(
+            width,
+            height,
+            headless_options.color_format,
+            headless_options.force_fallback_adapter,
+        );
+        self.init(graphics_context);
+        // Cameras without color attachment render into the offscreen color target instead of the surface.
+        self.frame_renderer.offscreen_color_target = self.texture_sampler_manager.create_attachment(
+            width,
+            height,
+            1,
+            TextureDimension::D2,
+            1,
+            headless_options.color_format,
+        );
+        self._is_inited = true;
+    }
+
+    /// Update and render one frame in headless mode.
+    /// 
+    /// [`Engine::init_headless`] must be called first.
+    pub fn render_frame(&mut self) {
+        self.on_update();
+    }
+
+    /// Read back the last rendered frame from the offscreen color target in headless mode.
+    pub fn capture_frame(&mut self) -> Result<CapturedFrame, FrameCaptureError> {
+        let graphics_context = match &self._graphics_context {
+            Some(graphics_context) if graphics_context.is_headless() => graphics_context,
+            _ => return Err(FrameCaptureError::NotHeadless),
+        };
+        let offscreen_color_target = self.frame_renderer.offscreen_color_target;
+        if offscreen_color_target == TextureHandle::INVALID {
+            return Err(FrameCaptureError::NoColorTarget);
+        }
+        let texture = self
+            .texture_sampler_manager
+            .get_texture(&offscreen_color_target)
+            .ok_or(FrameCaptureError::NoColorTarget)?;
+        let data = read_texture_2d(graphics_context, texture)?;
+        Ok(CapturedFrame::new(texture.size.width, texture.size.height, texture.format, data))
+    }
+
     /// Get the LogicContext of the engine.
     /// 
     /// The LogicContext provides the essential APIs for engine users to implement their own game logic.
@@ -144,9 +307,117 @@ impl Engine {
             mesh_manager: &mut self.mesh_manager,
             texture_sampler_manager: &mut self.texture_sampler_manager,
             input_manager: &mut self.input_manager,
+            model_loader: &mut self.model_loader,
+            node: None,
+        }
+    }
+
+    /// Save the current scene to a RON file.
+    pub fn save_scene<P: AsRef<std::path::Path>>(&self, path: P) -> Result<(), SceneSerializeError> {
+        self.world.scenes[self.world.current_scene_index].save(path, &self.scene_serializer)
+    }
+
+    /// Load a scene from a RON file and replace the current scene with it.
+    /// 
+    /// If the engine is running, the loaded scene is initialized immediately.
+    pub fn load_scene<P: AsRef<std::path::Path>>(&mut self, path: P) -> Result<(), SceneSerializeError> {
+        let scene = Scene::load(
+            path,
+            &self.scene_serializer,
+            &mut self.shader_manager,
+            &mut self.material_manager,
+            &mut self.mesh_manager,
+        )?;
+        let current_scene_index = self.world.current_scene_index;
+        let mut old_scene = std::mem::replace(self.world.current_scene_mut(), scene);
+        if self._graphics_context.is_some() {
+            old_scene.on_stop(&mut self.time);
+            self.init_scene(current_scene_index);
+        }
+        Ok(())
+    }
+
+    /// Switch to the scene at the index, which becomes the current scene.
+    /// 
+    /// The previous current scene is stopped and unloaded, while the scenes loaded additively stay loaded.
+    /// The new scene is initialized if it was not loaded.
+    /// 
+    /// # Returns
+    /// 
+    /// * `bool` - Returns `false` if the index is invalid.
+    pub fn switch_scene(&mut self, index: usize) -> bool {
+        if index >= self.world.scenes.len() {
+            return false;
+        }
+        let previous_scene_index = self.world.current_scene_index;
+        if previous_scene_index == index {
+            return true;
+        }
+        let is_newly_loaded = self.world.load_scene(index);
+        self.world.current_scene_index = index;
+        self.call_destroyed_scripts(previous_scene_index);
+        self.world.unload_scene(previous_scene_index, &mut self.time);
+        if is_newly_loaded {
+            self.init_scene(index);
+        }
+        true
+    }
+
+    /// Load the scene at the index additively, so that it is updated and rendered together with other loaded scenes.
+    /// 
+    /// # Returns
+    /// 
+    /// * `bool` - Returns `false` if the index is invalid or the scene has been loaded.
+    pub fn load_scene_additive(&mut self, index: usize) -> bool {
+        if self.world.load_scene(index) {
+            self.init_scene(index);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Stop and unload a scene which was loaded additively. The current scene can not be unloaded, switch to another scene instead.
+    /// 
+    /// # Returns
+    /// 
+    /// * `bool` - Returns `true` if the scene is unloaded.
+    pub fn unload_scene(&mut self, index: usize) -> bool {
+        if index != self.world.current_scene_index && self.world.is_scene_loaded(index) {
+            self.call_destroyed_scripts(index);
+        }
+        self.world.unload_scene(index, &mut self.time)
+    }
+
+    /// Initialize a loaded scene if the engine is running. Otherwise it is initialized when the engine starts.
+    fn init_scene(&mut self, index: usize) {
+        if let Some(graphics_context) = &mut self._graphics_context {
+            self.world.init_scene(index, graphics_context, &mut self.texture_sampler_manager, &mut self.shader_manager, &mut self.material_manager, &mut self.time);
         }
     }
 
+    /// Save the node and its descendants in the current scene as a prefab.
+    pub fn create_prefab(&self, node: &NodeHandle) -> Result<Prefab, SceneSerializeError> {
+        Prefab::create(&self.world.scenes[self.world.current_scene_index], node, &self.scene_serializer)
+    }
+
+    /// Instantiate the prefab in the current scene and return the new root node, see [`Scene::instantiate`].
+    pub fn instantiate(&mut self, prefab: &Prefab, parent: Option<NodeHandle>) -> Result<NodeHandle, SceneSerializeError> {
+        let scene = &mut self.world.scenes[self.world.current_scene_index];
+        scene.instantiate(prefab, parent, &self.scene_serializer)
+    }
+
+    /// Instantiate the prefab in the current scene with per-instance overrides, see [`Scene::instantiate_with_overrides`].
+    pub fn instantiate_with_overrides(
+        &mut self,
+        prefab: &Prefab,
+        parent: Option<NodeHandle>,
+        overrides: &PrefabOverrides,
+    ) -> Result<NodeHandle, SceneSerializeError> {
+        let scene = &mut self.world.scenes[self.world.current_scene_index];
+        scene.instantiate_with_overrides(prefab, parent, overrides, &self.scene_serializer, &mut self.material_manager)
+    }
+
     pub(crate) fn graphics_context(&mut self) -> &mut GraphicsContext {
         if let Some(context) = &mut self._graphics_context {
             context
@@ -165,7 +436,9 @@ impl Engine {
     }
 
     pub(crate) fn stop(&mut self) {
+        self.input_manager.stop_recording();
         self.remove_all_behavior();
+        self.destroy_all_scripts();
         self.world.stop(&mut self.time);
         self.event_dispatcher
             .borrow_mut()
@@ -183,11 +456,61 @@ impl Engine {
     }
 
     pub(crate) fn on_update(&mut self) {
+        self.input_manager.on_time_update(&mut self.time);
         self.time.on_update();
-        self.performance_tracker.on_update(self.time.time_data.x);
-        self.execute_behaviors();
-        self.world.updpate(&mut self.time);
+        self.performance_tracker.on_update(self.time.real_elapsed());
+        self.input_manager.on_frame_begin(self.time.delta());
+        for _ in 0..self.time.consume_fixed_steps() {
+            self.execute_fixed_update();
+        }
+        self.run_stage(Stage::PreUpdate);
+        self.run_stage(Stage::Update);
+        self.run_stage(Stage::PostUpdate);
+        self.run_stage(Stage::PreRender);
         self.render();
+        self.run_stage(Stage::PostRender);
+        self.input_manager.on_frame_end();
+    }
+
+    /// Run the systems of a stage in order, and then apply the commands queued by them.
+    pub(crate) fn run_stage(&mut self, stage: Stage) {
+        let mut stage_systems = std::mem::take(&mut self.schedule.stages[stage as usize]);
+        for &index in &stage_systems.order {
+            match &mut stage_systems.systems[index].kind {
+                SystemKind::Builtin(BuiltinSystem::Behaviors) => self.execute_behaviors(),
+                SystemKind::Builtin(BuiltinSystem::TransformPropagation) => {
+                    self.world.updpate(&mut self.time);
+                    self.world.sync_mesh_bounds(&self.mesh_manager);
+                }
+                SystemKind::Builtin(BuiltinSystem::CollectLights) => self.world.prepare_lights(),
+                SystemKind::Custom(system) => system(&mut self.get_logic_context()),
+            }
+        }
+        self.schedule.stages[stage as usize] = stage_systems;
+        self.apply_commands();
+    }
+
+    /// Add a system which runs every frame, see [`System`].
+    /// 
+    /// # Errors
+    /// 
+    /// Returns an error if a system with the same name exists, or the ordering constraints of the system form a cycle.
+    pub fn add_system(&mut self, system: System) -> Result<(), ScheduleError> {
+        self.schedule.add_system(system)
+    }
+
+    /// Remove a system by name. Built-in systems can not be removed.
+    /// 
+    /// # Returns
+    /// 
+    /// * `bool` - Whether the system is removed.
+    pub fn remove_system(&mut self, name: &str) -> bool {
+        self.schedule.remove_system(name)
+    }
+
+    /// The names of the systems of a stage in running order, including the built-in ones.
+    pub fn system_names(&self, stage: Stage) -> Vec<&str> {
+        self.schedule.system_names(stage)
     }
 
     pub(crate) fn render(&mut self) {
@@ -204,6 +527,8 @@ impl Engine {
                     mesh_manager: &mut self.mesh_manager,
                     texture_sampler_manager: &mut self.texture_sampler_manager,
                     input_manager: &mut self.input_manager,
+                    model_loader: &mut self.model_loader,
+                    node: None,
                 };
                 self.frame_renderer.render(&mut logic_context, graphics_context, &mut self._behavior_wrappers);
                 self.graphics_context().request_redraw();
@@ -241,6 +566,8 @@ impl Engine {
                     mesh_manager: &mut self.mesh_manager,
                     texture_sampler_manager: &mut self.texture_sampler_manager,
                     input_manager: &mut self.input_manager,
+                    model_loader: &mut self.model_loader,
+                    node: None,
                 };
                 behavior_wrapper.on_destroy(&mut logic_context);
                 false
@@ -261,6 +588,8 @@ impl Engine {
             mesh_manager: &mut self.mesh_manager,
             texture_sampler_manager: &mut self.texture_sampler_manager,
             input_manager: &mut self.input_manager,
+            model_loader: &mut self.model_loader,
+            node: None,
         };
         for behavior in &mut self._behavior_wrappers {
             behavior.on_destroy(&mut logic_context);
@@ -279,6 +608,8 @@ impl Engine {
                 mesh_manager: &mut self.mesh_manager,
                 texture_sampler_manager: &mut self.texture_sampler_manager,
                 input_manager: &mut self.input_manager,
+                model_loader: &mut self.model_loader,
+                node: None,
             };
             match behavior_wrapper.status {
                 BehaviorStatus::Start => {
@@ -290,5 +621,126 @@ impl Engine {
                 }
             }
         }
+        self.execute_scripts(false);
+        self.apply_commands();
+    }
+
+    /// Run one fixed update of behaviors and scripts which have started.
+    pub(crate) fn execute_fixed_update(&mut self) {
+        self.world.save_previous_transforms();
+        for behavior_wrapper in &mut self._behavior_wrappers {
+            if let BehaviorStatus::Update = behavior_wrapper.status {
+                let mut logic_context = LogicContext {
+                    world: &mut self.world,
+                    time: &mut self.time,
+                    performance_tracker: &mut self.performance_tracker,
+                    shader_manager: &mut self.shader_manager,
+                    material_manager: &mut self.material_manager,
+                    mesh_manager: &mut self.mesh_manager,
+                    texture_sampler_manager: &mut self.texture_sampler_manager,
+                    input_manager: &mut self.input_manager,
+                    model_loader: &mut self.model_loader,
+                    node: None,
+                };
+                behavior_wrapper.on_fixed_update(&mut logic_context);
+            }
+        }
+        self.execute_scripts(true);
+        self.apply_commands();
+    }
+
+    /// Run the scripts of all loaded scenes after calling `on_destroy` of the scripts of destroyed nodes.
+    /// 
+    /// In a fixed update, only `on_fixed_update` of started scripts is called.
+    pub(crate) fn execute_scripts(&mut self, is_fixed_update: bool) {
+        for scene_index in self.world.loaded_scene_indices.clone() {
+            self.call_destroyed_scripts(scene_index);
+            let scene = &self.world.scenes[scene_index];
+            let script_nodes: Vec<NodeHandle> = scene
+                .query::<&Script>()
+                .filter(|(node, script)| {
+                    (script.is_started || !is_fixed_update)
+                        && scene.get_node(node).is_some_and(|node| node.enabled_in_hierarchy)
+                })
+                .map(|(node, _)| node)
+                .collect();
+            for node in script_nodes {
+                self.execute_script(scene_index, node, is_fixed_update);
+            }
+        }
+    }
+
+    fn execute_script(&mut self, scene_index: usize, node: NodeHandle, is_fixed_update: bool) {
+        let Some(script) = self.world.scenes[scene_index].get_component_mut::<Script>(&node) else {
+            return;
+        };
+        let Some(mut behavior) = script.behavior.take() else {
+            return;
+        };
+        let is_started = std::mem::replace(&mut script.is_started, true);
+        let mut logic_context = self.get_logic_context();
+        logic_context.node = Some(node);
+        if is_fixed_update {
+            behavior.on_fixed_update(&mut logic_context);
+        } else if is_started {
+            behavior.on_update(&mut logic_context);
+        } else {
+            behavior.on_start(&mut logic_context);
+        }
+        if let Some(script) = self.world.scenes[scene_index].get_component_mut::<Script>(&node)
+            && script.behavior.is_none()
+        {
+            script.behavior = Some(behavior);
+        } else {
+            // The node or the script was destroyed by the behavior itself.
+            let mut logic_context = self.get_logic_context();
+            logic_context.node = Some(node);
+            behavior.on_destroy(&mut logic_context);
+        }
+    }
+
+    /// Apply the queued commands, then call `on_destroy` of the scripts of the nodes destroyed since the last sync point.
+    fn apply_commands(&mut self) {
+        self.world.apply_commands();
+        for scene_index in self.world.loaded_scene_indices.clone() {
+            self.call_destroyed_scripts(scene_index);
+        }
+    }
+
+    fn call_destroyed_scripts(&mut self, scene_index: usize) {
+        let destroyed_scripts = std::mem::take(&mut self.world.scenes[scene_index].destroyed_scripts);
+        for (node, mut script) in destroyed_scripts {
+            if let Some(behavior) = &mut script.behavior {
+                let mut logic_context = self.get_logic_context();
+                logic_context.node = Some(node);
+                behavior.on_destroy(&mut logic_context);
+            }
+        }
+    }
+
+    /// Call `on_destroy` of all started scripts of the loaded scenes when the engine stops.
+    fn destroy_all_scripts(&mut self) {
+        for scene_index in self.world.loaded_scene_indices.clone() {
+            self.call_destroyed_scripts(scene_index);
+            let script_nodes: Vec<NodeHandle> = self.world.scenes[scene_index]
+                .query::<&Script>()
+                .filter(|(_, script)| script.is_started)
+                .map(|(node, _)| node)
+                .collect();
+            for node in script_nodes {
+                let Some(script) = self.world.scenes[scene_index].get_component_mut::<Script>(&node) else {
+                    continue;
+                };
+                script.is_started = false;
+                if let Some(mut behavior) = script.behavior.take() {
+                    let mut logic_context = self.get_logic_context();
+                    logic_context.node = Some(node);
+                    behavior.on_destroy(&mut logic_context);
+                    if let Some(script) = self.world.scenes[scene_index].get_component_mut::<Script>(&node) {
+                        script.behavior = Some(behavior);
+                    }
+                }
+            }
+        }
     }
 }
\ No newline at end of file
@@ -35,9 +35,9 @@ impl Engine {
         event: winit::event::WindowEvent,
     ) {
         {
-            if let Some(ui_renderer) = &mut self.frame_renderer.ui_renderer {
-                let graphics_context = self._graphics_context.as_mut().unwrap();
-                ui_renderer.handle_input(graphics_context.main_window().get_ref(), &event);
+            if let Some(ui_renderer) = &mut self.frame_renderer.ui_renderer
+                && let Some(main_window) = self._graphics_context.as_ref().unwrap().main_window() {
+                ui_renderer.handle_input(main_window.get_ref(), &event);
             }
         }
         match event {
@@ -58,11 +58,12 @@ impl Engine {
                 self.on_update();
             }
             others => {
-                let is_ui_interacting = {
+                let (is_ui_interacting, is_ui_using_keyboard) = {
                     if let Some(ui_renderer) = &self.frame_renderer.ui_renderer {
-                        ui_renderer.state().egui_ctx().wants_pointer_input()
+                        let egui_ctx = ui_renderer.state().egui_ctx();
+                        (egui_ctx.wants_pointer_input(), egui_ctx.wants_keyboard_input())
                     } else {
-                        false
+                        (false, false)
                     }
                 };
                 self._window_input_processor.process_window_input(
@@ -71,6 +72,8 @@ impl Engine {
                     self._graphics_context.as_mut().unwrap().dpi(),
                     &mut self.input_manager,
                     is_ui_interacting,
+                    is_ui_using_keyboard,
+                    self.options.exit_on_escape,
                 );
             }
         }
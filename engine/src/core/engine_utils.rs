@@ -14,7 +14,7 @@
 //! ```
 
 use crate::{
-    assets::{Material, MaterialHandle, ModelLoader, ModelLoaderTrait, ShaderHandle},
+    assets::{Material, MaterialHandle, ShaderHandle},
     core::{Engine, LogicContext, Node, NodeHandle}, math::Vec3,
     prelude::{CameraController, CameraTarget, Component, Light, Transform}
 };
@@ -141,9 +141,8 @@ impl Engine {
     /// 
     /// * `Result<NodeHandle, Box<dyn std::error::Error>>` - The handle of the loaded model node if successful, or an error.
     pub fn load_model(&mut self, path: &str) -> Result<NodeHandle, Box<dyn std::error::Error>> {
-        let mut logic_context = self.get_logic_context();
-        let model_loader = ModelLoader::new();
-        model_loader.load(&mut logic_context, path)
+        let model_loader = self.model_loader.find_loader(path)?;
+        model_loader.load(&mut self.get_logic_context(), path)
     }
 
     /// Get a light component by node handle.
@@ -490,7 +489,7 @@ impl<'a> LogicContext<'a> {
     /// 
     /// * `Result<NodeHandle, Box<dyn std::error::Error>>` - The handle of the loaded model node if successful, or an error.
     pub fn load_model(&mut self, path: &str) -> Result<NodeHandle, Box<dyn std::error::Error>> {
-        let model_loader = ModelLoader::new();
+        let model_loader = self.model_loader.find_loader(path)?;
         model_loader.load(self, path)
     }
 
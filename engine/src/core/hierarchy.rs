@@ -0,0 +1,178 @@
+use std::collections::VecDeque;
+
+use crate::core::{LayerMask, NodeHandle, Scene};
+
+/// Iterates over a hierarchy in depth-first pre-order, see [`Scene::iter_depth_first`].
+pub struct DepthFirstIter<'a> {
+    scene: &'a Scene,
+    stack: Vec<NodeHandle>,
+}
+
+impl Iterator for DepthFirstIter<'_> {
+    type Item = NodeHandle;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(node_id) = self.stack.pop() {
+            if let Some(node) = self.scene.get_node(&node_id) {
+                if let Some(children) = &node.children {
+                    self.stack.extend(children.iter().rev());
+                }
+                return Some(node_id);
+            }
+        }
+        None
+    }
+}
+
+/// Iterates over a hierarchy level by level, see [`Scene::iter_breadth_first`].
+pub struct BreadthFirstIter<'a> {
+    scene: &'a Scene,
+    queue: VecDeque<NodeHandle>,
+}
+
+impl Iterator for BreadthFirstIter<'_> {
+    type Item = NodeHandle;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(node_id) = self.queue.pop_front() {
+            if let Some(node) = self.scene.get_node(&node_id) {
+                if let Some(children) = &node.children {
+                    self.queue.extend(children.iter());
+                }
+                return Some(node_id);
+            }
+        }
+        None
+    }
+}
+
+/// Node lookup and hierarchy iteration.
+///
+/// Only the nodes in the hierarchy of [`Scene::root_nodes`] are visited, so nodes which have been created but not
+/// added to the scene are not found.
+impl Scene {
+    fn start_nodes(&self, root: Option<&NodeHandle>) -> Vec<NodeHandle> {
+        match root {
+            Some(root) => vec![*root],
+            None => self.root_nodes.clone(),
+        }
+    }
+
+    /// Iterate over a node and its descendants in depth-first pre-order, or over the whole scene if `root` is None.
+    /// Children are visited in order.
+    pub fn iter_depth_first(&self, root: Option<&NodeHandle>) -> DepthFirstIter<'_> {
+        let mut stack = self.start_nodes(root);
+        stack.reverse();
+        DepthFirstIter { scene: self, stack }
+    }
+
+    /// Iterate over a node and its descendants level by level, or over the whole scene if `root` is None.
+    pub fn iter_breadth_first(&self, root: Option<&NodeHandle>) -> BreadthFirstIter<'_> {
+        BreadthFirstIter { scene: self, queue: self.start_nodes(root).into() }
+    }
+
+    /// Find the first node with the name in depth-first order.
+    pub fn find_by_name(&self, name: &str) -> Option<NodeHandle> {
+        self.iter_depth_first(None).find(|node_id| self.get_node_forcely(node_id).name == name)
+    }
+
+    /// Find the first descendant of a node with the name in depth-first order. The node itself is excluded.
+    pub fn find_descendant(&self, node_id: &NodeHandle, name: &str) -> Option<NodeHandle> {
+        self.iter_depth_first(Some(node_id))
+            .skip(1)
+            .find(|descendant| self.get_node_forcely(descendant).name == name)
+    }
+
+    /// Find a node by a path of names separated by `/`, e.g., `"Root/Body/Head"`.
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - The node which the path is relative to, so the first name is one of its children. If it is None,
+    ///   the first name is one of the root nodes of the scene.
+    /// * `path` - The path. Empty names and `.` are skipped, and `..` is the parent.
+    pub fn find_by_path(&self, root: Option<&NodeHandle>, path: &str) -> Option<NodeHandle> {
+        let mut current = root.copied();
+        for name in path.split('/').filter(|name| !name.is_empty() && *name != ".") {
+            current = if name == ".." {
+                Some(self.get_node(&current?)?.parent?)
+            } else {
+                let siblings = match &current {
+                    Some(node_id) => self.get_node(node_id)?.children.as_deref().unwrap_or_default(),
+                    None => &self.root_nodes,
+                };
+                Some(*siblings.iter().find(|sibling| self.get_node(sibling).is_some_and(|node| node.name == name))?)
+            };
+        }
+        current
+    }
+
+    /// Find the first node with the tag in depth-first order.
+    pub fn find_with_tag(&self, tag: &str) -> Option<NodeHandle> {
+        self.iter_depth_first(None).find(|node_id| self.get_node_forcely(node_id).has_tag(tag))
+    }
+
+    /// Find all nodes with the tag in depth-first order.
+    pub fn find_all_with_tag(&self, tag: &str) -> Vec<NodeHandle> {
+        self.iter_depth_first(None)
+            .filter(|node_id| self.get_node_forcely(node_id).has_tag(tag))
+            .collect()
+    }
+
+    /// Find all nodes whose layers are in the mask in depth-first order, e.g., to filter the targets of queries.
+    pub fn find_all_in_layers(&self, layer_mask: LayerMask) -> Vec<NodeHandle> {
+        self.iter_depth_first(None)
+            .filter(|node_id| layer_mask.contains(self.get_node_forcely(node_id).layer))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Layer;
+
+    #[test]
+    fn test_hierarchy_lookup() {
+        let mut scene = Scene::new();
+        let root = scene.create_node("Root");
+        let body = scene.create_node("Body");
+        let head = scene.create_node("Head");
+        let arm = scene.create_node("Arm");
+        let other_head = scene.create_node("Head");
+        scene.add(root);
+        scene.add(other_head);
+        scene.set_parent(&body, Some(root), false);
+        scene.set_parent(&arm, Some(root), false);
+        scene.set_parent(&head, Some(body), false);
+        scene.get_node_mut_forcely(&arm).add_tag("Enemy");
+        scene.get_node_mut_forcely(&other_head).add_tag("Enemy");
+        // Not in the hierarchy of the scene.
+        let orphan = scene.create_node("Orphan");
+
+        assert_eq!(scene.iter_depth_first(None).collect::<Vec<_>>(), vec![root, body, head, arm, other_head]);
+        assert_eq!(scene.iter_breadth_first(None).collect::<Vec<_>>(), vec![root, other_head, body, arm, head]);
+        assert_eq!(scene.iter_breadth_first(Some(&body)).collect::<Vec<_>>(), vec![body, head]);
+
+        assert_eq!(scene.find_by_name("Head"), Some(head));
+        assert_eq!(scene.find_by_name("Orphan"), None);
+        assert_eq!(scene.find_descendant(&root, "Head"), Some(head));
+        assert_eq!(scene.find_descendant(&root, "Root"), None);
+
+        assert_eq!(scene.find_by_path(None, "Root/Body/Head"), Some(head));
+        assert_eq!(scene.find_by_path(None, "Head"), Some(other_head));
+        assert_eq!(scene.find_by_path(Some(&root), "Body/Head"), Some(head));
+        assert_eq!(scene.find_by_path(Some(&head), "../../Arm"), Some(arm));
+        assert_eq!(scene.find_by_path(Some(&root), "Head"), None);
+        assert_eq!(scene.find_by_path(Some(&orphan), ""), Some(orphan));
+
+        assert_eq!(scene.find_all_with_tag("Enemy"), vec![arm, other_head]);
+        assert_eq!(scene.find_with_tag("Enemy"), Some(arm));
+        assert!(scene.get_node_mut_forcely(&arm).remove_tag("Enemy"));
+        assert_eq!(scene.find_all_with_tag("Enemy"), vec![other_head]);
+
+        scene.get_node_mut_forcely(&arm).layer = Layer::Custom1;
+        scene.get_node_mut_forcely(&head).layer = Layer::UI;
+        assert_eq!(scene.find_all_in_layers(Layer::Custom1 | Layer::UI), vec![head, arm]);
+        assert_eq!(scene.find_all_in_layers(LayerMask::NONE), vec![]);
+    }
+}
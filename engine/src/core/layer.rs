@@ -1,19 +1,66 @@
 use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign};
 
-/// Layer enum
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum LayerError {
+    #[error("Layer index {0} is out of range, there are {count} layers", count = Layer::COUNT)]
+    IndexOutOfRange(u8),
+    #[error("A layer named {0} already exists")]
+    DuplicateName(String),
+    #[error("Layer {0} has been named {1}")]
+    LayerTaken(u8, String),
+    #[error("No free layer for {0}")]
+    NoFreeLayer(String),
+    #[error("No layer named {0}")]
+    UnknownLayer(String),
+}
+
+/// Layer
+///
+/// The layer of the node, which is one of [`Layer::COUNT`] layers. Layers are used to filter nodes, e.g., by
+/// [`crate::prelude::Camera::visible_layers`] and [`crate::prelude::Light::culling_mask`].
 ///
-/// The layer of the node.
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum Layer {
-    Default = 1 << 0,
-    UI = 1 << 1,
-    Gizmo = 1 << 2,
-    RenderTarget = 1 << 3,
-    Custom1 = 1 << 4,
-    Custom2 = 1 << 5,
-    Custom3 = 1 << 6,
-    Custom4 = 1 << 7,
-    All = 0xffffffff,
+/// The first layers are built-in, and the others can be named by [`Layers`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "u8", into = "u8")]
+pub struct Layer(u8);
+
+#[allow(non_upper_case_globals)]
+impl Layer {
+    /// The count of layers.
+    pub const COUNT: usize = 32;
+
+    pub const Default: Layer = Layer(0);
+    pub const UI: Layer = Layer(1);
+    pub const Gizmo: Layer = Layer(2);
+    pub const RenderTarget: Layer = Layer(3);
+    pub const Custom1: Layer = Layer(4);
+    pub const Custom2: Layer = Layer(5);
+    pub const Custom3: Layer = Layer(6);
+    pub const Custom4: Layer = Layer(7);
+
+    /// The names of the built-in layers, in index order.
+    const BUILTIN_NAMES: [&'static str; 4] = ["Default", "UI", "Gizmo", "RenderTarget"];
+
+    /// Get the layer at an index.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Layer>` - The layer, or None if the index is not less than [`Layer::COUNT`].
+    pub fn new(index: u8) -> Option<Self> {
+        ((index as usize) < Self::COUNT).then_some(Layer(index))
+    }
+
+    pub fn index(&self) -> u8 {
+        self.0
+    }
+
+    /// The bit of the layer in a [`LayerMask`].
+    pub fn bit(&self) -> u32 {
+        1 << self.0
+    }
 }
 
 impl Default for Layer {
@@ -22,9 +69,23 @@ impl Default for Layer {
     }
 }
 
+impl TryFrom<u8> for Layer {
+    type Error = LayerError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Layer::new(value).ok_or(LayerError::IndexOutOfRange(value))
+    }
+}
+
+impl From<Layer> for u8 {
+    fn from(value: Layer) -> Self {
+        value.0
+    }
+}
+
 impl From<Layer> for u32 {
     fn from(value: Layer) -> Self {
-        value as u32
+        value.bit()
     }
 }
 
@@ -32,12 +93,93 @@ impl BitOr for Layer {
     type Output = LayerMask;
 
     fn bitor(self, rhs: Self) -> Self::Output {
-        LayerMask(self as u32 | rhs as u32)
+        LayerMask(self.bit() | rhs.bit())
+    }
+}
+
+/// The names of layers, which are registered at startup, e.g., by `engine.world.layers.register("Water")`.
+///
+/// The built-in layers are named after their constants, e.g., `"Default"` and `"UI"`, while the others have no
+/// name until they are registered.
+pub struct Layers {
+    names: [Option<String>; Layer::COUNT],
+}
+
+impl Default for Layers {
+    fn default() -> Self {
+        let mut names: [Option<String>; Layer::COUNT] = Default::default();
+        for (name, builtin_name) in names.iter_mut().zip(Layer::BUILTIN_NAMES) {
+            *name = Some(builtin_name.to_owned());
+        }
+        Self { names }
+    }
+}
+
+impl Layers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Name the first layer which has no name.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Layer, LayerError>` - The named layer, or an error if the name exists or all layers are named.
+    pub fn register(&mut self, name: impl Into<String>) -> Result<Layer, LayerError> {
+        let name = name.into();
+        if self.layer(&name).is_some() {
+            return Err(LayerError::DuplicateName(name));
+        }
+        let Some(index) = self.names.iter().position(Option::is_none) else {
+            return Err(LayerError::NoFreeLayer(name));
+        };
+        self.names[index] = Some(name);
+        Ok(Layer(index as u8))
+    }
+
+    /// Name a layer which has no name, e.g., to keep the layers of saved scenes stable.
+    pub fn register_at(&mut self, layer: Layer, name: impl Into<String>) -> Result<(), LayerError> {
+        let name = name.into();
+        if let Some(old_name) = &self.names[layer.0 as usize] {
+            return Err(LayerError::LayerTaken(layer.0, old_name.clone()));
+        }
+        if self.layer(&name).is_some() {
+            return Err(LayerError::DuplicateName(name));
+        }
+        self.names[layer.0 as usize] = Some(name);
+        Ok(())
+    }
+
+    /// Find a layer by name.
+    pub fn layer(&self, name: &str) -> Option<Layer> {
+        self.names
+            .iter()
+            .position(|layer_name| layer_name.as_deref() == Some(name))
+            .map(|index| Layer(index as u8))
+    }
+
+    /// The name of a layer, or None if it has not been named.
+    pub fn name(&self, layer: Layer) -> Option<&str> {
+        self.names[layer.0 as usize].as_deref()
+    }
+
+    /// The mask of the layers with the names.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<LayerMask, LayerError>` - The mask, or an error with the first name which is not registered.
+    pub fn mask(&self, names: &[&str]) -> Result<LayerMask, LayerError> {
+        names.iter().try_fold(LayerMask::NONE, |layer_mask, name| {
+            let layer = self.layer(name).ok_or_else(|| LayerError::UnknownLayer((*name).to_owned()))?;
+            Ok(layer_mask | layer.into())
+        })
     }
 }
 
 /// LayerMask
-#[derive(Debug, Clone, Copy)]
+///
+/// A set of layers, one bit per layer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct LayerMask(u32);
 
 impl Default for LayerMask {
@@ -58,7 +200,17 @@ impl From<Layer> for LayerMask {
     }
 }
 
+impl FromIterator<Layer> for LayerMask {
+    fn from_iter<T: IntoIterator<Item = Layer>>(iter: T) -> Self {
+        LayerMask(iter.into_iter().fold(0, |layer_mask, layer| layer_mask | layer.bit()))
+    }
+}
+
 impl LayerMask {
+    /// The mask of all layers.
+    pub const ALL: LayerMask = LayerMask(u32::MAX);
+    /// The mask of no layer.
+    pub const NONE: LayerMask = LayerMask(0);
 
     pub fn new(layer_mask: u32) -> Self {
         LayerMask(layer_mask)
@@ -73,15 +225,15 @@ impl LayerMask {
     }
 
     pub fn contains(&self, layer: Layer) -> bool {
-        self.0 & (layer as u32) != 0
+        self.0 & layer.bit() != 0
     }
 
     pub fn insert(&mut self, layer: Layer) {
-        self.0 |= layer as u32;
+        self.0 |= layer.bit();
     }
 
     pub fn remove(&mut self, layer: Layer) {
-        self.0 &= !(layer as u32);
+        self.0 &= !layer.bit();
     }
 }
 
@@ -111,4 +263,34 @@ impl BitAndAssign for LayerMask {
     fn bitand_assign(&mut self, rhs: LayerMask) {
         self.0 &= rhs.0;
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_named_layers() {
+        let mut layers = Layers::new();
+        assert_eq!(layers.layer("UI"), Some(Layer::UI));
+        assert_eq!(layers.register("Water"), Ok(Layer::Custom1));
+        assert_eq!(layers.register("Water"), Err(LayerError::DuplicateName("Water".to_owned())));
+        let ragdoll = Layer::new(31).unwrap();
+        layers.register_at(ragdoll, "Ragdoll").unwrap();
+        assert_eq!(layers.register_at(ragdoll, "Trigger"), Err(LayerError::LayerTaken(31, "Ragdoll".to_owned())));
+        assert_eq!(layers.name(ragdoll), Some("Ragdoll"));
+        assert_eq!(layers.name(Layer::Custom2), None);
+        assert_eq!(Layer::new(32), None);
+
+        let layer_mask = layers.mask(&["Water", "Ragdoll"]).unwrap();
+        assert_eq!(layer_mask.get(), 1 << 4 | 1 << 31);
+        assert!(layer_mask.contains(ragdoll) && !layer_mask.contains(Layer::Default));
+        assert_eq!(layers.mask(&["Lava"]), Err(LayerError::UnknownLayer("Lava".to_owned())));
+        assert_eq!([Layer::Custom1, ragdoll].into_iter().collect::<LayerMask>(), layer_mask);
+
+        for index in 0..(Layer::COUNT - 6) {
+            layers.register(format!("Layer{}", index)).unwrap();
+        }
+        assert_eq!(layers.register("Full"), Err(LayerError::NoFreeLayer("Full".to_owned())));
+    }
+}
@@ -9,10 +9,30 @@ pub mod engine_loop;
 pub mod engine_utils;
 pub mod node_arena;
 pub mod arena;
+pub mod scene_serializer;
+pub mod prefab;
+pub mod query;
+pub mod commands;
+pub mod schedule;
+pub mod plugin;
+pub mod transform_hierarchy;
+pub mod hierarchy;
+pub mod scene_event;
+pub mod node_bounds;
+pub mod spatial_index;
 
 pub use engine::*;
 pub use scene::*;
 pub use node::*;
 pub use world::*;
 pub use layer::*;
-pub use node_arena::*;
\ No newline at end of file
+pub use node_arena::*;
+pub use scene_serializer::*;
+pub use prefab::*;
+pub use query::*;
+pub use commands::*;
+pub use schedule::*;
+pub use plugin::*;
+pub use hierarchy::*;
+pub use scene_event::*;
+pub use spatial_index::*;
\ No newline at end of file
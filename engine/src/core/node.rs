@@ -1,7 +1,7 @@
-use ahash::AHashMap;
+use std::collections::BTreeSet;
 
 use crate::{
-    components::transform::Transform, core::layer::Layer, math::Mat4, prelude::{ComponentId, ComponentTypeId}
+    components::transform::Transform, core::layer::Layer, math::Mat4
 };
 
 /// Tag type for NodeHandle.
@@ -31,12 +31,13 @@ pub struct Node {
     /// The layer of the node.
     pub layer: Layer,
 
+    /// The tags of the node, see [`crate::core::Scene::find_all_with_tag`]. They are sorted to keep scene files stable.
+    pub(crate) tags: BTreeSet<String>,
+
     // built in components
     /// A node always has a Transform component.
     pub transform: Transform,
-
-    /// Other components attached to the node.
-    pub(crate) components: AHashMap<ComponentTypeId, ComponentId>,
+    // Other components are kept in the component storages of the scene, indexed by the node handle.
 }
 
 impl PartialEq for Node {
@@ -56,7 +57,7 @@ impl Default for Node {
             enabled: true,
             enabled_in_hierarchy: true,
             layer: Layer::default(),
-            components: AHashMap::new(),
+            tags: BTreeSet::new(),
         }
     }
 }
@@ -85,12 +86,38 @@ impl Node {
         }
     }
 
-    /// Lifecycle method called when the node is updated.
+    /// Add a tag to the node, e.g., "Enemy".
+    /// # Returns
+    /// 
+    /// * `bool` - Returns false if the node already has the tag.
+    pub fn add_tag(&mut self, tag: impl Into<String>) -> bool {
+        self.tags.insert(tag.into())
+    }
+
+    /// Remove a tag from the node.
+    /// # Returns
+    /// 
+    /// * `bool` - Returns false if the node does not have the tag.
+    pub fn remove_tag(&mut self, tag: &str) -> bool {
+        self.tags.remove(tag)
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.contains(tag)
+    }
+
+    /// The tags of the node in sorted order.
+    pub fn tags(&self) -> impl Iterator<Item = &str> {
+        self.tags.iter().map(String::as_str)
+    }
+
+    /// Lifecycle method called when the transform of the node needs to be updated.
     /// # Arguments
     /// 
     /// * `parent_model_matrix` - The model matrix of the parent node, if any.
-    pub(crate) fn on_update(&mut self, parent_model_matrix: Option<Mat4>) {
-        self.transform.update_model_matrix(parent_model_matrix);
+    /// * `interpolation_alpha` - See [`crate::time::Time::interpolation_alpha`].
+    pub(crate) fn on_update(&mut self, parent_model_matrix: Option<Mat4>, interpolation_alpha: f32) {
+        self.transform.update_model_matrix(parent_model_matrix, interpolation_alpha);
     }
 
     /// Check if the node is enabled in hierarchy.
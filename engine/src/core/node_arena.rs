@@ -1,6 +1,6 @@
 use crate::core::{Node, NodeHandle};
 
-struct NodeSlot {
+pub(crate) struct NodeSlot {
     node: Option<Node>,
     generation: u32,
 }
@@ -51,6 +51,40 @@ impl NodeArena {
         }
     }
 
+    /// Reserve a handle for a node which is created later by [`NodeArena::create_reserved_node`].
+    /// 
+    /// # Returns
+    /// 
+    /// * `NodeHandle` - The reserved handle. No node can be got by it until the node is created.
+    pub fn reserve_node(&mut self) -> NodeHandle {
+        if let Some(index) = self.free_list.pop() {
+            NodeHandle::new_with_generation(index as u64, self.nodes[index as usize].generation)
+        } else {
+            let id = self.nodes.len() as u64;
+            self.nodes.push(NodeSlot { node: None, generation: 0 });
+            NodeHandle::new(id)
+        }
+    }
+
+    /// Create a node with the given name in the slot reserved by [`NodeArena::reserve_node`].
+    /// 
+    /// # Returns
+    /// 
+    /// * `bool` - Returns `true` if the handle is reserved and the node was not created yet.
+    pub fn create_reserved_node(&mut self, node_handle: &NodeHandle, name: impl Into<String>) -> bool {
+        match self.nodes.get_mut(node_handle.id as usize) {
+            Some(slot) if slot.generation == node_handle.generation && slot.node.is_none() => {
+                slot.node = Some(Node {
+                    id: *node_handle,
+                    name: name.into(),
+                    ..Default::default()
+                });
+                true
+            }
+            _ => false,
+        }
+    }
+
     /// Get a reference to the node with the given ID.
     /// 
     /// # Panics
@@ -147,7 +181,10 @@ impl NodeArena {
     /// * `bool` - Returns `true` if the attachment was successful, `false` otherwise.
     pub fn attach_to_parent(&mut self, parent: NodeHandle, child: &NodeHandle) -> bool {
         if self.detach_from_parent(child) {
-            self.get_mut_forcely(child).parent = Some(parent);
+            let child_node = self.get_mut_forcely(child);
+            child_node.parent = Some(parent);
+            // The model matrix depends on the new parent.
+            child_node.transform.is_dirty = true;
             if let Some(parent) = self.get_mut(&parent) {
                 if let Some(children) = &mut parent.children {
                     children.push(*child);
@@ -180,7 +217,9 @@ impl NodeArena {
             siblings.retain(|&c| c != *child_id);
         }
         if let Some(child) = self.get_mut(child_id) {
-            child.parent = None;
+            if child.parent.take().is_some() {
+                child.transform.is_dirty = true;
+            }
             true
         } else {
             log::warn!("Invalid NodeID ({}) when NodeArena detach()", child_id);
@@ -224,4 +263,40 @@ impl NodeArena {
             false
         }        
     }
+
+    /// Iterate over all alive nodes in slot order.
+    pub fn iter(&self) -> impl Iterator<Item = &Node> {
+        self.nodes.iter().filter_map(|slot| slot.node.as_ref())
+    }
+
+    /// Iterate mutably over all alive nodes in slot order.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Node> {
+        self.nodes.iter_mut().filter_map(|slot| slot.node.as_mut())
+    }
+
+    /// Raw pointer to the node slots and the number of slots, used by queries to borrow several nodes at the same
+    /// time.
+    pub(crate) fn as_mut_ptr(&mut self) -> (*mut NodeSlot, usize) {
+        (self.nodes.as_mut_ptr(), self.nodes.len())
+    }
+
+    /// Pointer to the node in slots returned by [`NodeArena::as_mut_ptr`].
+    /// 
+    /// # Safety
+    /// 
+    /// The slots must be valid, and the arena must not be modified while the pointer is used.
+    pub(crate) unsafe fn node_ptr(slots: (*mut NodeSlot, usize), node_handle: &NodeHandle) -> Option<*mut Node> {
+        let (ptr, len) = slots;
+        if node_handle.id as usize >= len {
+            return None;
+        }
+        unsafe {
+            let slot = ptr.add(node_handle.id as usize);
+            if (*slot).generation != node_handle.generation {
+                None
+            } else {
+                (*slot).node.as_mut().map(|node| node as *mut Node)
+            }
+        }
+    }
 }
@@ -0,0 +1,104 @@
+use crate::{
+    assets::MeshManager,
+    components::mesh_renderer::MeshRenderer,
+    core::{NodeHandle, Scene},
+    math::{Aabb, BoundingSphere},
+};
+
+/// World-space bounds of nodes, computed from the bounds of their meshes, see [`MeshRenderer::local_bounds`].
+///
+/// Like [`Scene::world_matrix`], they are correct even if the transforms have been changed in this frame.
+impl Scene {
+    /// The bounding box of the mesh of a node in world space, or None if the node has no mesh with bounds.
+    pub fn world_bounds(&self, node_id: &NodeHandle) -> Option<Aabb> {
+        let local_bounds = self.get_component::<MeshRenderer>(node_id)?.local_bounds()?;
+        Some(local_bounds.transform(&self.world_matrix(node_id)?))
+    }
+
+    /// The bounding sphere of the mesh of a node in world space, or None if the node has no mesh with bounds.
+    pub fn world_bounding_sphere(&self, node_id: &NodeHandle) -> Option<BoundingSphere> {
+        let local_bounding_sphere = self.get_component::<MeshRenderer>(node_id)?.local_bounding_sphere()?;
+        Some(local_bounding_sphere.transform(&self.world_matrix(node_id)?))
+    }
+
+    /// The bounding box of the meshes of a node and its descendants in world space, e.g., to frame them by a camera.
+    /// Disabled nodes are included.
+    pub fn subtree_bounds(&self, node_id: &NodeHandle) -> Option<Aabb> {
+        self.iter_depth_first(Some(node_id))
+            .filter_map(|node| self.world_bounds(&node))
+            .reduce(|bounds, other| bounds.merge(&other))
+    }
+
+    /// Copy the bounds of the meshes into the mesh renderers, so that they follow the assigned and modified meshes.
+    /// Only the mesh renderers whose bounds are out of date are accessed mutably.
+    pub(crate) fn sync_mesh_bounds(&mut self, mesh_manager: &MeshManager) {
+        let out_of_date_nodes: Vec<NodeHandle> = self
+            .query::<&MeshRenderer>()
+            .filter(|(_, mesh_renderer)| {
+                mesh_manager.get_mesh(&mesh_renderer.mesh).is_some_and(|mesh| mesh_renderer.needs_bounds_sync(mesh))
+            })
+            .map(|(node_id, _)| node_id)
+            .collect();
+        for node_id in out_of_date_nodes {
+            if let Some(mesh_renderer) = self.get_component_mut::<MeshRenderer>(&node_id)
+                && let Some(mesh) = mesh_manager.get_mesh(&mesh_renderer.mesh)
+            {
+                mesh_renderer.sync_bounds(mesh);
+                self.spatial_index_changes.push(node_id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assets::{Mesh, meshes::primitives::cuboid::Cuboid}, math::Vec3};
+
+    #[test]
+    fn test_node_bounds() {
+        let mut mesh_manager = MeshManager::new();
+        let cube = mesh_manager.add_mesh(Cuboid::default().into());
+        assert_eq!(mesh_manager.get_mesh_forcely(&cube).bounds(), Some(Aabb::new(Vec3::splat(-0.5), Vec3::splat(0.5))));
+
+        let mut scene = Scene::new();
+        let parent = scene.create_node("Parent");
+        let child = scene.create_node("Child");
+        scene.add(parent);
+        scene.attach_to_parent(&child, parent);
+        scene.add_component(&parent, MeshRenderer::new(cube, vec![]));
+        scene.add_component(&child, MeshRenderer::new(cube, vec![]));
+        assert_eq!(scene.world_bounds(&parent), None);
+
+        scene.sync_mesh_bounds(&mesh_manager);
+        scene.get_node_mut_forcely(&parent).transform.set_position(Vec3::new(10.0, 0.0, 0.0));
+        scene.get_node_mut_forcely(&child).transform.set_position(Vec3::new(0.0, 2.0, 0.0));
+        scene.get_node_mut_forcely(&child).transform.set_scale(Vec3::splat(2.0));
+        assert_eq!(scene.world_bounds(&child), Some(Aabb::new(Vec3::new(9.0, 1.0, -1.0), Vec3::new(11.0, 3.0, 1.0))));
+        assert_eq!(
+            scene.subtree_bounds(&parent),
+            Some(Aabb::new(Vec3::new(9.0, -0.5, -1.0), Vec3::new(11.0, 3.0, 1.0)))
+        );
+        let bounding_sphere = scene.world_bounding_sphere(&child).unwrap();
+        assert_eq!(bounding_sphere.center, Vec3::new(10.0, 2.0, 0.0));
+        assert!((bounding_sphere.radius - 3.0f32.sqrt()).abs() < 1e-5);
+
+        // Modified meshes are synced again.
+        let mesh: &mut Mesh = mesh_manager.get_mesh_mut_forcely(&cube);
+        mesh.vertex_attributes.position.iter_mut().for_each(|position| *position *= 2.0);
+        mesh.mark_modified();
+        scene.sync_mesh_bounds(&mesh_manager);
+        assert_eq!(scene.world_bounds(&parent), Some(Aabb::new(Vec3::new(9.0, -1.0, -1.0), Vec3::new(11.0, 1.0, 1.0))));
+        let mesh = mesh_manager.get_mesh_forcely(&cube);
+        assert!(!scene.get_component::<MeshRenderer>(&parent).unwrap().needs_bounds_sync(mesh));
+
+        // Assigned meshes are synced.
+        let small_cube = mesh_manager.add_mesh(Cuboid::new(0.5, 0.5, 0.5, 1, 1, 1).into());
+        scene.get_component_mut::<MeshRenderer>(&parent).unwrap().mesh = small_cube;
+        scene.sync_mesh_bounds(&mesh_manager);
+        assert_eq!(
+            scene.world_bounds(&parent),
+            Some(Aabb::new(Vec3::new(9.75, -0.25, -0.25), Vec3::new(10.25, 0.25, 0.25)))
+        );
+    }
+}
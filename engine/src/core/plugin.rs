@@ -0,0 +1,118 @@
+use crate::core::Engine;
+
+/// A plugin extends the engine without changing it, e.g., physics, audio or tools shipped as separate crates.
+///
+/// [`EnginePlugin::build`] is called once when the plugin is added, by [`crate::core::EngineOptions::plugins`] or
+/// [`Engine::add_plugin`]. It can use the whole engine, for example:
+///
+/// * register component types by `engine.scene_serializer.register_component::<T>()`.
+/// * add systems by [`Engine::add_system`] and behaviors by [`Engine::add_behavior`]. A behavior can draw egui
+///   panels in `Behavior::on_gui`.
+/// * register model loaders by `engine.model_loader.register_loader(&["obj"], ObjLoader::new())`.
+/// * register shaders by `engine.shader_manager.register_shader("water", WATER_SHADER)`.
+/// * set an input backend by `engine.input_manager.set_gamepad_backend(backend)`.
+///
+/// # Examples
+///
+/// ```ignore
+/// struct PhysicsPlugin;
+///
+/// impl EnginePlugin for PhysicsPlugin {
+///     fn build(&self, engine: &mut Engine) {
+///         engine.scene_serializer.register_component::<RigidBody>();
+///         engine.add_system(System::new("physics::sync_transforms", Stage::PostUpdate, sync_transforms)
+///             .before(System::TRANSFORM_PROPAGATION)).unwrap();
+///     }
+/// }
+///
+/// let options = EngineOptions {
+///     plugins: vec![Box::new(PhysicsPlugin)],
+///     ..Default::default()
+/// };
+/// let mut engine = Engine::new(options);
+/// ```
+pub trait EnginePlugin {
+    /// Register the extensions of the plugin into the engine.
+    fn build(&self, engine: &mut Engine);
+
+    /// The unique name of the plugin. A plugin is not added again if a plugin with the same name has been added.
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+}
+
+impl Engine {
+    /// Add a plugin and build it immediately.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - Whether the plugin is added. It is false if a plugin with the same name has been added.
+    pub fn add_plugin<T: EnginePlugin + 'static>(&mut self, plugin: T) -> bool {
+        self.add_boxed_plugin(Box::new(plugin))
+    }
+
+    pub(crate) fn add_boxed_plugin(&mut self, plugin: Box<dyn EnginePlugin>) -> bool {
+        if self.has_plugin(plugin.name()) {
+            log::warn!("Plugin {} has been added.", plugin.name());
+            return false;
+        }
+        self.plugin_names.push(plugin.name().to_owned());
+        plugin.build(self);
+        true
+    }
+
+    /// Whether a plugin with the name has been added.
+    pub fn has_plugin(&self, name: &str) -> bool {
+        self.plugin_names.iter().any(|plugin_name| plugin_name == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        assets::ModelLoaderTrait,
+        core::{EngineOptions, LogicContext, NodeHandle, Stage, System},
+        input::gamepad::ScriptedGamepadBackend,
+    };
+
+    struct FakeLoader;
+
+    impl ModelLoaderTrait for FakeLoader {
+        fn load(&self, logic_context: &mut LogicContext<'_>, path: &str) -> Result<NodeHandle, Box<dyn std::error::Error>> {
+            let scene = logic_context.world.current_scene_mut();
+            let node = scene.create_node(path);
+            scene.add(node);
+            Ok(node)
+        }
+    }
+
+    struct ToolsPlugin;
+
+    impl EnginePlugin for ToolsPlugin {
+        fn build(&self, engine: &mut Engine) {
+            engine.add_system(System::new("tools::stats", Stage::PostRender, |_| {})).unwrap();
+            engine.model_loader.register_loader(&["fake"], FakeLoader);
+            engine.shader_manager.register_shader("tools::wireframe", "");
+            engine.input_manager.set_gamepad_backend(ScriptedGamepadBackend::new());
+        }
+    }
+
+    #[test]
+    fn test_plugins() {
+        let mut engine = Engine::new(EngineOptions {
+            plugins: vec![Box::new(ToolsPlugin)],
+            ..Default::default()
+        });
+        assert!(engine.has_plugin(ToolsPlugin.name()));
+        assert!(!engine.add_plugin(ToolsPlugin));
+        assert_eq!(engine.system_names(Stage::PostRender), vec!["tools::stats"]);
+        assert!(engine.shader_manager.get_registered_shader("tools::wireframe").is_some());
+        assert!(engine.input_manager.take_gamepad_backend().is_some());
+
+        let node = engine.load_model("models/cube.FAKE").unwrap();
+        assert_eq!(engine.world.current_scene().get_node_forcely(&node).name, "models/cube.FAKE");
+        assert!(engine.model_loader.supports("helmet.glb"));
+        assert!(engine.load_model("helmet.obj").is_err());
+    }
+}
@@ -0,0 +1,327 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    assets::{MaterialManager, TextureHandle},
+    components::mesh_renderer::MeshRenderer,
+    core::{NodeHandle, Scene, SceneSerializeError, SceneSerializer, scene_serializer::NodeData},
+    math::{Vec3, Vec4, color::Color},
+};
+
+/// A saved node subtree, which can be instantiated many times, e.g., to spawn enemies, props and UI widgets.
+///
+/// The subtree is stored with its components, and meshes, materials and textures are stored as
+/// [`crate::assets::AssetRef`]s, so a prefab can be saved as a RON asset and loaded in another run.
+/// Create one by [`Prefab::create`] and instantiate it by [`Scene::instantiate`].
+#[derive(Serialize, Deserialize)]
+pub struct Prefab {
+    /// All nodes of the subtree in depth first order. The first one is the root.
+    nodes: Vec<NodeData>,
+}
+
+impl Prefab {
+    /// Save the node and its descendants as a prefab.
+    ///
+    /// The assets and user components used by the subtree must be registered in the `scene_serializer`.
+    /// A skybox can not be a part of prefabs, since a scene has at most one skybox.
+    pub fn create(scene: &Scene, node: &NodeHandle, scene_serializer: &SceneSerializer) -> Result<Self, SceneSerializeError> {
+        let mut nodes = Vec::new();
+        if scene_serializer.collect_node_data(scene, node, &mut nodes)?.is_none() {
+            return Err(SceneSerializeError::InvalidPrefab(format!("the root node {} does not exist", node)));
+        }
+        if nodes.iter().any(|node_data| node_data.skybox.is_some()) {
+            return Err(SceneSerializeError::InvalidPrefab("a skybox can not be a part of prefabs".to_owned()));
+        }
+        Ok(Self { nodes })
+    }
+
+    /// The name of the root node.
+    pub fn root_name(&self) -> &str {
+        &self.nodes[0].name
+    }
+
+    /// Parse a prefab from a RON string.
+    pub fn from_ron_str(ron_str: &str) -> Result<Self, SceneSerializeError> {
+        let prefab: Self = ron::from_str(ron_str)?;
+        if prefab.nodes.is_empty() {
+            return Err(SceneSerializeError::InvalidPrefab("the prefab has no node".to_owned()));
+        }
+        Ok(prefab)
+    }
+
+    /// Serialize the prefab to a pretty RON string.
+    pub fn to_ron_string(&self) -> Result<String, SceneSerializeError> {
+        Ok(ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?)
+    }
+
+    /// Load a prefab from a RON file.
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self, SceneSerializeError> {
+        let ron_str = std::fs::read_to_string(path)?;
+        Self::from_ron_str(&ron_str)
+    }
+
+    /// Save the prefab to a RON file.
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), SceneSerializeError> {
+        std::fs::write(path, self.to_ron_string()?)?;
+        Ok(())
+    }
+
+    /// Deep clone the subtree into the scene, apply the overrides and return the new root node.
+    pub(crate) fn instantiate_into(
+        &self,
+        scene: &mut Scene,
+        parent: Option<NodeHandle>,
+        scene_serializer: &SceneSerializer,
+        overrides: Option<(&PrefabOverrides, &mut MaterialManager)>,
+    ) -> Result<NodeHandle, SceneSerializeError> {
+        let node_handles = scene_serializer.build_nodes(scene, &self.nodes)?;
+        let root = node_handles[0];
+        let result = match overrides {
+            Some((overrides, material_manager)) => overrides.apply(scene, &root, material_manager),
+            None => Ok(()),
+        };
+        let result = result.and_then(|_| match parent {
+            Some(parent) if !scene.attach_to_parent(&root, parent) => {
+                Err(SceneSerializeError::InvalidPrefab(format!("the parent node {} does not exist", parent)))
+            }
+            Some(_) => Ok(()),
+            None => {
+                scene.add(root);
+                Ok(())
+            }
+        });
+        if let Err(error) = result {
+            for node_handle in node_handles.iter().rev() {
+                scene.destroy_node(node_handle);
+            }
+            return Err(error);
+        }
+        Ok(root)
+    }
+}
+
+/// A value which overrides a material property of one prefab instance.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MaterialPropertyValue {
+    Float(f32),
+    Vec4(Vec4),
+    Color(Color),
+    Texture(TextureHandle),
+}
+
+/// The transform of a prefab node which is overridden. `None` keeps the value saved in the prefab.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TransformOverride {
+    pub position: Option<Vec3>,
+    /// The rotation in euler angles (in radians), see [`crate::components::transform::Transform::set_rotation_euler`].
+    pub rotation: Option<Vec3>,
+    pub scale: Option<Vec3>,
+}
+
+struct MaterialOverride {
+    node_path: String,
+    material_index: usize,
+    property_name: String,
+    value: MaterialPropertyValue,
+}
+
+/// Per-instance overrides of a prefab.
+///
+/// Nodes are addressed by paths of node names relative to the prefab root, e.g., `""` is the root
+/// and `"Body/Arm"` is the child "Arm" of the child "Body" of the root.
+/// Overriding a material property duplicates the material for the instance, so other instances are not affected.
+#[derive(Default)]
+pub struct PrefabOverrides {
+    transforms: Vec<(String, TransformOverride)>,
+    materials: Vec<MaterialOverride>,
+}
+
+impl PrefabOverrides {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Override the position of the node.
+    pub fn set_position(&mut self, node_path: &str, position: Vec3) -> &mut Self {
+        self.transform_override_mut(node_path).position = Some(position);
+        self
+    }
+
+    /// Override the rotation of the node in euler angles (in radians).
+    pub fn set_rotation(&mut self, node_path: &str, rotation: Vec3) -> &mut Self {
+        self.transform_override_mut(node_path).rotation = Some(rotation);
+        self
+    }
+
+    /// Override the scale of the node.
+    pub fn set_scale(&mut self, node_path: &str, scale: Vec3) -> &mut Self {
+        self.transform_override_mut(node_path).scale = Some(scale);
+        self
+    }
+
+    /// Override a property of the material at `material_index` of the node's MeshRenderer.
+    pub fn set_material_property(
+        &mut self,
+        node_path: &str,
+        material_index: usize,
+        property_name: &str,
+        value: MaterialPropertyValue,
+    ) -> &mut Self {
+        self.materials.push(MaterialOverride {
+            node_path: node_path.to_owned(),
+            material_index,
+            property_name: property_name.to_owned(),
+            value,
+        });
+        self
+    }
+
+    /// The transform override of the node, if any.
+    pub fn transform_override(&self, node_path: &str) -> Option<&TransformOverride> {
+        self.transforms
+            .iter()
+            .find(|(path, _)| path == node_path)
+            .map(|(_, transform_override)| transform_override)
+    }
+
+    fn transform_override_mut(&mut self, node_path: &str) -> &mut TransformOverride {
+        let index = match self.transforms.iter().position(|(path, _)| path == node_path) {
+            Some(index) => index,
+            None => {
+                self.transforms.push((node_path.to_owned(), TransformOverride::default()));
+                self.transforms.len() - 1
+            }
+        };
+        &mut self.transforms[index].1
+    }
+
+    fn apply(&self, scene: &mut Scene, root: &NodeHandle, material_manager: &mut MaterialManager) -> Result<(), SceneSerializeError> {
+        for (node_path, transform_override) in &self.transforms {
+            let node_handle = Self::find_node(scene, root, node_path)?;
+            let transform = &mut scene.get_node_mut_forcely(&node_handle).transform;
+            if let Some(position) = transform_override.position {
+                transform.set_position(position);
+            }
+            if let Some(rotation) = transform_override.rotation {
+                transform.set_rotation_euler(rotation);
+            }
+            if let Some(scale) = transform_override.scale {
+                transform.set_scale(scale);
+            }
+        }
+
+        // The materials which have been duplicated for this instance, keyed by node and material index.
+        let mut duplicated_materials = Vec::new();
+        for material_override in &self.materials {
+            let node_handle = Self::find_node(scene, root, &material_override.node_path)?;
+            let material_index = material_override.material_index;
+            let mesh_renderer = scene.get_component_mut::<MeshRenderer>(&node_handle).ok_or_else(|| {
+                SceneSerializeError::InvalidPrefab(format!("the node \"{}\" has no MeshRenderer", material_override.node_path))
+            })?;
+            let material_handle = *mesh_renderer.materials.get(material_index).ok_or_else(|| {
+                SceneSerializeError::InvalidPrefab(format!(
+                    "the node \"{}\" has no material at index {}",
+                    material_override.node_path, material_index
+                ))
+            })?;
+            let material_handle = if duplicated_materials.contains(&(node_handle, material_index)) {
+                material_handle
+            } else {
+                let material = material_manager
+                    .get_material(&material_handle)
+                    .ok_or_else(|| SceneSerializeError::InvalidPrefab(format!("the material {} does not exist", material_handle)))?
+                    .duplicate();
+                let new_material_handle = material_manager.add_material(material);
+                mesh_renderer.materials[material_index] = new_material_handle;
+                duplicated_materials.push((node_handle, material_index));
+                new_material_handle
+            };
+            let material = material_manager.get_material_mut_forcely(&material_handle);
+            let property_name = material_override.property_name.as_str();
+            match material_override.value {
+                MaterialPropertyValue::Float(value) => material.set_float(property_name, value),
+                MaterialPropertyValue::Vec4(value) => material.set_vec4f(property_name, value),
+                MaterialPropertyValue::Color(value) => material.set_color(property_name, value),
+                MaterialPropertyValue::Texture(value) => material.set_texture(property_name, value),
+            }
+        }
+        Ok(())
+    }
+
+    fn find_node(scene: &Scene, root: &NodeHandle, node_path: &str) -> Result<NodeHandle, SceneSerializeError> {
+        let mut node_handle = *root;
+        for name in node_path.split('/').filter(|name| !name.is_empty()) {
+            let node = scene.get_node_forcely(&node_handle);
+            node_handle = node
+                .children
+                .iter()
+                .flatten()
+                .find(|child| scene.get_node(child).is_some_and(|child| child.name == name))
+                .copied()
+                .ok_or_else(|| SceneSerializeError::InvalidPrefab(format!("no node is found at path \"{}\"", node_path)))?;
+        }
+        Ok(node_handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assets::{AssetRef, MaterialHandle, MeshHandle, ShaderManager};
+
+    #[test]
+    fn test_instantiate_prefab() {
+        let mut shader_manager = ShaderManager::new();
+        let mut material_manager = MaterialManager::new();
+        let unlit_shader = *shader_manager.get_builtin_unlit_shader().1;
+        let material = material_manager.create_material(unlit_shader, &mut shader_manager);
+        let mesh = MeshHandle::new(3);
+        let mut serializer = SceneSerializer::new();
+        serializer.asset_registry.register_mesh(AssetRef::Path("meshes/enemy.glb".to_owned()), mesh);
+        serializer.asset_registry.register_material(AssetRef::Path("materials/enemy.ron".to_owned()), material);
+
+        let mut scene = Scene::new();
+        let enemy = scene.create_node("Enemy");
+        let body = scene.create_node("Body");
+        scene.add_component(&body, MeshRenderer::new(mesh, vec![material]));
+        scene.attach_to_parent(&body, enemy);
+        scene.add(enemy);
+        let prefab = Prefab::create(&scene, &enemy, &serializer).unwrap();
+        let prefab = Prefab::from_ron_str(&prefab.to_ron_string().unwrap()).unwrap();
+        assert_eq!(prefab.root_name(), "Enemy");
+
+        let first = scene.instantiate(&prefab, None, &serializer).unwrap();
+        let mut overrides = PrefabOverrides::new();
+        overrides
+            .set_position("", Vec3::new(1.0, 0.0, 0.0))
+            .set_material_property("Body", 0, "_albedo_color", MaterialPropertyValue::Color(Color::RED));
+        let second = scene
+            .instantiate_with_overrides(&prefab, Some(first), &overrides, &serializer, &mut material_manager)
+            .unwrap();
+
+        assert_ne!(first, second);
+        assert_eq!(scene.root_nodes.len(), 2);
+        assert!(scene.get_node_forcely(&first).has_child(&second));
+        assert_eq!(scene.get_node_forcely(&first).transform.position, Vec3::ZERO);
+        assert_eq!(scene.get_node_forcely(&second).transform.position, Vec3::new(1.0, 0.0, 0.0));
+
+        let first_body = PrefabOverrides::find_node(&scene, &first, "Body").unwrap();
+        let second_body = PrefabOverrides::find_node(&scene, &second, "Body").unwrap();
+        assert_ne!(first_body, body);
+        assert_eq!(scene.get_component::<MeshRenderer>(&first_body).unwrap().materials, vec![material]);
+        let second_material: MaterialHandle = scene.get_component::<MeshRenderer>(&second_body).unwrap().materials[0];
+        assert_ne!(second_material, material);
+        let red = Vec4::from_array(Color::RED.to_array());
+        assert_eq!(material_manager.get_material_forcely(&second_material).get_vec4f("_albedo_color"), Some(red));
+        assert_ne!(material_manager.get_material_forcely(&material).get_vec4f("_albedo_color"), Some(red));
+
+        // A wrong path fails without leaving any node in the scene.
+        let mut overrides = PrefabOverrides::new();
+        overrides.set_scale("Tail", Vec3::ONE);
+        assert!(scene.instantiate_with_overrides(&prefab, None, &overrides, &serializer, &mut material_manager).is_err());
+        assert_eq!(scene.root_nodes.len(), 2);
+        // The slots of the destroyed nodes are reused.
+        assert!(scene.create_node("Reused").generation > 0);
+    }
+}
@@ -0,0 +1,317 @@
+use std::{any::{Any, TypeId}, marker::PhantomData};
+
+use crate::{
+    components::transform::Transform,
+    core::{Node, Scene},
+    prelude::{Component, ComponentId},
+    utils::sparse_set::SparseSet,
+};
+
+/// Data fetched for every node matched by [`Scene::query_mut`].
+///
+/// It is implemented for `&T`, `&mut T` and `Option<Q>` where `T` is a component, and for tuples of them, e.g.,
+/// `(&Transform, &mut Health, Option<&Light>)`. The [`Transform`] is read from [`Node::transform`], so every node has it.
+pub trait QueryData {
+    /// The item yielded for a matched node.
+    type Item<'a>;
+    #[doc(hidden)]
+    type State: 'static;
+
+    /// Append the accessed component types and whether they are accessed mutably.
+    #[doc(hidden)]
+    fn access(access: &mut Vec<(TypeId, bool)>);
+
+    /// Append the component types which a matched node must have, except [`Transform`].
+    #[doc(hidden)]
+    fn required(required: &mut Vec<TypeId>);
+
+    #[doc(hidden)]
+    fn init_state(scene: &mut Scene) -> Self::State;
+
+    /// # Safety
+    ///
+    /// `node` must be valid, `state` must be initialized from the scene which owns `node` and the scene must not be
+    /// modified while the item is alive. The accesses of the query must not conflict, and a node must not be fetched
+    /// twice at the same time.
+    #[doc(hidden)]
+    unsafe fn fetch<'a>(state: &Self::State, node: *mut Node) -> Option<Self::Item<'a>>;
+}
+
+/// [`QueryData`] which only reads components, used by [`Scene::query`].
+pub trait ReadOnlyQueryData: QueryData {
+    /// Initialize a state which is only used to read components.
+    #[doc(hidden)]
+    fn init_read_state(scene: &Scene) -> Self::State;
+}
+
+/// Filters the nodes matched by a query without fetching any data, e.g., `(With<Light>, Without<Camera>)`.
+pub trait QueryFilter {
+    /// Whether the node passes the filter.
+    fn matches(node: &Node, scene: &Scene) -> bool;
+}
+
+/// Only match nodes which have the component `T`.
+pub struct With<T: Component>(PhantomData<T>);
+
+/// Only match nodes which do not have the component `T`.
+pub struct Without<T: Component>(PhantomData<T>);
+
+fn is_transform<T: Component>() -> bool {
+    TypeId::of::<T>() == TypeId::of::<Transform>()
+}
+
+fn has_component<T: Component>(node: &Node, scene: &Scene) -> bool {
+    is_transform::<T>() || scene.component_storages.contains(&ComponentId::from(&node.id), &TypeId::of::<T>())
+}
+
+/// Panic if a component type is accessed mutably more than once, or both mutably and immutably.
+pub(crate) fn check_query_access<Q: QueryData>() {
+    let mut access = Vec::new();
+    Q::access(&mut access);
+    for (i, (type_id, mutable)) in access.iter().enumerate() {
+        for (other_type_id, other_mutable) in &access[i + 1..] {
+            if type_id == other_type_id && (*mutable || *other_mutable) {
+                panic!("Query {} accesses a component mutably more than once, or both mutably and immutably.", std::any::type_name::<Q>());
+            }
+        }
+    }
+}
+
+/// The storage of one component type in a scene, see [`QueryData`].
+#[doc(hidden)]
+pub struct ComponentColumn<T> {
+    components: *mut SparseSet<T>,
+}
+
+impl<T: Component> ComponentColumn<T> {
+    fn new(scene: &mut Scene) -> Option<Self> {
+        scene.component_storages
+            .get_storage_mut::<T>()
+            .map(|storage| Self { components: &mut storage.components })
+    }
+
+    fn new_read(scene: &Scene) -> Option<Self> {
+        // The pointer is only used by `get` to read components.
+        scene.component_storages
+            .get_storage::<T>()
+            .map(|storage| Self { components: &storage.components as *const SparseSet<T> as *mut SparseSet<T> })
+    }
+
+    unsafe fn get<'a>(column: &Option<Self>, node: *mut Node) -> Option<&'a T> {
+        unsafe {
+            if is_transform::<T>() {
+                return (&(*node).transform as &dyn Any).downcast_ref::<T>();
+            }
+            let component_id = ComponentId::from(&(*node).id);
+            SparseSet::get_ptr(column.as_ref()?.components, &component_id).map(|component| &*component)
+        }
+    }
+
+    unsafe fn get_mut<'a>(column: &Option<Self>, node: *mut Node) -> Option<&'a mut T> {
+        unsafe {
+            if is_transform::<T>() {
+                return (&mut (*node).transform as &mut dyn Any).downcast_mut::<T>();
+            }
+            let component_id = ComponentId::from(&(*node).id);
+            SparseSet::get_mut_ptr(column.as_ref()?.components, &component_id).map(|component| &mut *component)
+        }
+    }
+}
+
+fn push_required<T: Component>(required: &mut Vec<TypeId>) {
+    if !is_transform::<T>() {
+        required.push(TypeId::of::<T>());
+    }
+}
+
+impl<T: Component> QueryData for &T {
+    type Item<'a> = &'a T;
+    type State = Option<ComponentColumn<T>>;
+
+    fn access(access: &mut Vec<(TypeId, bool)>) {
+        access.push((TypeId::of::<T>(), false));
+    }
+
+    fn required(required: &mut Vec<TypeId>) {
+        push_required::<T>(required);
+    }
+
+    fn init_state(scene: &mut Scene) -> Self::State {
+        ComponentColumn::new(scene)
+    }
+
+    unsafe fn fetch<'a>(state: &Self::State, node: *mut Node) -> Option<Self::Item<'a>> {
+        unsafe { ComponentColumn::get(state, node) }
+    }
+}
+
+impl<T: Component> ReadOnlyQueryData for &T {
+    fn init_read_state(scene: &Scene) -> Self::State {
+        ComponentColumn::new_read(scene)
+    }
+}
+
+impl<T: Component> QueryData for &mut T {
+    type Item<'a> = &'a mut T;
+    type State = Option<ComponentColumn<T>>;
+
+    fn access(access: &mut Vec<(TypeId, bool)>) {
+        access.push((TypeId::of::<T>(), true));
+    }
+
+    fn required(required: &mut Vec<TypeId>) {
+        push_required::<T>(required);
+    }
+
+    fn init_state(scene: &mut Scene) -> Self::State {
+        ComponentColumn::new(scene)
+    }
+
+    unsafe fn fetch<'a>(state: &Self::State, node: *mut Node) -> Option<Self::Item<'a>> {
+        unsafe { ComponentColumn::get_mut(state, node) }
+    }
+}
+
+impl<Q: QueryData> QueryData for Option<Q> {
+    type Item<'a> = Option<Q::Item<'a>>;
+    type State = Q::State;
+
+    fn access(access: &mut Vec<(TypeId, bool)>) {
+        Q::access(access);
+    }
+
+    fn required(_required: &mut Vec<TypeId>) {}
+
+    fn init_state(scene: &mut Scene) -> Self::State {
+        Q::init_state(scene)
+    }
+
+    unsafe fn fetch<'a>(state: &Self::State, node: *mut Node) -> Option<Self::Item<'a>> {
+        unsafe { Some(Q::fetch(state, node)) }
+    }
+}
+
+impl<Q: ReadOnlyQueryData> ReadOnlyQueryData for Option<Q> {
+    fn init_read_state(scene: &Scene) -> Self::State {
+        Q::init_read_state(scene)
+    }
+}
+
+impl<T: Component> QueryFilter for With<T> {
+    fn matches(node: &Node, scene: &Scene) -> bool {
+        has_component::<T>(node, scene)
+    }
+}
+
+impl<T: Component> QueryFilter for Without<T> {
+    fn matches(node: &Node, scene: &Scene) -> bool {
+        !has_component::<T>(node, scene)
+    }
+}
+
+impl QueryFilter for () {
+    fn matches(_node: &Node, _scene: &Scene) -> bool {
+        true
+    }
+}
+
+macro_rules! impl_query_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: QueryData),+> QueryData for ($($name,)+) {
+            type Item<'a> = ($($name::Item<'a>,)+);
+            type State = ($($name::State,)+);
+
+            fn access(access: &mut Vec<(TypeId, bool)>) {
+                $($name::access(access);)+
+            }
+
+            fn required(required: &mut Vec<TypeId>) {
+                $($name::required(required);)+
+            }
+
+            fn init_state(scene: &mut Scene) -> Self::State {
+                ($($name::init_state(scene),)+)
+            }
+
+            #[allow(non_snake_case)]
+            unsafe fn fetch<'a>(state: &Self::State, node: *mut Node) -> Option<Self::Item<'a>> {
+                let ($($name,)+) = state;
+                unsafe { Some(($($name::fetch($name, node)?,)+)) }
+            }
+        }
+
+        impl<$($name: ReadOnlyQueryData),+> ReadOnlyQueryData for ($($name,)+) {
+            fn init_read_state(scene: &Scene) -> Self::State {
+                ($($name::init_read_state(scene),)+)
+            }
+        }
+
+        impl<$($name: QueryFilter),+> QueryFilter for ($($name,)+) {
+            fn matches(node: &Node, scene: &Scene) -> bool {
+                $($name::matches(node, scene))&&+
+            }
+        }
+    };
+}
+
+impl_query_tuple!(A);
+impl_query_tuple!(A, B);
+impl_query_tuple!(A, B, C);
+impl_query_tuple!(A, B, C, D);
+impl_query_tuple!(A, B, C, D, E);
+impl_query_tuple!(A, B, C, D, E, F);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{impl_component, math::Vec3};
+
+    struct Health(i32);
+    impl_component!(Health);
+
+    struct Poisoned;
+    impl_component!(Poisoned);
+
+    #[test]
+    fn test_query() {
+        let mut scene = Scene::new();
+        let player = scene.create_node("Player");
+        scene.add_component(&player, Health(100));
+        let enemy = scene.create_node("Enemy");
+        scene.add_component(&enemy, Health(50));
+        scene.add_component(&enemy, Poisoned);
+        let prop = scene.create_node("Prop");
+
+        let mut healths: Vec<_> = scene.query::<&Health>().map(|(node, health)| (node, health.0)).collect();
+        healths.sort_by_key(|(_, health)| *health);
+        assert_eq!(healths, vec![(enemy, 50), (player, 100)]);
+        assert_eq!(scene.query::<&Transform>().count(), 3);
+        assert_eq!(scene.query::<(&Health, Option<&Poisoned>)>().filter(|(_, (_, poisoned))| poisoned.is_some()).count(), 1);
+
+        for (_, (transform, health)) in scene.query_filtered_mut::<(&mut Transform, &mut Health), With<Poisoned>>() {
+            health.0 -= 10;
+            transform.position = Vec3::new(1.0, 0.0, 0.0);
+        }
+        for (_, health) in scene.query_filtered_mut::<&mut Health, Without<Poisoned>>() {
+            health.0 += 1;
+        }
+        assert_eq!(scene.get_component::<Health>(&enemy).unwrap().0, 40);
+        assert_eq!(scene.get_component::<Health>(&player).unwrap().0, 101);
+        assert_eq!(scene.get_node_forcely(&enemy).transform.position, Vec3::new(1.0, 0.0, 0.0));
+        assert_eq!(scene.query_filtered::<&Transform, Without<Health>>().map(|(node, _)| node).collect::<Vec<_>>(), vec![prop]);
+
+        // A node reusing the slot of a destroyed one does not see its components.
+        scene.destroy_node(&enemy);
+        let reused = scene.create_node("Reused");
+        assert!(scene.get_component::<Health>(&enemy).is_none());
+        assert!(scene.get_component::<Health>(&reused).is_none());
+        assert_eq!(scene.query::<&Health>().count(), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_query_conflicting_access() {
+        let mut scene = Scene::new();
+        let _ = scene.query_mut::<(&mut Health, &Health)>().count();
+    }
+}
@@ -1,12 +1,13 @@
 use crate::{
     assets::{
-        MaterialManager, ShaderManager, Texture, TextureHandle, TextureSamplerManager, environment::{ibldata::IBLData, skybox::Skybox}
+        MaterialManager, MeshManager, ShaderManager, Texture, TextureHandle, TextureSamplerManager, environment::{ibldata::IBLData, skybox::Skybox}
     },
     components::mesh_renderer::MeshRenderer,
-    core::{NodeArena, NodeHandle},
+    core::{NodeArena, NodeHandle, ObserverHandle, Prefab, PrefabOverrides, QueryData, QueryFilter, ReadOnlyQueryData, SceneEvent, SceneObserver, SceneSerializeError, SceneSerializer, SpatialIndex, check_query_access},
     graphics::graphics_context::GraphicsContext,
-    math::{Mat4, Vec4, color::Color},
-    prelude::{Camera, Component, GPULightData, Light, LightType, LightsGPUData, component_storage::ComponentStorages},
+    math::{Vec4, color::Color},
+    prelude::{Component, ComponentId, Script, GPULightData, Light, LightType, LightsGPUData, component_storage::ComponentStorages},
+    renderer::shadow_map::{DEFAULT_SHADOW_ATLAS_SIZE, ShadowCastingLight},
     time::Time,
 };
 
@@ -32,6 +33,8 @@ pub struct Scene {
     pub fog_color: Color,
     /// The IBL data of the scene.
     pub ibl_data: Option<IBLData>,
+    /// The size in pixels of the shadow atlas which contains the shadow maps of all lights.
+    pub shadow_atlas_size: u32,
     /// Cached camera nodes in the scene used to render the scene.
     pub(crate) cached_cameras: Vec<NodeHandle>,
     /// Cached renderable nodes in the scene used to render the scene.
@@ -39,11 +42,21 @@ pub struct Scene {
     /// Cached skybox node in the scene used to render the scene.
     pub(crate) cached_skybox_: NodeHandle,
     pub(crate) cached_lights: Vec<NodeHandle>,
+    /// Started scripts of destroyed nodes, whose `on_destroy` is called by the engine.
+    pub(crate) destroyed_scripts: Vec<(NodeHandle, Script)>,
     /// The SH coefficients of the scene.
     pub(crate) sh: SH,
 
     /// Component storages for the scene.
     pub(crate) component_storages: ComponentStorages,
+    /// The observers of scene events.
+    pub(crate) observers: Vec<(ObserverHandle, SceneObserver)>,
+    pub(crate) next_observer_handle: ObserverHandle,
+    /// The spatial index over the world bounds of mesh renderers.
+    pub(crate) spatial_index: SpatialIndex,
+    /// The nodes whose entries in the spatial index may be out of date, i.e., whose model matrices, enabled states in
+    /// hierarchy, layers or mesh bounds have changed, or whose mesh renderers have been added.
+    pub(crate) spatial_index_changes: Vec<NodeHandle>,
 }
 
 impl Scene {
@@ -56,12 +69,18 @@ impl Scene {
             fog_enabled: false,
             fog_color: Color::new(0.0, 0.0, 0.0, 1.0),
             ibl_data: None,
+            shadow_atlas_size: DEFAULT_SHADOW_ATLAS_SIZE,
             cached_cameras: vec![],
             cached_renderables: vec![],
             cached_skybox_: NodeHandle::INVALID,
             cached_lights: vec![],
+            destroyed_scripts: vec![],
             sh: Default::default(),
             component_storages: ComponentStorages::new(),
+            observers: vec![],
+            next_observer_handle: 0,
+            spatial_index: SpatialIndex::new(),
+            spatial_index_changes: vec![],
         };
         scene
     }
@@ -117,20 +136,87 @@ impl Scene {
     /// 
     /// * `NodeHandle` - The ID of the created node.
     pub fn create_node(&mut self, name: impl Into<String>) -> NodeHandle {
-        self.node_arena.create_node(name)
+        let node_id = self.node_arena.create_node(name);
+        self.publish_event(SceneEvent::NodeCreated(node_id));
+        node_id
     }
 
-    /// Destroy a node in the scene.
+    /// Destroy a node and all its descendants in the scene.
+    /// 
+    /// All their components are removed after their `on_removed` hooks are called, and they are removed from the root
+    /// nodes. The [`Script`]s are destroyed with them, and the `on_destroy` of the started ones is called at the next
+    /// sync point of the engine loop, see [`Script`].
     /// # Arguments
     /// 
     /// * `node_id` - The ID of the node to destroy.
     pub fn destroy_node(&mut self, node_id: &NodeHandle) {
-        if let Some(node) = self.node_arena.get_mut(node_id) {
-            for (component_type_id, component_id) in &node.components {
-                self.component_storages.remove_component_internal(component_id, component_type_id);
+        if self.node_arena.get(node_id).is_none() {
+            return;
+        }
+        let subtree: Vec<NodeHandle> = self.iter_depth_first(Some(node_id)).collect();
+        for node in &subtree {
+            let component_id = ComponentId::from(node);
+            for component_type_id in self.component_storages.component_types(&component_id) {
+                self.on_component_removing(node, &component_type_id);
+            }
+            if let Some(script) = self.component_storages.remove_component::<Script>(&component_id)
+                && script.is_started
+            {
+                self.destroyed_scripts.push((*node, script));
             }
-            self.node_arena.destroy_node(node_id);        
+            self.component_storages.remove_components(&component_id);
+            self.publish_event(SceneEvent::NodeDestroyed(*node));
+        }
+        self.root_nodes.retain(|root_node| root_node != node_id);
+        self.node_arena.destroy_node(node_id);
+    }
+
+    /// Duplicate a node and all its descendants, like duplicating in an editor.
+    /// 
+    /// The names, tags, layers, enabled flags and transforms are copied, and the components are copied by
+    /// [`Component::clone_component`]. The duplicate has the same parent as the node, or it is added to the root
+    /// nodes if the node is a root node.
+    /// # Arguments
+    /// 
+    /// * `node_id` - The ID of the node to duplicate.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Option<NodeHandle>` - The ID of the duplicate, or None if the node does not exist.
+    pub fn duplicate_node(&mut self, node_id: &NodeHandle) -> Option<NodeHandle> {
+        let parent = self.get_node(node_id)?.parent;
+        let duplicate = self.duplicate_node_recursively(node_id);
+        if let Some(parent) = parent {
+            self.attach_to_parent(&duplicate, parent);
+        } else if self.root_nodes.contains(node_id) {
+            self.add(duplicate);
         }
+        Some(duplicate)
+    }
+
+    fn duplicate_node_recursively(&mut self, node_id: &NodeHandle) -> NodeHandle {
+        let node = self.get_node_forcely(node_id);
+        let (name, enabled, layer, tags, mut transform) =
+            (node.name.clone(), node.enabled, node.layer, node.tags.clone(), node.transform.clone());
+        let children = node.children.clone().unwrap_or_default();
+        transform.is_dirty = true;
+
+        let duplicate = self.create_node(name);
+        {
+            let duplicate_node = self.get_node_mut_forcely(&duplicate);
+            duplicate_node.enabled = enabled;
+            duplicate_node.layer = layer;
+            duplicate_node.tags = tags;
+            duplicate_node.transform = transform;
+        }
+        for component_type_id in self.component_storages.clone_components(&ComponentId::from(node_id), ComponentId::from(&duplicate)) {
+            self.on_component_added(&duplicate, &component_type_id);
+        }
+        for child in &children {
+            let duplicate_child = self.duplicate_node_recursively(child);
+            self.attach_to_parent(&duplicate_child, duplicate);
+        }
+        duplicate
     }
 
     /// Add a component to a node in the scene.
@@ -143,28 +229,19 @@ impl Scene {
     /// 
     /// * `Option<T>` - Returns the old component if it exists, `None` otherwise.
     pub fn add_component<T: Component>(&mut self, node_id: &NodeHandle, component: T) -> Option<T>  {
-        if let Some(node) = self.node_arena.get_mut(node_id) {
+        if self.node_arena.get(node_id).is_some() {
+            let component_id = ComponentId::from(node_id);
             let component_type_id = std::any::TypeId::of::<T>();
-            if let Some(old_component_id) = node.components.get(&component_type_id) {
+            if self.component_storages.contains(&component_id, &component_type_id) {
                 #[cfg(debug_assertions)]
                 {
                     log::warn!("Try to add more than one component of type {:?} to node {:?}, the old one is replaced and returned.", component_type_id, node_id);
                 }
-                self.component_storages.replace_component(old_component_id, component_type_id, component)
-            } else {
-                let component_id = self.component_storages.add_component(component, component_type_id);
-                node.components.insert(component_type_id, component_id);
-                if component_type_id == std::any::TypeId::of::<Camera>() {
-                    self.cached_cameras.push(*node_id);
-                } else if component_type_id == std::any::TypeId::of::<MeshRenderer>() {
-                    self.cached_renderables.push(*node_id);
-                } else if  component_type_id == std::any::TypeId::of::<Skybox>() {
-                    self.cached_skybox_ = *node_id;
-                } else if component_type_id == std::any::TypeId::of::<Light>() {
-                    self.cached_lights.push(*node_id);
-                }
-                None
+                self.on_component_removing(node_id, &component_type_id);
             }
+            let old_component = self.component_storages.add_component(component_id, component);
+            self.on_component_added(node_id, &component_type_id);
+            old_component
         } else {
             #[cfg(debug_assertions)]
             {
@@ -183,25 +260,13 @@ impl Scene {
     /// 
     /// * `Option<T>` - Returns the removed component if it exists, `None` otherwise.
     pub fn remove_component<T: Component>(&mut self, node_id: &NodeHandle) -> Option<T> {
-        if let Some(node) = self.node_arena.get_mut(node_id) {
-            let component_type_id = std::any::TypeId::of::<T>();
-            if let Some(component_id) = node.components.remove(&component_type_id) {
-                if component_type_id == std::any::TypeId::of::<Camera>() {
-                    self.cached_cameras.retain(|&id| id != *node_id);
-                } else if component_type_id == std::any::TypeId::of::<MeshRenderer>() {
-                    self.cached_renderables.retain(|&id| id != *node_id);
-                } else if  component_type_id == std::any::TypeId::of::<Skybox>() {
-                    self.cached_skybox_ = NodeHandle::INVALID;
-                } else if component_type_id == std::any::TypeId::of::<Light>() {
-                    self.cached_lights.retain(|&id| id != *node_id);
-                }
-                self.component_storages.remove_component(&component_id, component_type_id)
-            } else {
-                None
-            }
-        } else {
-            None
+        let component_id = ComponentId::from(node_id);
+        let component_type_id = std::any::TypeId::of::<T>();
+        if !self.component_storages.contains(&component_id, &component_type_id) {
+            return None;
         }
+        self.on_component_removing(node_id, &component_type_id);
+        self.component_storages.remove_component::<T>(&component_id)
     }
 
     /// Get a reference to a component of a node in the scene.
@@ -213,12 +278,7 @@ impl Scene {
     /// 
     /// * `Option<&T>` - Returns a reference to the component if it exists, `None` otherwise.
     pub fn get_component<T: Component>(&self, node_id: &NodeHandle) -> Option<&T> {
-        let component_type_id = std::any::TypeId::of::<T>();
-        if let Some(node) = self.node_arena.get(node_id) && let Some(component_id) = node.components.get(&component_type_id) {
-            self.component_storages.get_component(component_id)
-        } else {
-            None
-        }
+        self.component_storages.get_component(&ComponentId::from(node_id))
     }
 
     /// Get a mutable reference to a component of a node in the scene.
@@ -230,12 +290,136 @@ impl Scene {
     /// 
     /// * `Option<&mut T>` - Returns a mutable reference to the component if it exists, `None` otherwise.
     pub fn get_component_mut<T: Component>(&mut self, node_id: &NodeHandle) -> Option<&mut T> {
-        let component_type_id = std::any::TypeId::of::<T>();
-        if let Some(node) = self.node_arena.get(node_id) && let Some(component_id) = node.components.get(&component_type_id) {
-            self.component_storages.get_component_mut(component_id)
+        self.component_storages.get_component_mut(&ComponentId::from(node_id))
+    }
+
+    /// Iterate over the nodes which match the query, e.g., `scene.query::<(&Transform, &Light)>()`.
+    /// # Returns
+    /// 
+    /// * `impl Iterator<Item = (NodeHandle, Q::Item)>` - The handle of each matched node and its components.
+    pub fn query<Q: ReadOnlyQueryData>(&self) -> impl Iterator<Item = (NodeHandle, Q::Item<'_>)> + '_ {
+        self.query_filtered::<Q, ()>()
+    }
+
+    /// Iterate over the nodes which match both the query and the filter, e.g.,
+    /// `scene.query_filtered::<&Transform, Without<Camera>>()`.
+    pub fn query_filtered<Q: ReadOnlyQueryData, F: QueryFilter>(&self) -> impl Iterator<Item = (NodeHandle, Q::Item<'_>)> + '_ {
+        let state = Q::init_read_state(self);
+        self.query_candidates::<Q>().filter_map(move |node_id| {
+            let node = self.node_arena.get(&node_id)?;
+            if !F::matches(node, self) {
+                return None;
+            }
+            // Safety: the state is created from this scene, which is borrowed by the iterator, and is only read.
+            unsafe { Q::fetch(&state, node as *const Node as *mut Node) }.map(|item| (node_id, item))
+        })
+    }
+
+    /// Iterate over the nodes which match the query, with mutable access to components, e.g.,
+    /// `scene.query_mut::<(&Transform, &mut Light)>()`.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the query accesses a component type mutably more than once, or both mutably and immutably.
+    pub fn query_mut<Q: QueryData>(&mut self) -> impl Iterator<Item = (NodeHandle, Q::Item<'_>)> + '_ {
+        self.query_filtered_mut::<Q, ()>()
+    }
+
+    /// Iterate over the nodes which match both the query and the filter, with mutable access to components.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the query accesses a component type mutably more than once, or both mutably and immutably.
+    pub fn query_filtered_mut<Q: QueryData, F: QueryFilter>(&mut self) -> impl Iterator<Item = (NodeHandle, Q::Item<'_>)> + '_ {
+        check_query_access::<Q>();
+        let node_ids: Vec<NodeHandle> = self
+            .query_candidates::<Q>()
+            .filter(|node_id| self.node_arena.get(node_id).is_some_and(|node| F::matches(node, self)))
+            .collect();
+        let state = Q::init_state(self);
+        let node_slots = self.node_arena.as_mut_ptr();
+        node_ids.into_iter().filter_map(move |node_id| {
+            // Safety: the state and slots are created from this scene, which is borrowed by the iterator, the accesses
+            // are checked above, and every node is fetched once, so the yielded references never alias.
+            unsafe {
+                let node = NodeArena::node_ptr(node_slots, &node_id)?;
+                Q::fetch(&state, node).map(|item| (node_id, item))
+            }
+        })
+    }
+
+    /// The nodes which may match the query: the owners of the fewest components among the required types, in the
+    /// order they are packed in memory, or all nodes if no component is required.
+    fn query_candidates<Q: QueryData>(&self) -> impl Iterator<Item = NodeHandle> + '_ {
+        let mut required = Vec::new();
+        Q::required(&mut required);
+        let (owners, all_nodes) = if required.is_empty() {
+            (None, Some(self.node_arena.iter().map(|node| node.id)))
         } else {
-            None
-        }
+            // A required type without storage matches nothing.
+            let component_ids = required
+                .iter()
+                .map(|component_type_id| self.component_storages.component_ids(component_type_id).unwrap_or(&[]))
+                .min_by_key(|component_ids| component_ids.len())
+                .unwrap_or(&[]);
+            (Some(component_ids.iter().map(NodeHandle::from)), None)
+        };
+        owners.into_iter().flatten().chain(all_nodes.into_iter().flatten())
+    }
+
+    /// Save the scene to a RON file.
+    /// # Arguments
+    /// 
+    /// * `path` - The path of the scene file.
+    /// * `scene_serializer` - The serializer which knows the registered assets and user components.
+    pub fn save<P: AsRef<std::path::Path>>(&self, path: P, scene_serializer: &SceneSerializer) -> Result<(), SceneSerializeError> {
+        scene_serializer.save(self, path)
+    }
+
+    /// Load a scene from a RON file saved by [`Scene::save`].
+    /// # Arguments
+    /// 
+    /// * `path` - The path of the scene file.
+    /// * `scene_serializer` - The serializer which knows the registered assets and user components.
+    /// * `shader_manager`, `material_manager` and `mesh_manager` - Used to create the skybox geometry and material.
+    pub fn load<P: AsRef<std::path::Path>>(
+        path: P,
+        scene_serializer: &SceneSerializer,
+        shader_manager: &mut ShaderManager,
+        material_manager: &mut MaterialManager,
+        mesh_manager: &mut MeshManager,
+    ) -> Result<Scene, SceneSerializeError> {
+        scene_serializer.load(path, shader_manager, material_manager, mesh_manager)
+    }
+
+    /// Deep clone the node subtree of the prefab into the scene.
+    /// # Arguments
+    /// 
+    /// * `prefab` - The prefab to instantiate.
+    /// * `parent` - The parent node of the new subtree. If it is None, the new root node is added to root nodes.
+    /// * `scene_serializer` - The serializer which resolves the assets and user components of the prefab.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Result<NodeHandle, SceneSerializeError>` - The new root node.
+    pub fn instantiate(&mut self, prefab: &Prefab, parent: Option<NodeHandle>, scene_serializer: &SceneSerializer) -> Result<NodeHandle, SceneSerializeError> {
+        prefab.instantiate_into(self, parent, scene_serializer, None)
+    }
+
+    /// The same as [`Scene::instantiate`], but the transforms and material properties of the instance are overridden.
+    /// # Arguments
+    /// 
+    /// * `overrides` - The per-instance overrides.
+    /// * `material_manager` - Used to duplicate the materials whose properties are overridden.
+    pub fn instantiate_with_overrides(
+        &mut self,
+        prefab: &Prefab,
+        parent: Option<NodeHandle>,
+        overrides: &PrefabOverrides,
+        scene_serializer: &SceneSerializer,
+        material_manager: &mut MaterialManager,
+    ) -> Result<NodeHandle, SceneSerializeError> {
+        prefab.instantiate_into(self, parent, scene_serializer, Some((overrides, material_manager)))
     }
 
     /// Set the IBL data of the scene.
@@ -309,31 +493,51 @@ impl Scene {
     /// # Arguments
     /// 
     /// * `time` - The time delta since the last update.
-    pub(crate) fn on_update(&mut self, _time: &mut Time) {
-        for root_node in &self.root_nodes {
-            Self::update_node_hierarchy(&mut self.node_arena, root_node, None, true);
+    pub(crate) fn on_update(&mut self, time: &mut Time) {
+        let interpolation_alpha = time.interpolation_alpha();
+        // (node, parent, whether the model matrix of the parent is recomputed, whether the parent is enabled in hierarchy)
+        let mut enabled_changes = Vec::new();
+        let mut stack: Vec<(NodeHandle, Option<NodeHandle>, bool, bool)> =
+            self.root_nodes.iter().rev().map(|root_node| (*root_node, None, false, true)).collect();
+        while let Some((node_id, parent_id, is_parent_changed, is_parent_enabled)) = stack.pop() {
+            let Some(node) = self.node_arena.get(&node_id) else {
+                continue;
+            };
+            // Only the changed subtrees are recomputed.
+            let is_changed = node.transform.needs_update(is_parent_changed);
+            if is_changed {
+                let parent_model_matrix = parent_id
+                    .and_then(|parent_id| self.node_arena.get(&parent_id))
+                    .map(|parent| parent.transform.model_matrix);
+                self.node_arena.get_mut_forcely(&node_id).on_update(parent_model_matrix, interpolation_alpha);
+            }
+            let node = self.node_arena.get_mut_forcely(&node_id);
+            let is_enabled_in_hierarchy = node.enabled && is_parent_enabled;
+            let is_enabled_changed = node.enabled_in_hierarchy != is_enabled_in_hierarchy;
+            if is_enabled_changed {
+                node.enabled_in_hierarchy = is_enabled_in_hierarchy;
+                enabled_changes.push((node_id, is_enabled_in_hierarchy));
+            }
+            if is_changed
+                || is_enabled_changed
+                || self.spatial_index.layer(&node_id).is_some_and(|layer| layer != node.layer)
+            {
+                self.spatial_index_changes.push(node_id);
+            }
+            if let Some(children) = &node.children {
+                stack.extend(children.iter().rev().map(|child| (*child, Some(node_id), is_changed, is_enabled_in_hierarchy)));
+            }
         }
-    }
-
-    fn update_node_hierarchy(node_arena: &mut NodeArena, node_id: &NodeHandle, parent_model_matrix: Option<Mat4>, is_parent_enabled: bool) {
-        let mut model_matrix = None;
-        let mut children = None;
-        let mut node_is_valid = false;
-        let mut is_enabled_in_hierarchy = is_parent_enabled;
-        if let Some(node) = node_arena.get_mut(node_id) {
-            node_is_valid = true;
-            node.on_update(parent_model_matrix);
-            model_matrix = Some(node.transform.model_matrix);
-            children = node.children.clone();
-            is_enabled_in_hierarchy = node.enabled && is_parent_enabled;
-            node.enabled_in_hierarchy = is_enabled_in_hierarchy;
+        for (node_id, is_enabled) in enabled_changes {
+            self.on_enabled_in_hierarchy_changed(&node_id, is_enabled);
         }
+    }
 
-        if node_is_valid {
-            if let Some(children) = &mut children {
-                for child in children {
-                    Self::update_node_hierarchy(node_arena, &child, model_matrix, is_enabled_in_hierarchy);
-                }
+    /// Save the transforms which are interpolated for rendering, before a fixed update.
+    pub(crate) fn save_previous_transforms(&mut self) {
+        for node in self.node_arena.iter_mut() {
+            if node.transform.interpolate {
+                node.transform.save_previous_state();
             }
         }
     }
@@ -342,8 +546,14 @@ impl Scene {
     pub(crate) fn on_stop(&mut self, _time: &mut Time) {
     }
 
-    pub(crate) fn collect_lights_data(&self) -> LightsGPUData {
+    /// Collect the GPU data of all enabled lights, and the lights which cast shadow.
+    /// 
+    /// # Returns
+    /// 
+    /// * `(LightsGPUData, Vec<ShadowCastingLight>)` - The lights data and the shadow casting lights.
+    pub(crate) fn collect_lights_data(&self) -> (LightsGPUData, Vec<ShadowCastingLight>) {
         let mut lights_gpu_data = LightsGPUData::default();
+        let mut shadow_casting_lights = Vec::new();
         let mut light_count = 0u32;
         for light_handle in &self.cached_lights {
             let light_node = self.get_node_forcely(light_handle);
@@ -351,10 +561,11 @@ impl Scene {
                 light_count += 1;
                 let mut light_data = GPULightData::default();
                 light_data.flags[0] = light.light_type.as_u32();
+                light_data.culling[0] = light.culling_mask.get();
                 let light_color = light.color * light.intensity;
                 light_data.color = light_color.to_array();
                 
-                let light_position = light_node.transform.position;
+                let light_position = light_node.transform.model_matrix.w_axis.truncate();
                 light_data.position = [light_position.x, light_position.y, light_position.z, 1.0];
                 let mut light_direction = light_node.transform.model_matrix * Vec4::new(0.0, 0.0, -1.0, 0.0);
                 light_direction = light_direction.normalize();
@@ -375,6 +586,24 @@ impl Scene {
                     }
                 }
 
+                if light.cast_shadow {
+                    // The shader treats the direction of a directional light as the direction towards the light,
+                    // so its light rays travel in the opposite direction.
+                    let ray_direction = if let LightType::Directional{} = light.light_type {
+                        -light_direction.truncate()
+                    } else {
+                        light_direction.truncate()
+                    };
+                    shadow_casting_lights.push(ShadowCastingLight {
+                        light_index: lights_gpu_data.lights_info.len(),
+                        light_type: light.light_type,
+                        position: light_position,
+                        direction: ray_direction,
+                        settings: light.shadow_settings,
+                        culling_mask: light.culling_mask,
+                    });
+                }
+
                 lights_gpu_data.lights_info.push(light_data);
             }
         }
@@ -383,6 +612,67 @@ impl Scene {
             // dummy light which is not used in shader. Just to avoid validation error.
             lights_gpu_data.lights_info.push(GPULightData::default());
         }
-        lights_gpu_data
+        (lights_gpu_data, shadow_casting_lights)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        assets::{MaterialHandle, MeshHandle},
+        core::LogicContext,
+        impl_as_any, impl_component,
+        math::{Vec3, color::Color},
+        prelude::{Behavior, MeshRenderer},
+    };
+
+    #[derive(Clone)]
+    struct Health(i32);
+    impl_component!(Health, Clone);
+
+    struct Ai;
+    impl Behavior for Ai {
+        impl_as_any!();
+        fn on_update(&mut self, _logic_context: &mut LogicContext) {}
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_duplicate_and_destroy_node() {
+        let mut scene = Scene::new();
+        let enemy = scene.create_node("Enemy");
+        let body = scene.create_node("Body");
+        scene.add(enemy);
+        scene.attach_to_parent(&body, enemy);
+        scene.get_node_mut_forcely(&enemy).add_tag("Enemy");
+        scene.get_node_mut_forcely(&body).transform.set_position(Vec3::new(0.0, 1.0, 0.0));
+        scene.add_component(&enemy, Health(3));
+        scene.add_component(&enemy, Script::new(Ai));
+        scene.add_component(&body, MeshRenderer::new(MeshHandle::new(3), vec![MaterialHandle::new(5)]));
+        scene.add_component(&body, Light::new_point_light(5.0, Color::WHITE, 1.0, false));
+
+        let copy = scene.duplicate_node(&enemy).unwrap();
+        assert_eq!(scene.root_nodes, vec![enemy, copy]);
+        let copy_body = scene.find_by_path(Some(&copy), "Body").unwrap();
+        assert_ne!(copy_body, body);
+        assert!(scene.get_node_forcely(&copy).has_tag("Enemy"));
+        assert_eq!(scene.get_node_forcely(&copy_body).transform.get_position(), &Vec3::new(0.0, 1.0, 0.0));
+        assert_eq!(scene.get_component::<Health>(&copy).unwrap().0, 3);
+        // Scripts have no clone hook.
+        assert!(scene.get_component::<Script>(&copy).is_none());
+        assert_eq!(scene.get_component::<MeshRenderer>(&copy_body).unwrap().materials, vec![MaterialHandle::new(5)]);
+        assert_eq!(scene.cached_renderables, vec![body, copy_body]);
+        assert_eq!(scene.cached_lights, vec![body, copy_body]);
+
+        // Duplicating a child keeps it under the same parent.
+        let second_body = scene.duplicate_node(&body).unwrap();
+        assert_eq!(scene.get_node_forcely(&second_body).parent, Some(enemy));
+
+        scene.destroy_node(&enemy);
+        assert_eq!(scene.root_nodes, vec![copy]);
+        assert!(scene.get_node(&body).is_none() && scene.get_node(&second_body).is_none());
+        assert!(scene.get_component::<Health>(&enemy).is_none());
+        assert!(scene.get_component::<MeshRenderer>(&body).is_none());
+        assert_eq!(scene.cached_renderables, vec![copy_body]);
+        assert_eq!(scene.cached_lights, vec![copy_body]);
+    }
+}
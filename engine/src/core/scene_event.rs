@@ -0,0 +1,187 @@
+use std::any::TypeId;
+
+use crate::{
+    core::{NodeHandle, Scene},
+    prelude::{ComponentId, ComponentTypeId},
+};
+
+/// The structural changes of a scene, which are published to the observers added by [`Scene::add_observer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SceneEvent {
+    NodeCreated(NodeHandle),
+    /// Published for the node and each of its descendants, after their components are removed.
+    NodeDestroyed(NodeHandle),
+    /// Published after [`crate::prelude::Component::on_added`] is called.
+    ComponentAdded {
+        node: NodeHandle,
+        component_type: TypeId,
+    },
+    /// Published after [`crate::prelude::Component::on_removed`] is called, when the component can still be got.
+    ComponentRemoved {
+        node: NodeHandle,
+        component_type: TypeId,
+    },
+}
+
+impl SceneEvent {
+    /// The node of the event.
+    pub fn node(&self) -> NodeHandle {
+        match self {
+            SceneEvent::NodeCreated(node) | SceneEvent::NodeDestroyed(node) => *node,
+            SceneEvent::ComponentAdded { node, .. } | SceneEvent::ComponentRemoved { node, .. } => *node,
+        }
+    }
+}
+
+pub type ObserverHandle = usize;
+
+pub(crate) type SceneObserver = Box<dyn FnMut(&SceneEvent)>;
+
+/// Scene events and component lifecycle hooks.
+impl Scene {
+    /// Add an observer which is called immediately when a [`SceneEvent`] happens in the scene.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let spawned = Rc::new(RefCell::new(Vec::new()));
+    /// let spawned_in_observer = spawned.clone();
+    /// scene.add_observer(move |scene_event| {
+    ///     if let SceneEvent::NodeCreated(node) = scene_event {
+    ///         spawned_in_observer.borrow_mut().push(*node);
+    ///     }
+    /// });
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `ObserverHandle` - The handle used to remove the observer.
+    pub fn add_observer(&mut self, observer: impl FnMut(&SceneEvent) + 'static) -> ObserverHandle {
+        let observer_handle = self.next_observer_handle;
+        self.next_observer_handle += 1;
+        self.observers.push((observer_handle, Box::new(observer)));
+        observer_handle
+    }
+
+    /// Remove an observer.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - Whether the observer exists.
+    pub fn remove_observer(&mut self, observer_handle: ObserverHandle) -> bool {
+        let observer_count = self.observers.len();
+        self.observers.retain(|(handle, _)| *handle != observer_handle);
+        self.observers.len() != observer_count
+    }
+
+    pub(crate) fn publish_event(&mut self, scene_event: SceneEvent) {
+        for (_, observer) in &mut self.observers {
+            observer(&scene_event);
+        }
+    }
+
+    /// Call the `on_added` hook of a component which has been put into the storages, and publish the event.
+    pub(crate) fn on_component_added(&mut self, node_id: &NodeHandle, component_type_id: &ComponentTypeId) {
+        if let Some(hooks) = self.component_storages.hooks(component_type_id) {
+            (hooks.on_added)(self, node_id);
+        }
+        self.publish_event(SceneEvent::ComponentAdded { node: *node_id, component_type: *component_type_id });
+    }
+
+    /// Call the `on_removed` hook of a component which is about to be taken from the storages, and publish the event.
+    pub(crate) fn on_component_removing(&mut self, node_id: &NodeHandle, component_type_id: &ComponentTypeId) {
+        if let Some(hooks) = self.component_storages.hooks(component_type_id) {
+            (hooks.on_removed)(self, node_id);
+        }
+        self.publish_event(SceneEvent::ComponentRemoved { node: *node_id, component_type: *component_type_id });
+    }
+
+    /// Call the `on_enable` or `on_disable` hooks of all components of a node.
+    pub(crate) fn on_enabled_in_hierarchy_changed(&mut self, node_id: &NodeHandle, is_enabled: bool) {
+        for component_type_id in self.component_storages.component_types(&ComponentId::from(node_id)) {
+            if let Some(hooks) = self.component_storages.hooks(&component_type_id) {
+                let hook = if is_enabled { hooks.on_enable } else { hooks.on_disable };
+                hook(self, node_id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::*;
+    use crate::{prelude::Component, time::Time};
+
+    thread_local! {
+        static HOOK_CALLS: RefCell<Vec<(&'static str, i32)>> = const { RefCell::new(Vec::new()) };
+    }
+
+    struct Shield(i32);
+
+    impl Component for Shield {
+        fn on_added(scene: &mut Scene, node: &NodeHandle) {
+            let shield = scene.get_component::<Shield>(node).unwrap().0;
+            HOOK_CALLS.with_borrow_mut(|calls| calls.push(("added", shield)));
+        }
+        fn on_removed(scene: &mut Scene, node: &NodeHandle) {
+            let shield = scene.get_component::<Shield>(node).unwrap().0;
+            HOOK_CALLS.with_borrow_mut(|calls| calls.push(("removed", shield)));
+        }
+        fn on_enable(scene: &mut Scene, node: &NodeHandle) {
+            let shield = scene.get_component::<Shield>(node).unwrap().0;
+            HOOK_CALLS.with_borrow_mut(|calls| calls.push(("enable", shield)));
+        }
+        fn on_disable(scene: &mut Scene, node: &NodeHandle) {
+            let shield = scene.get_component::<Shield>(node).unwrap().0;
+            HOOK_CALLS.with_borrow_mut(|calls| calls.push(("disable", shield)));
+        }
+    }
+
+    #[test]
+    fn test_component_hooks_and_scene_events() {
+        let mut scene = Scene::new();
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let events_in_observer = events.clone();
+        let observer = scene.add_observer(move |scene_event| events_in_observer.borrow_mut().push(*scene_event));
+
+        let parent = scene.create_node("Parent");
+        let knight = scene.create_node("Knight");
+        scene.add(parent);
+        scene.attach_to_parent(&knight, parent);
+        scene.add_component(&knight, Shield(1));
+        assert_eq!(scene.add_component(&knight, Shield(2)).unwrap().0, 1);
+
+        scene.get_node_mut_forcely(&parent).enabled = false;
+        scene.on_update(&mut Time::new());
+        // Only changes call the hooks.
+        scene.on_update(&mut Time::new());
+        scene.get_node_mut_forcely(&parent).enabled = true;
+        scene.on_update(&mut Time::new());
+        scene.destroy_node(&parent);
+        assert_eq!(
+            HOOK_CALLS.take(),
+            vec![("added", 1), ("removed", 1), ("added", 2), ("disable", 2), ("enable", 2), ("removed", 2)]
+        );
+
+        let shield = TypeId::of::<Shield>();
+        assert_eq!(
+            *events.borrow(),
+            vec![
+                SceneEvent::NodeCreated(parent),
+                SceneEvent::NodeCreated(knight),
+                SceneEvent::ComponentAdded { node: knight, component_type: shield },
+                SceneEvent::ComponentRemoved { node: knight, component_type: shield },
+                SceneEvent::ComponentAdded { node: knight, component_type: shield },
+                SceneEvent::NodeDestroyed(parent),
+                SceneEvent::ComponentRemoved { node: knight, component_type: shield },
+                SceneEvent::NodeDestroyed(knight),
+            ]
+        );
+
+        assert!(scene.remove_observer(observer));
+        scene.create_node("Unobserved");
+        assert_eq!(events.borrow().len(), 8);
+    }
+}
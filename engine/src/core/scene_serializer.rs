@@ -0,0 +1,753 @@
+use std::{any::TypeId, path::Path};
+
+use ahash::AHashMap;
+use ron::value::RawValue;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use thiserror::Error;
+
+use crate::{
+    assets::{AssetRef, AssetRegistry, MaterialManager, MeshManager, ShaderManager, TextureHandle, environment::skybox::Skybox},
+    components::{
+        camera::{Camera, CameraMode},
+        light::{Light, LightType, ShadowSettings},
+        mesh_renderer::MeshRenderer,
+    },
+    core::{LayerMask, NodeHandle, layer::Layer, scene::Scene},
+    math::{Vec3, Vec4, color::Color},
+    prelude::Component,
+    utils::SkyboxBuilder,
+};
+
+#[derive(Error, Debug)]
+pub enum SceneSerializeError {
+    #[error("Failed to read or write the scene file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to parse the scene: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[error("Failed to serialize the scene: {0}")]
+    Serialize(#[from] ron::Error),
+    #[error("The asset {0} is used by the scene but not registered in the AssetRegistry")]
+    UnregisteredAsset(String),
+    #[error("The asset reference {0} can not be resolved by the AssetRegistry")]
+    UnknownAsset(AssetRef),
+    #[error("The component type {0} is not registered in the SceneSerializer")]
+    UnknownComponent(String),
+    #[error("Invalid prefab: {0}")]
+    InvalidPrefab(String),
+    #[error("Invalid node hierarchy: {0}")]
+    InvalidHierarchy(String),
+}
+
+/// A user component which can be saved into and loaded from scene files.
+///
+/// Register it by [`SceneSerializer::register_component`] before saving or loading scenes.
+pub trait SerializableComponent: Component + Serialize + DeserializeOwned {
+    /// The unique name of the component type written to scene files. It must not change once scenes are saved.
+    const TYPE_NAME: &'static str;
+}
+
+type SaveComponentFn = fn(&Scene, &NodeHandle) -> Option<Result<Box<RawValue>, ron::Error>>;
+type LoadComponentFn = fn(&mut Scene, &NodeHandle, &RawValue) -> Result<(), ron::error::SpannedError>;
+
+struct ComponentSerializer {
+    type_name: &'static str,
+    save: SaveComponentFn,
+    load: LoadComponentFn,
+}
+
+fn save_component<T: SerializableComponent>(scene: &Scene, node: &NodeHandle) -> Option<Result<Box<RawValue>, ron::Error>> {
+    scene.get_component::<T>(node).map(RawValue::from_rust)
+}
+
+fn load_component<T: SerializableComponent>(scene: &mut Scene, node: &NodeHandle, raw_value: &RawValue) -> Result<(), ron::error::SpannedError> {
+    let component: T = raw_value.into_rust()?;
+    scene.add_component(node, component);
+    Ok(())
+}
+
+/// Saves scenes into and loads scenes from RON files.
+///
+/// Runtime handles of assets are written as [`AssetRef`]s, so the meshes, materials and textures used by a scene
+/// must be registered in [`SceneSerializer::asset_registry`]. Besides the built-in components, which are Camera,
+/// Light, MeshRenderer and Skybox, user components are saved only if they are registered.
+#[derive(Default)]
+pub struct SceneSerializer {
+    /// The registry used to map asset handles to references.
+    pub asset_registry: AssetRegistry,
+    component_serializers: AHashMap<TypeId, ComponentSerializer>,
+    type_ids_by_name: AHashMap<&'static str, TypeId>,
+}
+
+impl SceneSerializer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a user component so that it is saved and loaded with scenes.
+    pub fn register_component<T: SerializableComponent>(&mut self) -> &mut Self {
+        let type_id = TypeId::of::<T>();
+        self.component_serializers.insert(
+            type_id,
+            ComponentSerializer {
+                type_name: T::TYPE_NAME,
+                save: save_component::<T>,
+                load: load_component::<T>,
+            },
+        );
+        self.type_ids_by_name.insert(T::TYPE_NAME, type_id);
+        self
+    }
+
+    /// Serialize the scene to a pretty RON string.
+    ///
+    /// Only the nodes reachable from [`Scene::root_nodes`] are saved.
+    pub fn scene_to_ron_string(&self, scene: &Scene) -> Result<String, SceneSerializeError> {
+        let scene_data = self.collect_scene_data(scene)?;
+        Ok(ron::ser::to_string_pretty(&scene_data, ron::ser::PrettyConfig::default())?)
+    }
+
+    /// Parse a scene from a RON string.
+    ///
+    /// The managers are used to create the material and mesh of the skybox, if there is one.
+    pub fn scene_from_ron_str(
+        &self,
+        ron_str: &str,
+        shader_manager: &mut ShaderManager,
+        material_manager: &mut MaterialManager,
+        mesh_manager: &mut MeshManager,
+    ) -> Result<Scene, SceneSerializeError> {
+        let scene_data: SceneData = ron::from_str(ron_str)?;
+        self.build_scene(scene_data, shader_manager, material_manager, mesh_manager)
+    }
+
+    /// Save the scene to a RON file.
+    pub fn save<P: AsRef<Path>>(&self, scene: &Scene, path: P) -> Result<(), SceneSerializeError> {
+        std::fs::write(path, self.scene_to_ron_string(scene)?)?;
+        Ok(())
+    }
+
+    /// Load a scene from a RON file.
+    pub fn load<P: AsRef<Path>>(
+        &self,
+        path: P,
+        shader_manager: &mut ShaderManager,
+        material_manager: &mut MaterialManager,
+        mesh_manager: &mut MeshManager,
+    ) -> Result<Scene, SceneSerializeError> {
+        let ron_str = std::fs::read_to_string(path)?;
+        self.scene_from_ron_str(&ron_str, shader_manager, material_manager, mesh_manager)
+    }
+
+    fn collect_scene_data(&self, scene: &Scene) -> Result<SceneData, SceneSerializeError> {
+        let mut scene_data = SceneData {
+            clear_color: scene.clear_color,
+            fog_enabled: scene.fog_enabled,
+            fog_color: scene.fog_color,
+            shadow_atlas_size: scene.shadow_atlas_size,
+            nodes: Vec::new(),
+            root_nodes: Vec::new(),
+        };
+        for root_node in &scene.root_nodes {
+            if let Some(index) = self.collect_node_data(scene, root_node, &mut scene_data.nodes)? {
+                scene_data.root_nodes.push(index);
+            }
+        }
+        Ok(scene_data)
+    }
+
+    /// Collect the data of the node and its descendants, and return the index of the node.
+    pub(crate) fn collect_node_data(&self, scene: &Scene, node_handle: &NodeHandle, nodes: &mut Vec<NodeData>) -> Result<Option<usize>, SceneSerializeError> {
+        let Some(node) = scene.get_node(node_handle) else {
+            return Ok(None);
+        };
+        let index = nodes.len();
+        let transform = &node.transform;
+        let skybox = match scene.get_component::<Skybox>(node_handle) {
+            Some(skybox) => Some(SkyboxData {
+                input_texture: self.texture_ref(&skybox.input_texture)?,
+                is_input_cube_map: skybox.is_inpunt_cube_map,
+                reflection_cube_face_resolution: skybox.reflection_cube_face_resolution,
+            }),
+            None => None,
+        };
+        // The MeshRenderer of the skybox is created by the engine, so it is recreated when loading.
+        let mesh_renderer = match scene.get_component::<MeshRenderer>(node_handle) {
+            Some(mesh_renderer) if skybox.is_none() => Some(MeshRendererData {
+                mesh: self
+                    .asset_registry
+                    .get_mesh_ref(&mesh_renderer.mesh)
+                    .cloned()
+                    .ok_or_else(|| SceneSerializeError::UnregisteredAsset(mesh_renderer.mesh.to_string()))?,
+                materials: mesh_renderer
+                    .materials
+                    .iter()
+                    .map(|material| {
+                        self.asset_registry
+                            .get_material_ref(material)
+                            .cloned()
+                            .ok_or_else(|| SceneSerializeError::UnregisteredAsset(material.to_string()))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?,
+                cast_shadow: mesh_renderer.cast_shadow,
+            }),
+            _ => None,
+        };
+        let camera = match scene.get_component::<Camera>(node_handle) {
+            Some(camera) => Some(CameraData {
+                mode: camera.mode,
+                fov: camera.fov,
+                aspect: camera.aspect,
+                near: camera.near,
+                far: camera.far,
+                target_pos: camera.target_pos.to_array(),
+                up: camera.up.to_array(),
+                clear_color: camera.clear_color,
+                visible_layers: camera.visible_layers.get(),
+                priority: camera.priority,
+                orthogonal_frustum_size: camera.orthogonal_frustum_size,
+                left: camera.left,
+                right: camera.right,
+                top: camera.top,
+                bottom: camera.bottom,
+                viewport: camera.get_viewport().to_array(),
+                color_attachment: if camera.color_attachment == TextureHandle::INVALID {
+                    None
+                } else {
+                    Some(self.texture_ref(&camera.color_attachment)?)
+                },
+            }),
+            None => None,
+        };
+        let light = scene.get_component::<Light>(node_handle).map(|light| LightData {
+            light_type: light.light_type,
+            enabled: light.enabled,
+            color: light.color,
+            intensity: light.intensity,
+            cast_shadow: light.cast_shadow,
+            shadow_settings: light.shadow_settings,
+            culling_mask: light.culling_mask.get(),
+        });
+        let mut components = Vec::new();
+        for component_serializer in self.component_serializers.values() {
+            if let Some(raw_value) = (component_serializer.save)(scene, node_handle) {
+                components.push((component_serializer.type_name.to_owned(), raw_value?));
+            }
+        }
+        // Keep the output stable, since the order of a hash map is random.
+        components.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        nodes.push(NodeData {
+            name: node.name.clone(),
+            enabled: node.enabled,
+            layer: node.layer,
+            tags: node.tags.iter().cloned().collect(),
+            position: transform.position.to_array(),
+            rotation: transform.get_rotation_quat().to_array(),
+            scale: transform.scale.to_array(),
+            children: Vec::new(),
+            camera,
+            light,
+            mesh_renderer,
+            skybox,
+            components,
+        });
+
+        if let Some(children) = &node.children {
+            let mut child_indices = Vec::with_capacity(children.len());
+            for child in children {
+                if let Some(child_index) = self.collect_node_data(scene, child, nodes)? {
+                    child_indices.push(child_index);
+                }
+            }
+            nodes[index].children = child_indices;
+        }
+        Ok(Some(index))
+    }
+
+    fn build_scene(
+        &self,
+        scene_data: SceneData,
+        shader_manager: &mut ShaderManager,
+        material_manager: &mut MaterialManager,
+        mesh_manager: &mut MeshManager,
+    ) -> Result<Scene, SceneSerializeError> {
+        let mut scene = Scene::new();
+        scene.clear_color = scene_data.clear_color;
+        scene.fog_enabled = scene_data.fog_enabled;
+        scene.fog_color = scene_data.fog_color;
+        scene.shadow_atlas_size = scene_data.shadow_atlas_size;
+
+        let parents = validate_hierarchy(&scene_data.nodes)?;
+        for root_index in &scene_data.root_nodes {
+            match parents.get(*root_index) {
+                None => {
+                    return Err(SceneSerializeError::InvalidHierarchy(format!("the root node index {} is out of range", root_index)));
+                }
+                Some(Some(parent_index)) => {
+                    return Err(SceneSerializeError::InvalidHierarchy(format!(
+                        "the root node {} is a child of node {}",
+                        root_index, parent_index
+                    )));
+                }
+                Some(None) => {}
+            }
+        }
+
+        let node_handles = self.build_nodes(&mut scene, &scene_data.nodes)?;
+        for (node_data, node_handle) in scene_data.nodes.iter().zip(&node_handles) {
+            if let Some(skybox_data) = &node_data.skybox {
+                let input_texture = self.texture_handle(&skybox_data.input_texture)?;
+                let (mesh_renderer, mut skybox) = SkyboxBuilder::create_skybox_components(
+                    input_texture,
+                    skybox_data.is_input_cube_map,
+                    shader_manager,
+                    material_manager,
+                    mesh_manager,
+                );
+                skybox.reflection_cube_face_resolution = skybox_data.reflection_cube_face_resolution;
+                scene.add_component(node_handle, mesh_renderer);
+                scene.add_component(node_handle, skybox);
+            }
+        }
+        for root_index in &scene_data.root_nodes {
+            scene.add(node_handles[*root_index]);
+        }
+        Ok(scene)
+    }
+
+    /// Create the nodes with their components except Skybox, and attach the children to their parents.
+    ///
+    /// The nodes are not added to [`Scene::root_nodes`]. The hierarchy is validated before any node is created, and if
+    /// any other error occurs, the created nodes are destroyed.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<NodeHandle>` - The handles of created nodes, in the same order as `nodes`.
+    pub(crate) fn build_nodes(&self, scene: &mut Scene, nodes: &[NodeData]) -> Result<Vec<NodeHandle>, SceneSerializeError> {
+        validate_hierarchy(nodes)?;
+        let mut node_handles = Vec::with_capacity(nodes.len());
+        for node_data in nodes {
+            let node_handle = scene.create_node(node_data.name.clone());
+            node_handles.push(node_handle);
+            {
+                let node = scene.get_node_mut_forcely(&node_handle);
+                node.enabled = node_data.enabled;
+                node.layer = node_data.layer;
+                node.tags = node_data.tags.iter().cloned().collect();
+                node.transform
+                    .set_position_rotation_scale_from_arrays(node_data.position, node_data.rotation, node_data.scale);
+            }
+            if let Err(error) = self.add_components(scene, &node_handle, node_data) {
+                for node_handle in &node_handles {
+                    scene.destroy_node(node_handle);
+                }
+                return Err(error);
+            }
+        }
+
+        for (node_data, node_handle) in nodes.iter().zip(&node_handles) {
+            for child_index in &node_data.children {
+                scene.attach_to_parent(&node_handles[*child_index], *node_handle);
+            }
+        }
+        Ok(node_handles)
+    }
+
+    fn add_components(&self, scene: &mut Scene, node_handle: &NodeHandle, node_data: &NodeData) -> Result<(), SceneSerializeError> {
+        self.add_builtin_components(scene, node_handle, node_data)?;
+        for (type_name, raw_value) in &node_data.components {
+            let component_serializer = self
+                .type_ids_by_name
+                .get(type_name.as_str())
+                .and_then(|type_id| self.component_serializers.get(type_id))
+                .ok_or_else(|| SceneSerializeError::UnknownComponent(type_name.clone()))?;
+            (component_serializer.load)(scene, node_handle, raw_value)?;
+        }
+        Ok(())
+    }
+
+    fn add_builtin_components(
+        &self,
+        scene: &mut Scene,
+        node_handle: &NodeHandle,
+        node_data: &NodeData,
+    ) -> Result<(), SceneSerializeError> {
+        if let Some(camera_data) = &node_data.camera {
+            let mut camera = Camera::default();
+            camera.mode = camera_data.mode;
+            camera.fov = camera_data.fov;
+            camera.aspect = camera_data.aspect;
+            camera.near = camera_data.near;
+            camera.far = camera_data.far;
+            camera.target_pos = Vec3::from_array(camera_data.target_pos);
+            camera.up = Vec3::from_array(camera_data.up);
+            camera.clear_color = camera_data.clear_color;
+            camera.visible_layers = LayerMask::new(camera_data.visible_layers);
+            camera.priority = camera_data.priority;
+            camera.orthogonal_frustum_size = camera_data.orthogonal_frustum_size;
+            camera.left = camera_data.left;
+            camera.right = camera_data.right;
+            camera.top = camera_data.top;
+            camera.bottom = camera_data.bottom;
+            camera.set_viewport_by_vector(Vec4::from_array(camera_data.viewport));
+            if let Some(color_attachment) = &camera_data.color_attachment {
+                camera.color_attachment = self.texture_handle(color_attachment)?;
+            }
+            scene.add_component(node_handle, camera);
+        }
+
+        if let Some(light_data) = &node_data.light {
+            let mut light = match light_data.light_type {
+                LightType::Spot { max_distance, inner_angle, outer_angle } => Light::new_spot_light(
+                    max_distance,
+                    inner_angle,
+                    outer_angle,
+                    light_data.color,
+                    light_data.intensity,
+                    light_data.cast_shadow,
+                ),
+                light_type => Light::new(light_type, light_data.color, light_data.intensity, light_data.cast_shadow),
+            };
+            light.enabled = light_data.enabled;
+            light.shadow_settings = light_data.shadow_settings;
+            light.culling_mask = LayerMask::new(light_data.culling_mask);
+            scene.add_component(node_handle, light);
+        }
+
+        if let Some(mesh_renderer_data) = &node_data.mesh_renderer {
+            let mesh = self
+                .asset_registry
+                .get_mesh(&mesh_renderer_data.mesh)
+                .ok_or_else(|| SceneSerializeError::UnknownAsset(mesh_renderer_data.mesh.clone()))?;
+            let materials = mesh_renderer_data
+                .materials
+                .iter()
+                .map(|material| {
+                    self.asset_registry
+                        .get_material(material)
+                        .ok_or_else(|| SceneSerializeError::UnknownAsset(material.clone()))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let mut mesh_renderer = MeshRenderer::new(mesh, materials);
+            mesh_renderer.cast_shadow = mesh_renderer_data.cast_shadow;
+            scene.add_component(node_handle, mesh_renderer);
+        }
+
+        Ok(())
+    }
+
+    fn texture_ref(&self, texture: &TextureHandle) -> Result<AssetRef, SceneSerializeError> {
+        self.asset_registry
+            .get_texture_ref(texture)
+            .cloned()
+            .ok_or_else(|| SceneSerializeError::UnregisteredAsset(texture.to_string()))
+    }
+
+    pub(crate) fn texture_handle(&self, asset_ref: &AssetRef) -> Result<TextureHandle, SceneSerializeError> {
+        self.asset_registry
+            .get_texture(asset_ref)
+            .ok_or_else(|| SceneSerializeError::UnknownAsset(asset_ref.clone()))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct SceneData {
+    clear_color: Color,
+    fog_enabled: bool,
+    fog_color: Color,
+    shadow_atlas_size: u32,
+    /// All nodes in depth first order.
+    nodes: Vec<NodeData>,
+    /// The indices of root nodes in `nodes`.
+    root_nodes: Vec<usize>,
+}
+
+/// Check that every child index is in range, each node has at most one parent and there is no cycle.
+///
+/// # Returns
+///
+/// * `Vec<Option<usize>>` - The parent index of each node.
+fn validate_hierarchy(nodes: &[NodeData]) -> Result<Vec<Option<usize>>, SceneSerializeError> {
+    let mut parents = vec![None; nodes.len()];
+    for (index, node_data) in nodes.iter().enumerate() {
+        for &child_index in &node_data.children {
+            if child_index >= nodes.len() {
+                return Err(SceneSerializeError::InvalidHierarchy(format!(
+                    "the child index {} of node {} is out of range",
+                    child_index, index
+                )));
+            }
+            if child_index == index {
+                return Err(SceneSerializeError::InvalidHierarchy(format!("the node {} is its own child", index)));
+            }
+            if let Some(parent_index) = parents[child_index].replace(index) {
+                return Err(SceneSerializeError::InvalidHierarchy(format!(
+                    "the node {} is a child of both node {} and node {}",
+                    child_index, parent_index, index
+                )));
+            }
+        }
+    }
+    // With at most one parent per node, a chain of parents longer than the node count must contain a cycle.
+    for index in 0..nodes.len() {
+        let mut ancestor = parents[index];
+        for _ in 0..nodes.len() {
+            match ancestor {
+                Some(ancestor_index) if ancestor_index == index => {
+                    return Err(SceneSerializeError::InvalidHierarchy(format!("the node {} is its own ancestor", index)));
+                }
+                Some(ancestor_index) => ancestor = parents[ancestor_index],
+                None => break,
+            }
+        }
+    }
+    Ok(parents)
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct NodeData {
+    pub(crate) name: String,
+    pub(crate) enabled: bool,
+    pub(crate) layer: Layer,
+    #[serde(default)]
+    pub(crate) tags: Vec<String>,
+    pub(crate) position: [f32; 3],
+    /// The rotation quaternion.
+    pub(crate) rotation: [f32; 4],
+    pub(crate) scale: [f32; 3],
+    /// The indices of children in `SceneData::nodes`.
+    #[serde(default)]
+    pub(crate) children: Vec<usize>,
+    #[serde(default)]
+    pub(crate) camera: Option<CameraData>,
+    #[serde(default)]
+    pub(crate) light: Option<LightData>,
+    #[serde(default)]
+    pub(crate) mesh_renderer: Option<MeshRendererData>,
+    #[serde(default)]
+    pub(crate) skybox: Option<SkyboxData>,
+    /// Registered user components, which are pairs of type name and value.
+    #[serde(default)]
+    pub(crate) components: Vec<(String, Box<RawValue>)>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct CameraData {
+    mode: CameraMode,
+    fov: f32,
+    aspect: f32,
+    near: f32,
+    far: f32,
+    target_pos: [f32; 3],
+    up: [f32; 3],
+    clear_color: Option<Color>,
+    visible_layers: u32,
+    priority: u32,
+    orthogonal_frustum_size: f32,
+    left: f32,
+    right: f32,
+    top: f32,
+    bottom: f32,
+    viewport: [f32; 4],
+    color_attachment: Option<AssetRef>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct LightData {
+    light_type: LightType,
+    enabled: bool,
+    color: Color,
+    intensity: f32,
+    cast_shadow: bool,
+    shadow_settings: ShadowSettings,
+    #[serde(default = "LightData::default_culling_mask")]
+    culling_mask: u32,
+}
+
+impl LightData {
+    fn default_culling_mask() -> u32 {
+        LayerMask::ALL.get()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct MeshRendererData {
+    mesh: AssetRef,
+    materials: Vec<AssetRef>,
+    cast_shadow: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct SkyboxData {
+    pub(crate) input_texture: AssetRef,
+    pub(crate) is_input_cube_map: bool,
+    pub(crate) reflection_cube_face_resolution: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assets::{MaterialHandle, MeshHandle}, impl_component};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Health {
+        value: i32,
+    }
+
+    impl_component!(Health);
+
+    impl SerializableComponent for Health {
+        const TYPE_NAME: &'static str = "Health";
+    }
+
+    fn hierarchy_scene_data(children: &[&[usize]], root_nodes: &[usize]) -> SceneData {
+        let nodes = children
+            .iter()
+            .enumerate()
+            .map(|(index, children)| NodeData {
+                name: format!("Node{}", index),
+                enabled: true,
+                layer: Layer::Default,
+                tags: Vec::new(),
+                position: [0.0; 3],
+                rotation: [0.0, 0.0, 0.0, 1.0],
+                scale: [1.0; 3],
+                children: children.to_vec(),
+                camera: None,
+                light: None,
+                mesh_renderer: None,
+                skybox: None,
+                components: Vec::new(),
+            })
+            .collect();
+        SceneData {
+            clear_color: Color::BLACK,
+            fog_enabled: false,
+            fog_color: Color::BLACK,
+            shadow_atlas_size: 1024,
+            nodes,
+            root_nodes: root_nodes.to_vec(),
+        }
+    }
+
+    fn build_hierarchy(children: &[&[usize]], root_nodes: &[usize]) -> Result<Scene, SceneSerializeError> {
+        SceneSerializer::new().build_scene(
+            hierarchy_scene_data(children, root_nodes),
+            &mut ShaderManager::new(),
+            &mut MaterialManager::new(),
+            &mut MeshManager::new(),
+        )
+    }
+
+    #[test]
+    fn test_invalid_hierarchy() {
+        let scene = build_hierarchy(&[&[1, 2], &[], &[]], &[0]).unwrap();
+        assert_eq!(scene.root_nodes.len(), 1);
+        assert_eq!(scene.get_node_forcely(&scene.root_nodes[0]).children.as_ref().unwrap().len(), 2);
+
+        // A child index is out of range.
+        assert!(matches!(build_hierarchy(&[&[1, 3], &[], &[]], &[0]), Err(SceneSerializeError::InvalidHierarchy(_))));
+        // A node is its own child.
+        assert!(matches!(build_hierarchy(&[&[0]], &[0]), Err(SceneSerializeError::InvalidHierarchy(_))));
+        // A node has two parents.
+        assert!(matches!(build_hierarchy(&[&[2], &[2], &[]], &[0, 1]), Err(SceneSerializeError::InvalidHierarchy(_))));
+        // A cycle, A -> B -> A.
+        assert!(matches!(build_hierarchy(&[&[1], &[0]], &[]), Err(SceneSerializeError::InvalidHierarchy(_))));
+        // A longer cycle which is reachable from a root.
+        assert!(matches!(
+            build_hierarchy(&[&[1], &[2], &[3], &[1]], &[0]),
+            Err(SceneSerializeError::InvalidHierarchy(_))
+        ));
+        // A root index is out of range.
+        assert!(matches!(build_hierarchy(&[&[]], &[1]), Err(SceneSerializeError::InvalidHierarchy(_))));
+        // A root node has a parent.
+        assert!(matches!(build_hierarchy(&[&[1], &[]], &[0, 1]), Err(SceneSerializeError::InvalidHierarchy(_))));
+
+        // Invalid prefabs are rejected before any node is created.
+        let mut scene = Scene::new();
+        let nodes = hierarchy_scene_data(&[&[1], &[0]], &[]).nodes;
+        assert!(matches!(SceneSerializer::new().build_nodes(&mut scene, &nodes), Err(SceneSerializeError::InvalidHierarchy(_))));
+        assert_eq!(scene.node_arena.iter().count(), 0);
+    }
+
+    #[test]
+    fn test_scene_round_trip() {
+        let mut serializer = SceneSerializer::new();
+        serializer.register_component::<Health>();
+        let mesh = MeshHandle::new(3);
+        let material = MaterialHandle::new(5);
+        serializer.asset_registry.register_mesh(AssetRef::Path("meshes/cube.glb".to_owned()), mesh);
+        serializer.asset_registry.register_material(AssetRef::Guid("0d2c-pbr".to_owned()), material);
+
+        let mut scene = Scene::new();
+        scene.fog_enabled = true;
+        let parent = scene.create_node("Parent");
+        scene.get_node_mut_forcely(&parent).transform.set_position(Vec3::new(1.0, 2.0, 3.0));
+        let mut light = Light::new_spot_light(10.0, 0.3, 0.5, Color::WHITE, 2.0, true);
+        light.culling_mask = Layer::Default | Layer::Custom1;
+        scene.add_component(&parent, light);
+        let child = scene.create_node("Child");
+        {
+            let child_node = scene.get_node_mut_forcely(&child);
+            child_node.enabled = false;
+            child_node.layer = Layer::Custom1;
+            child_node.add_tag("Loot");
+        }
+        scene.add_component(&child, MeshRenderer::new(mesh, vec![material]));
+        scene.add_component(&child, Health { value: 42 });
+        scene.attach_to_parent(&child, parent);
+        scene.add(parent);
+        let camera_node = scene.create_node("Camera");
+        scene.add_component(&camera_node, Camera::new_orthogonal_by_size(4.0, 1.0, 0.1, 100.0));
+        scene.add(camera_node);
+
+        let ron_str = serializer.scene_to_ron_string(&scene).unwrap();
+        let loaded_scene = serializer
+            .scene_from_ron_str(&ron_str, &mut ShaderManager::new(), &mut MaterialManager::new(), &mut MeshManager::new())
+            .unwrap();
+        assert_eq!(serializer.scene_to_ron_string(&loaded_scene).unwrap(), ron_str);
+
+        // The file round trip of `Scene::save` and `Scene::load` writes the same RON string.
+        let path = std::env::temp_dir().join(format!("imagic_scene_round_trip_{}.ron", std::process::id()));
+        scene.save(&path, &serializer).unwrap();
+        let file_scene =
+            Scene::load(&path, &serializer, &mut ShaderManager::new(), &mut MaterialManager::new(), &mut MeshManager::new());
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(serializer.scene_to_ron_string(&file_scene.unwrap()).unwrap(), ron_str);
+
+        assert!(loaded_scene.fog_enabled);
+        assert_eq!(loaded_scene.root_nodes.len(), 2);
+        let loaded_parent = loaded_scene.get_node_forcely(&loaded_scene.root_nodes[0]);
+        assert_eq!(loaded_parent.name, "Parent");
+        assert_eq!(loaded_parent.transform.position, Vec3::new(1.0, 2.0, 3.0));
+        let light = loaded_scene.get_component::<Light>(&loaded_parent.id).unwrap();
+        assert_eq!(light.get_outer_angle(), 0.5);
+        assert_eq!(light.cached_outer_cos, 0.5f32.cos());
+        assert_eq!(light.culling_mask, Layer::Default | Layer::Custom1);
+
+        let loaded_child_handle = loaded_parent.children.as_ref().unwrap()[0];
+        let loaded_child = loaded_scene.get_node_forcely(&loaded_child_handle);
+        assert!(!loaded_child.enabled);
+        assert_eq!(loaded_child.layer, Layer::Custom1);
+        assert!(loaded_child.has_tag("Loot"));
+        assert_eq!(loaded_child.parent, Some(loaded_parent.id));
+        let mesh_renderer = loaded_scene.get_component::<MeshRenderer>(&loaded_child_handle).unwrap();
+        assert_eq!(mesh_renderer.mesh, mesh);
+        assert_eq!(mesh_renderer.materials, vec![material]);
+        assert_eq!(loaded_scene.get_component::<Health>(&loaded_child_handle), Some(&Health { value: 42 }));
+
+        let loaded_camera = loaded_scene.get_component::<Camera>(&loaded_scene.root_nodes[1]).unwrap();
+        assert_eq!(loaded_camera.mode, CameraMode::Orthogonal);
+        assert_eq!(loaded_camera.orthogonal_frustum_size, 4.0);
+
+        // Handles which are not registered can not be saved.
+        scene.add_component(&camera_node, MeshRenderer::new(MeshHandle::new(7), vec![]));
+        assert!(matches!(serializer.scene_to_ron_string(&scene), Err(SceneSerializeError::UnregisteredAsset(_))));
+        // Unknown components can not be loaded.
+        let unknown_component_str = ron_str.replace("\"Health\"", "\"Mana\"");
+        assert!(matches!(
+            serializer.scene_from_ron_str(&unknown_component_str, &mut ShaderManager::new(), &mut MaterialManager::new(), &mut MeshManager::new()),
+            Err(SceneSerializeError::UnknownComponent(_))
+        ));
+    }
+}
@@ -0,0 +1,293 @@
+use thiserror::Error;
+
+use crate::core::LogicContext;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ScheduleError {
+    #[error("A system named {0} already exists")]
+    DuplicateSystem(String),
+    #[error("The ordering constraints of system {0} form a cycle")]
+    Cycle(String),
+}
+
+/// The stages of a frame, in running order.
+///
+/// The time, the input and the fixed updates are updated before [`Stage::PreUpdate`]. The frame is rendered between
+/// [`Stage::PreRender`] and [`Stage::PostRender`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stage {
+    /// Runs before behaviors and scripts.
+    PreUpdate,
+    /// Runs the behaviors and scripts, see [`System::BEHAVIORS`].
+    Update,
+    /// Propagates transforms, see [`System::TRANSFORM_PROPAGATION`].
+    PostUpdate,
+    /// Collects the lights right before the render frame is generated, see [`System::COLLECT_LIGHTS`].
+    PreRender,
+    /// Runs after the frame is rendered.
+    PostRender,
+}
+
+impl Stage {
+    /// All stages in running order.
+    pub const ALL: [Stage; 5] = [Stage::PreUpdate, Stage::Update, Stage::PostUpdate, Stage::PreRender, Stage::PostRender];
+}
+
+type SystemFn = Box<dyn FnMut(&mut LogicContext)>;
+
+/// The work of the engine which runs as systems, so that user systems can be ordered relative to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BuiltinSystem {
+    Behaviors,
+    TransformPropagation,
+    CollectLights,
+}
+
+pub(crate) enum SystemKind {
+    Builtin(BuiltinSystem),
+    Custom(SystemFn),
+}
+
+/// A named function which runs once per frame in a [`Stage`].
+///
+/// Systems in a stage run in registration order unless they are constrained by [`System::before`] and
+/// [`System::after`]. Constraints only order systems of the same stage, and constraints on systems which are not
+/// registered are ignored.
+///
+/// # Examples
+///
+/// ```ignore
+/// fn follow_target(logic_context: &mut LogicContext) {
+///     // The model matrices of this frame are resolved here.
+/// }
+///
+/// engine.add_system(System::new("follow_target", Stage::PostUpdate, follow_target).after(System::TRANSFORM_PROPAGATION))?;
+/// ```
+pub struct System {
+    name: String,
+    stage: Stage,
+    before: Vec<String>,
+    after: Vec<String>,
+    pub(crate) kind: SystemKind,
+}
+
+impl System {
+    /// The built-in system in [`Stage::Update`] which runs the behaviors, the scripts and the queued commands.
+    pub const BEHAVIORS: &'static str = "imagic::behaviors";
+    /// The built-in system in [`Stage::PostUpdate`] which propagates transforms and updates the model matrices and
+    /// the bounds of mesh renderers.
+    pub const TRANSFORM_PROPAGATION: &'static str = "imagic::transform_propagation";
+    /// The built-in system in [`Stage::PreRender`] which collects the lights of loaded scenes for the render frame.
+    pub const COLLECT_LIGHTS: &'static str = "imagic::collect_lights";
+
+    /// Create a system.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The unique name of the system, which is referenced by ordering constraints.
+    /// * `stage` - The stage in which the system runs.
+    /// * `system` - The function to run, which can be a plain function or a closure.
+    pub fn new(name: impl Into<String>, stage: Stage, system: impl FnMut(&mut LogicContext) + 'static) -> Self {
+        Self::new_internal(name.into(), stage, SystemKind::Custom(Box::new(system)))
+    }
+
+    fn new_internal(name: String, stage: Stage, kind: SystemKind) -> Self {
+        Self {
+            name,
+            stage,
+            before: Vec::new(),
+            after: Vec::new(),
+            kind,
+        }
+    }
+
+    /// Run before the system named `name`.
+    pub fn before(mut self, name: impl Into<String>) -> Self {
+        self.before.push(name.into());
+        self
+    }
+
+    /// Run after the system named `name`.
+    pub fn after(mut self, name: impl Into<String>) -> Self {
+        self.after.push(name.into());
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn stage(&self) -> Stage {
+        self.stage
+    }
+
+    fn is_builtin(&self) -> bool {
+        matches!(self.kind, SystemKind::Builtin(_))
+    }
+}
+
+/// The systems of one stage.
+#[derive(Default)]
+pub(crate) struct StageSystems {
+    /// The systems in registration order.
+    pub(crate) systems: Vec<System>,
+    /// The indices of systems in running order.
+    pub(crate) order: Vec<usize>,
+}
+
+impl StageSystems {
+    /// Sort the systems topologically. Among systems whose constraints are satisfied, the earlier registered runs first.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Vec<usize>>` - The running order, or None if the constraints form a cycle.
+    fn sort(&self) -> Option<Vec<usize>> {
+        let index_of = |name: &String| self.systems.iter().position(|system| system.name == *name);
+        let count = self.systems.len();
+        let mut successors = vec![Vec::new(); count];
+        let mut predecessor_counts = vec![0; count];
+        for (index, system) in self.systems.iter().enumerate() {
+            let edges = system.before.iter().filter_map(index_of).map(|other| (index, other))
+                .chain(system.after.iter().filter_map(index_of).map(|other| (other, index)));
+            for (from, to) in edges {
+                successors[from].push(to);
+                predecessor_counts[to] += 1;
+            }
+        }
+
+        let mut order = Vec::with_capacity(count);
+        let mut is_ready: Vec<bool> = predecessor_counts.iter().map(|count| *count == 0).collect();
+        while let Some(index) = is_ready.iter().position(|ready| *ready) {
+            is_ready[index] = false;
+            order.push(index);
+            for &successor in &successors[index] {
+                predecessor_counts[successor] -= 1;
+                if predecessor_counts[successor] == 0 {
+                    is_ready[successor] = true;
+                }
+            }
+        }
+        (order.len() == count).then_some(order)
+    }
+}
+
+/// The systems of all stages, see [`System`].
+pub(crate) struct Schedule {
+    pub(crate) stages: [StageSystems; Stage::ALL.len()],
+}
+
+impl Schedule {
+    /// Create a schedule with the built-in systems.
+    pub(crate) fn new() -> Self {
+        let mut schedule = Self { stages: Default::default() };
+        let builtin_systems = [
+            (System::BEHAVIORS, Stage::Update, BuiltinSystem::Behaviors),
+            (System::TRANSFORM_PROPAGATION, Stage::PostUpdate, BuiltinSystem::TransformPropagation),
+            (System::COLLECT_LIGHTS, Stage::PreRender, BuiltinSystem::CollectLights),
+        ];
+        for (name, stage, builtin_system) in builtin_systems {
+            let _ = schedule.add_system(System::new_internal(name.to_owned(), stage, SystemKind::Builtin(builtin_system)));
+        }
+        schedule
+    }
+
+    pub(crate) fn add_system(&mut self, system: System) -> Result<(), ScheduleError> {
+        if self.contains(&system.name) {
+            return Err(ScheduleError::DuplicateSystem(system.name));
+        }
+        let stage_systems = &mut self.stages[system.stage as usize];
+        stage_systems.systems.push(system);
+        match stage_systems.sort() {
+            Some(order) => {
+                stage_systems.order = order;
+                Ok(())
+            }
+            None => {
+                let system = stage_systems.systems.pop().expect("the system has been pushed");
+                Err(ScheduleError::Cycle(system.name))
+            }
+        }
+    }
+
+    /// Remove a system which is not built-in.
+    pub(crate) fn remove_system(&mut self, name: &str) -> bool {
+        for stage_systems in &mut self.stages {
+            if let Some(index) = stage_systems.systems.iter().position(|system| system.name == name) {
+                if stage_systems.systems[index].is_builtin() {
+                    log::warn!("Built-in system {} can not be removed.", name);
+                    return false;
+                }
+                stage_systems.systems.remove(index);
+                // Removing a system never makes a cycle.
+                stage_systems.order = stage_systems.sort().unwrap_or_default();
+                return true;
+            }
+        }
+        false
+    }
+
+    pub(crate) fn contains(&self, name: &str) -> bool {
+        self.stages.iter().any(|stage_systems| stage_systems.systems.iter().any(|system| system.name == name))
+    }
+
+    /// The names of the systems of a stage in running order.
+    pub(crate) fn system_names(&self, stage: Stage) -> Vec<&str> {
+        let stage_systems = &self.stages[stage as usize];
+        stage_systems.order.iter().map(|&index| stage_systems.systems[index].name()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::*;
+    use crate::{core::{Engine, EngineOptions}, math::{Mat4, Vec3}};
+
+    #[test]
+    fn test_schedule() {
+        let mut schedule = Schedule::new();
+        schedule.add_system(System::new("c", Stage::PostUpdate, |_| {})).unwrap();
+        schedule.add_system(System::new("a", Stage::PostUpdate, |_| {}).before(System::TRANSFORM_PROPAGATION).before("c")).unwrap();
+        schedule.add_system(System::new("b", Stage::PostUpdate, |_| {}).after("a").before("c").after("not registered")).unwrap();
+        assert_eq!(schedule.system_names(Stage::PostUpdate), vec!["a", System::TRANSFORM_PROPAGATION, "b", "c"]);
+
+        assert_eq!(
+            schedule.add_system(System::new("d", Stage::PostUpdate, |_| {}).after("c").before("a")),
+            Err(ScheduleError::Cycle("d".to_owned()))
+        );
+        assert_eq!(
+            schedule.add_system(System::new("a", Stage::PreUpdate, |_| {})),
+            Err(ScheduleError::DuplicateSystem("a".to_owned()))
+        );
+        assert!(!schedule.remove_system(System::TRANSFORM_PROPAGATION));
+        assert!(schedule.remove_system("a"));
+        assert_eq!(schedule.system_names(Stage::PostUpdate), vec![System::TRANSFORM_PROPAGATION, "b", "c"]);
+    }
+
+    #[test]
+    fn test_systems_after_transform_propagation() {
+        let mut engine = Engine::new(EngineOptions::default());
+        let scene = engine.world.current_scene_mut();
+        let node = scene.create_node("Moved");
+        scene.add(node);
+        scene.get_node_mut_forcely(&node).transform.set_position(Vec3::new(1.0, 2.0, 3.0));
+
+        let model_matrices = Rc::new(RefCell::new(Vec::new()));
+        for (name, before) in [("after", false), ("before", true)] {
+            let model_matrices = model_matrices.clone();
+            let system = System::new(name, Stage::PostUpdate, move |logic_context| {
+                let model_matrix = logic_context.world.current_scene_mut().get_node_forcely(&node).transform.model_matrix;
+                model_matrices.borrow_mut().push((name, model_matrix));
+            });
+            let system = if before { system.before(System::TRANSFORM_PROPAGATION) } else { system.after(System::TRANSFORM_PROPAGATION) };
+            engine.add_system(system).unwrap();
+        }
+
+        engine.run_stage(Stage::PostUpdate);
+        assert_eq!(
+            *model_matrices.borrow(),
+            vec![("before", Mat4::IDENTITY), ("after", Mat4::from_translation(Vec3::new(1.0, 2.0, 3.0)))]
+        );
+    }
+}
@@ -0,0 +1,518 @@
+use ahash::AHashMap;
+
+use crate::{
+    assets::MeshManager,
+    components::mesh_renderer::MeshRenderer,
+    core::{Layer, LayerMask, NodeHandle, Scene},
+    math::{Aabb, BoundingSphere, Frustum, Ray, Vec3},
+};
+
+/// The margin by which the boxes of leaves are enlarged, so that nodes moving a little do not change the tree.
+const AABB_MARGIN: f32 = 0.1;
+
+/// A node hit by a ray.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RaycastHit {
+    pub node: NodeHandle,
+    /// The distance from the origin of the ray to the hit point.
+    pub distance: f32,
+    /// The hit point in world space.
+    pub point: Vec3,
+}
+
+#[derive(Clone, Copy)]
+enum TreeNodeKind {
+    /// A node in the scene with its world bounds.
+    Leaf { node: NodeHandle, layer: Layer, aabb: Aabb },
+    Branch([usize; 2]),
+}
+
+struct TreeNode {
+    /// The enlarged box of a leaf, or the box containing both children of a branch.
+    aabb: Aabb,
+    parent: Option<usize>,
+    kind: TreeNodeKind,
+}
+
+/// SpatialIndex
+///
+/// A dynamic bounding volume hierarchy over the world bounds of the enabled mesh renderers of a scene, which answers
+/// ray, sphere, box and frustum queries without testing every node.
+///
+/// The engine updates it after transforms are propagated, see [`Scene::spatial_index`]. Leaves are inserted where
+/// they increase the surface area of the tree least, and are reinserted only when nodes move out of their enlarged
+/// boxes.
+#[derive(Default)]
+pub struct SpatialIndex {
+    tree_nodes: Vec<TreeNode>,
+    /// The slots of removed tree nodes, which are reused by new tree nodes.
+    free_slots: Vec<usize>,
+    root: Option<usize>,
+    leaves: AHashMap<NodeHandle, usize>,
+}
+
+impl SpatialIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The count of nodes in the index.
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    pub fn contains(&self, node: &NodeHandle) -> bool {
+        self.leaves.contains_key(node)
+    }
+
+    /// The layer of a node in the index, which is the layer of the node when it was inserted or updated last time.
+    pub(crate) fn layer(&self, node: &NodeHandle) -> Option<Layer> {
+        match self.tree_nodes[*self.leaves.get(node)?].kind {
+            TreeNodeKind::Leaf { layer, .. } => Some(layer),
+            TreeNodeKind::Branch(_) => None,
+        }
+    }
+
+    /// The world bounds of a node in the index.
+    pub fn bounds(&self, node: &NodeHandle) -> Option<Aabb> {
+        match self.tree_nodes[*self.leaves.get(node)?].kind {
+            TreeNodeKind::Leaf { aabb, .. } => Some(aabb),
+            TreeNodeKind::Branch(_) => None,
+        }
+    }
+
+    /// The nodes whose bounds are hit by a ray within a distance, sorted from near to far.
+    pub fn raycast(&self, ray: &Ray, max_distance: f32, layer_mask: LayerMask) -> Vec<RaycastHit> {
+        let mut hits = Vec::new();
+        self.query(
+            |aabb| ray.intersect_aabb(aabb).is_some_and(|distance| distance <= max_distance),
+            layer_mask,
+            |node, aabb| {
+                if let Some(distance) = ray.intersect_aabb(aabb) {
+                    hits.push(RaycastHit { node, distance, point: ray.at(distance) });
+                }
+            },
+        );
+        hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        hits
+    }
+
+    /// The nodes whose bounds intersect a sphere.
+    pub fn overlap_sphere(&self, bounding_sphere: &BoundingSphere, layer_mask: LayerMask) -> Vec<NodeHandle> {
+        self.collect(|aabb| bounding_sphere.intersects_aabb(aabb), layer_mask)
+    }
+
+    /// The nodes whose bounds intersect a box.
+    pub fn overlap_aabb(&self, aabb: &Aabb, layer_mask: LayerMask) -> Vec<NodeHandle> {
+        self.collect(|other| aabb.intersects(other), layer_mask)
+    }
+
+    /// The nodes whose bounds are inside or intersect a frustum, e.g., to select the nodes in a rectangle on screen.
+    pub fn overlap_frustum(&self, frustum: &Frustum, layer_mask: LayerMask) -> Vec<NodeHandle> {
+        self.collect(|aabb| frustum.intersects_aabb(aabb), layer_mask)
+    }
+
+    /// Insert a node, or update its layer and bounds if it is in the index.
+    pub(crate) fn insert_or_update(&mut self, node: NodeHandle, layer: Layer, aabb: Aabb) {
+        let kind = TreeNodeKind::Leaf { node, layer, aabb };
+        let leaf = match self.leaves.get(&node) {
+            Some(&leaf) if self.tree_nodes[leaf].aabb.contains_aabb(&aabb) => {
+                self.tree_nodes[leaf].kind = kind;
+                return;
+            }
+            Some(&leaf) => {
+                self.remove_leaf(leaf);
+                self.tree_nodes[leaf] = TreeNode { aabb: Self::enlarge(&aabb), parent: None, kind };
+                leaf
+            }
+            None => {
+                let leaf = self.allocate(TreeNode { aabb: Self::enlarge(&aabb), parent: None, kind });
+                self.leaves.insert(node, leaf);
+                leaf
+            }
+        };
+        self.insert_leaf(leaf);
+    }
+
+    /// Remove a node.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - Whether the node was in the index.
+    pub(crate) fn remove(&mut self, node: &NodeHandle) -> bool {
+        let Some(leaf) = self.leaves.remove(node) else {
+            return false;
+        };
+        self.remove_leaf(leaf);
+        self.free_slots.push(leaf);
+        true
+    }
+
+    fn enlarge(aabb: &Aabb) -> Aabb {
+        Aabb::new(aabb.min - Vec3::splat(AABB_MARGIN), aabb.max + Vec3::splat(AABB_MARGIN))
+    }
+
+    fn surface_area(aabb: &Aabb) -> f32 {
+        let size = aabb.size();
+        2.0 * (size.x * size.y + size.y * size.z + size.z * size.x)
+    }
+
+    fn allocate(&mut self, tree_node: TreeNode) -> usize {
+        if let Some(slot) = self.free_slots.pop() {
+            self.tree_nodes[slot] = tree_node;
+            slot
+        } else {
+            self.tree_nodes.push(tree_node);
+            self.tree_nodes.len() - 1
+        }
+    }
+
+    fn insert_leaf(&mut self, leaf: usize) {
+        let Some(root) = self.root else {
+            self.tree_nodes[leaf].parent = None;
+            self.root = Some(leaf);
+            return;
+        };
+
+        // Descend to the sibling of the leaf by the surface area heuristic.
+        let leaf_aabb = self.tree_nodes[leaf].aabb;
+        let mut sibling = root;
+        while let TreeNodeKind::Branch(children) = self.tree_nodes[sibling].kind {
+            let area = Self::surface_area(&self.tree_nodes[sibling].aabb);
+            let combined_area = Self::surface_area(&self.tree_nodes[sibling].aabb.merge(&leaf_aabb));
+            // The cost of pairing the leaf with this node, and the cost of enlarging this node to reach its children.
+            let cost = 2.0 * combined_area;
+            let inheritance_cost = 2.0 * (combined_area - area);
+            let child_cost = |child: usize| {
+                let child_aabb = &self.tree_nodes[child].aabb;
+                let merged_area = Self::surface_area(&child_aabb.merge(&leaf_aabb));
+                match self.tree_nodes[child].kind {
+                    TreeNodeKind::Leaf { .. } => merged_area + inheritance_cost,
+                    TreeNodeKind::Branch(_) => merged_area - Self::surface_area(child_aabb) + inheritance_cost,
+                }
+            };
+            let costs = children.map(child_cost);
+            if cost < costs[0] && cost < costs[1] {
+                break;
+            }
+            sibling = if costs[0] <= costs[1] { children[0] } else { children[1] };
+        }
+
+        let old_parent = self.tree_nodes[sibling].parent;
+        let new_parent = self.allocate(TreeNode {
+            aabb: self.tree_nodes[sibling].aabb.merge(&leaf_aabb),
+            parent: old_parent,
+            kind: TreeNodeKind::Branch([sibling, leaf]),
+        });
+        self.tree_nodes[sibling].parent = Some(new_parent);
+        self.tree_nodes[leaf].parent = Some(new_parent);
+        match old_parent {
+            Some(old_parent) => {
+                self.replace_child(old_parent, sibling, new_parent);
+                self.refit(old_parent);
+            }
+            None => self.root = Some(new_parent),
+        }
+    }
+
+    /// Detach a leaf from the tree, and free its parent which is replaced by its sibling.
+    fn remove_leaf(&mut self, leaf: usize) {
+        let Some(parent) = self.tree_nodes[leaf].parent else {
+            self.root = None;
+            return;
+        };
+        let TreeNodeKind::Branch(children) = self.tree_nodes[parent].kind else {
+            unreachable!("The parent of a tree node must be a branch.");
+        };
+        let sibling = if children[0] == leaf { children[1] } else { children[0] };
+        let grandparent = self.tree_nodes[parent].parent;
+        self.tree_nodes[sibling].parent = grandparent;
+        match grandparent {
+            Some(grandparent) => {
+                self.replace_child(grandparent, parent, sibling);
+                self.refit(grandparent);
+            }
+            None => self.root = Some(sibling),
+        }
+        self.free_slots.push(parent);
+    }
+
+    fn replace_child(&mut self, parent: usize, old_child: usize, new_child: usize) {
+        if let TreeNodeKind::Branch(children) = &mut self.tree_nodes[parent].kind {
+            for child in children.iter_mut().filter(|child| **child == old_child) {
+                *child = new_child;
+            }
+        }
+    }
+
+    /// Recompute the boxes of a branch and its ancestors.
+    fn refit(&mut self, branch: usize) {
+        let mut index = Some(branch);
+        while let Some(current) = index {
+            if let TreeNodeKind::Branch([a, b]) = self.tree_nodes[current].kind {
+                self.tree_nodes[current].aabb = self.tree_nodes[a].aabb.merge(&self.tree_nodes[b].aabb);
+            }
+            index = self.tree_nodes[current].parent;
+        }
+    }
+
+    /// Visit the nodes in the layers whose bounds overlap a shape, skipping the subtrees whose boxes do not.
+    fn query(&self, overlaps: impl Fn(&Aabb) -> bool, layer_mask: LayerMask, mut visit: impl FnMut(NodeHandle, &Aabb)) {
+        let mut stack: Vec<usize> = self.root.into_iter().collect();
+        while let Some(index) = stack.pop() {
+            let tree_node = &self.tree_nodes[index];
+            if !overlaps(&tree_node.aabb) {
+                continue;
+            }
+            match &tree_node.kind {
+                TreeNodeKind::Branch(children) => stack.extend(children),
+                TreeNodeKind::Leaf { node, layer, aabb } => {
+                    if layer_mask.contains(*layer) && overlaps(aabb) {
+                        visit(*node, aabb);
+                    }
+                }
+            }
+        }
+    }
+
+    fn collect(&self, overlaps: impl Fn(&Aabb) -> bool, layer_mask: LayerMask) -> Vec<NodeHandle> {
+        let mut nodes = Vec::new();
+        self.query(overlaps, layer_mask, |node, _| nodes.push(node));
+        nodes
+    }
+}
+
+/// Spatial queries of scenes.
+impl Scene {
+    /// The spatial index over the world bounds of the enabled mesh renderers.
+    ///
+    /// It is updated after transforms are propagated in each frame, so it does not see the nodes moved, created or
+    /// enabled by behaviors in the current frame until then. Mesh renderers removed from the scene are removed from
+    /// the index immediately.
+    pub fn spatial_index(&self) -> &SpatialIndex {
+        &self.spatial_index
+    }
+
+    /// Cast a ray against the world bounds of nodes, see [`SpatialIndex::raycast`].
+    pub fn raycast(&self, ray: &Ray, max_distance: f32, layer_mask: LayerMask) -> Vec<RaycastHit> {
+        self.spatial_index.raycast(ray, max_distance, layer_mask)
+    }
+
+    /// Cast a ray against the triangles of the meshes of nodes, e.g., to pick the node under the cursor or to place
+    /// objects on geometry.
+    ///
+    /// # Arguments
+    ///
+    /// * `ray` - The ray in world space.
+    /// * `max_distance` - The max distance of hits.
+    /// * `layer_mask` - The layers of nodes which can be hit.
+    /// * `mesh_manager` - The mesh manager which contains the meshes.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<RaycastHit>` - The nearest hit of each node, sorted from near to far.
+    pub fn raycast_meshes(
+        &self,
+        ray: &Ray,
+        max_distance: f32,
+        layer_mask: LayerMask,
+        mesh_manager: &MeshManager,
+    ) -> Vec<RaycastHit> {
+        let mut hits: Vec<RaycastHit> = self
+            .spatial_index
+            .raycast(ray, max_distance, layer_mask)
+            .into_iter()
+            .filter_map(|bounds_hit| {
+                let model_matrix = self.get_node(&bounds_hit.node)?.transform.model_matrix;
+                let mesh = mesh_manager.get_mesh(&self.get_component::<MeshRenderer>(&bounds_hit.node)?.mesh)?;
+                // Distances in the local space are scaled, so the hit point is transformed back to measure it.
+                let local_ray = ray.transform(&model_matrix.inverse());
+                let point = model_matrix.transform_point3(local_ray.at(mesh.raycast(&local_ray)?));
+                let distance = point.distance(ray.origin);
+                (distance <= max_distance).then_some(RaycastHit { node: bounds_hit.node, distance, point })
+            })
+            .collect();
+        hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        hits
+    }
+
+    /// Update the spatial index with the nodes changed since the last update, which are collected by transform
+    /// propagation, mesh bounds syncs and added mesh renderers.
+    /// Enabled mesh renderers with bounds are inserted at their world bounds, and the others are removed.
+    pub(crate) fn update_spatial_index(&mut self) {
+        let changed_nodes = std::mem::take(&mut self.spatial_index_changes);
+        for node_id in changed_nodes {
+            let Some(node) = self.get_node(&node_id) else {
+                continue;
+            };
+            let Some(mesh_renderer) = self.get_component::<MeshRenderer>(&node_id) else {
+                continue;
+            };
+            let entry = mesh_renderer
+                .local_bounds()
+                .filter(|_| node.enabled_in_hierarchy)
+                .map(|local_bounds| (node.layer, local_bounds.transform(&node.transform.model_matrix)));
+            match entry {
+                Some((layer, aabb)) => self.spatial_index.insert_or_update(node_id, layer, aabb),
+                None => {
+                    self.spatial_index.remove(&node_id);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        assets::meshes::primitives::cuboid::Cuboid,
+        math::{Mat4, Quat, Vec2},
+        prelude::Camera,
+    };
+
+    #[test]
+    fn test_spatial_index() {
+        let mut spatial_index = SpatialIndex::new();
+        let nodes: Vec<NodeHandle> = (0..64).map(NodeHandle::new).collect();
+        let bounds_of = |index: usize, offset: f32| {
+            let center = Vec3::new((index % 8) as f32 * 3.0 + offset, (index / 8) as f32 * 3.0, 0.0);
+            Aabb::from_center_half_extents(center, Vec3::ONE)
+        };
+        for (index, node) in nodes.iter().enumerate() {
+            let layer = if index % 2 == 0 { Layer::Default } else { Layer::Custom1 };
+            spatial_index.insert_or_update(*node, layer, bounds_of(index, 0.0));
+        }
+        // Move the nodes by a small and a large offset, and remove some of them.
+        for (index, node) in nodes.iter().enumerate() {
+            let layer = if index % 2 == 0 { Layer::Default } else { Layer::Custom1 };
+            spatial_index.insert_or_update(*node, layer, bounds_of(index, 0.05));
+            spatial_index.insert_or_update(*node, layer, bounds_of(index, 100.0));
+        }
+        for node in &nodes[..8] {
+            assert!(spatial_index.remove(node));
+        }
+        assert!(!spatial_index.remove(&nodes[0]));
+        assert_eq!(spatial_index.len(), 56);
+        assert_eq!(spatial_index.bounds(&nodes[9]), Some(bounds_of(9, 100.0)));
+
+        // The queries match brute force.
+        let query_box = Aabb::new(Vec3::new(105.0, 5.0, -1.0), Vec3::new(112.0, 13.0, 1.0));
+        let mut overlapped = spatial_index.overlap_aabb(&query_box, LayerMask::ALL);
+        overlapped.sort_by_key(|node| node.id);
+        let expected: Vec<NodeHandle> =
+            (8..64).filter(|index| bounds_of(*index, 100.0).intersects(&query_box)).map(|index| nodes[index]).collect();
+        assert_eq!(overlapped.len(), 9);
+        assert_eq!(overlapped, expected);
+        assert!(spatial_index.overlap_aabb(&query_box, Layer::Custom1.into()).iter().all(|node| node.id % 2 == 1));
+        let bounding_sphere = BoundingSphere::new(Vec3::new(100.0, 21.0, 0.0), 0.5);
+        assert_eq!(spatial_index.overlap_sphere(&bounding_sphere, LayerMask::ALL), vec![nodes[56]]);
+
+        let ray = Ray::new(Vec3::new(90.0, 6.0, 0.0), Vec3::X);
+        let hits = spatial_index.raycast(&ray, 1000.0, LayerMask::ALL);
+        assert_eq!(hits.iter().map(|hit| hit.node).collect::<Vec<_>>(), nodes[16..24].to_vec());
+        assert_eq!(hits[0].distance, 9.0);
+        assert_eq!(hits[0].point, Vec3::new(99.0, 6.0, 0.0));
+        assert_eq!(spatial_index.raycast(&ray, 8.0, LayerMask::ALL), vec![]);
+
+        let frustum = Frustum::from_view_projection(
+            &(Mat4::orthographic_rh(-2.0, 2.0, -2.0, 2.0, 0.0, 10.0)
+                * Mat4::look_at_rh(Vec3::new(103.5, 3.5, 5.0), Vec3::new(103.5, 3.5, 0.0), Vec3::Y)),
+        );
+        let mut in_frustum = spatial_index.overlap_frustum(&frustum, LayerMask::ALL);
+        in_frustum.sort_by_key(|node| node.id);
+        assert_eq!(in_frustum, vec![nodes[9], nodes[10], nodes[17], nodes[18]]);
+    }
+
+    #[test]
+    fn test_scene_raycast() {
+        let mut mesh_manager = MeshManager::new();
+        let cube = mesh_manager.add_mesh(Cuboid::default().into());
+        let mut scene = Scene::new();
+        let near_cube = scene.create_node("Near");
+        let far_cube = scene.create_node("Far");
+        let hidden_cube = scene.create_node("Hidden");
+        for (node, z) in [(near_cube, -5.0), (far_cube, -10.0), (hidden_cube, -2.0)] {
+            scene.add(node);
+            scene.add_component(&node, MeshRenderer::new(cube, vec![]));
+            let transform = &mut scene.get_node_mut_forcely(&node).transform;
+            transform.set_position(Vec3::new(0.0, 0.0, z));
+            // Rotate the cube by 45 degrees, so that its corners are in its bounds but not in the mesh.
+            transform.set_rotation_quat(Quat::from_rotation_z(std::f32::consts::FRAC_PI_4));
+        }
+        scene.get_node_mut_forcely(&hidden_cube).layer = Layer::UI;
+        scene.on_update(&mut crate::time::Time::new());
+        scene.sync_mesh_bounds(&mesh_manager);
+        scene.update_spatial_index();
+        assert_eq!(scene.spatial_index().len(), 3);
+
+        let mut camera = Camera::new(std::f32::consts::FRAC_PI_2, 1.0, 0.1, 100.0);
+        camera.target_pos = Vec3::NEG_Z;
+        let camera_position = Vec3::ZERO;
+        // The center of the default logical viewport.
+        let ray = camera.screen_point_to_ray(&camera_position, Vec2::new(50.0, 50.0));
+        assert!(ray.direction.abs_diff_eq(Vec3::NEG_Z, 1e-5));
+        let layer_mask = Layer::Default.into();
+        let hits = scene.raycast_meshes(&ray, 100.0, layer_mask, &mesh_manager);
+        assert_eq!(hits.iter().map(|hit| hit.node).collect::<Vec<_>>(), vec![near_cube, far_cube]);
+        // The ray starts on the near plane.
+        assert!((hits[0].distance - 4.4).abs() < 1e-4);
+        assert!(hits[0].point.abs_diff_eq(Vec3::new(0.0, 0.0, -4.5), 1e-4));
+
+        // Near the corner of the bounds of the near cube, the bounds are hit but the mesh is not.
+        let corner_ray = Ray::new(Vec3::new(0.65, 0.65, 0.0), Vec3::NEG_Z);
+        assert_eq!(scene.raycast(&corner_ray, 100.0, layer_mask).len(), 2);
+        assert_eq!(scene.raycast_meshes(&corner_ray, 100.0, layer_mask, &mesh_manager), vec![]);
+
+        scene.remove_component::<MeshRenderer>(&far_cube);
+        assert!(!scene.spatial_index().contains(&far_cube));
+        scene.get_node_mut_forcely(&near_cube).enabled = false;
+        scene.on_update(&mut crate::time::Time::new());
+        scene.update_spatial_index();
+        assert_eq!(scene.raycast_meshes(&ray, 100.0, LayerMask::ALL, &mesh_manager)[0].node, hidden_cube);
+        assert_eq!(scene.spatial_index().len(), 1);
+    }
+
+    #[test]
+    fn test_incremental_spatial_index_update() {
+        let mut mesh_manager = MeshManager::new();
+        let cube = mesh_manager.add_mesh(Cuboid::default().into());
+        let mut scene = Scene::new();
+        let parent = scene.create_node("Parent");
+        let child = scene.create_node("Child");
+        let static_cube = scene.create_node("Static");
+        scene.add(parent);
+        scene.attach_to_parent(&child, parent);
+        scene.add(static_cube);
+        scene.add_component(&child, MeshRenderer::new(cube, vec![]));
+        scene.add_component(&static_cube, MeshRenderer::new(cube, vec![]));
+        scene.on_update(&mut crate::time::Time::new());
+        scene.sync_mesh_bounds(&mesh_manager);
+        scene.update_spatial_index();
+        assert_eq!(scene.spatial_index().len(), 2);
+        assert!(scene.spatial_index_changes.is_empty());
+
+        // Nothing changed, so no node is updated.
+        scene.on_update(&mut crate::time::Time::new());
+        scene.sync_mesh_bounds(&mesh_manager);
+        assert!(scene.spatial_index_changes.is_empty());
+
+        // Moving the parent updates its subtree only.
+        scene.get_node_mut_forcely(&parent).transform.set_position(Vec3::new(10.0, 0.0, 0.0));
+        scene.on_update(&mut crate::time::Time::new());
+        assert_eq!(scene.spatial_index_changes, vec![parent, child]);
+        scene.update_spatial_index();
+        assert_eq!(scene.spatial_index().bounds(&child), scene.world_bounds(&child));
+
+        // Layer changes are detected without transform changes.
+        scene.get_node_mut_forcely(&static_cube).layer = Layer::Custom1;
+        scene.on_update(&mut crate::time::Time::new());
+        assert_eq!(scene.spatial_index_changes, vec![static_cube]);
+        scene.update_spatial_index();
+        assert_eq!(scene.spatial_index().layer(&static_cube), Some(Layer::Custom1));
+    }
+}
@@ -0,0 +1,247 @@
+use crate::{
+    core::{NodeHandle, Scene},
+    math::{Mat3, Mat4, Quat, Vec3},
+};
+
+/// World-space transforms of nodes.
+///
+/// They are computed from the local transforms of the node and its ancestors, so they are correct even if the
+/// transforms have been changed in this frame, while [`crate::components::transform::Transform::model_matrix`] is only
+/// updated after behaviors have run. The rendered interpolation of fixed updates is ignored.
+impl Scene {
+    /// The matrix from the local space of a node to world space.
+    pub fn world_matrix(&self, node_id: &NodeHandle) -> Option<Mat4> {
+        let mut node = self.get_node(node_id)?;
+        let mut world_matrix = node.transform.local_matrix();
+        while let Some(parent) = node.parent.and_then(|parent_id| self.get_node(&parent_id)) {
+            world_matrix = parent.transform.local_matrix() * world_matrix;
+            node = parent;
+        }
+        Some(world_matrix)
+    }
+
+    pub fn world_position(&self, node_id: &NodeHandle) -> Option<Vec3> {
+        self.world_matrix(node_id).map(|world_matrix| world_matrix.w_axis.truncate())
+    }
+
+    /// The world rotation of a node, which is the product of the rotations of the node and its ancestors.
+    pub fn world_rotation(&self, node_id: &NodeHandle) -> Option<Quat> {
+        let mut node = self.get_node(node_id)?;
+        let mut world_rotation = *node.transform.get_rotation_quat();
+        while let Some(parent) = node.parent.and_then(|parent_id| self.get_node(&parent_id)) {
+            world_rotation = *parent.transform.get_rotation_quat() * world_rotation;
+            node = parent;
+        }
+        Some(world_rotation)
+    }
+
+    /// The world scale of a node. It is lossy if an ancestor is rotated and scaled non-uniformly, which skews the node.
+    pub fn world_scale(&self, node_id: &NodeHandle) -> Option<Vec3> {
+        self.world_matrix(node_id).map(|world_matrix| world_matrix.to_scale_rotation_translation().0)
+    }
+
+    /// The world matrix of the parent of a node, or the identity matrix for root nodes.
+    fn parent_world_matrix(&self, node_id: &NodeHandle) -> Option<Mat4> {
+        match self.get_node(node_id)?.parent {
+            Some(parent_id) => self.world_matrix(&parent_id),
+            None => Some(Mat4::IDENTITY),
+        }
+    }
+
+    /// Move a node to a position in world space.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - Whether the node exists.
+    pub fn set_world_position(&mut self, node_id: &NodeHandle, position: Vec3) -> bool {
+        let Some(parent_world_matrix) = self.parent_world_matrix(node_id) else {
+            return false;
+        };
+        let local_position = parent_world_matrix.inverse().transform_point3(position);
+        self.get_node_mut_forcely(node_id).transform.set_position(local_position);
+        true
+    }
+
+    /// Rotate a node to a rotation in world space.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - Whether the node exists.
+    pub fn set_world_rotation(&mut self, node_id: &NodeHandle, rotation: Quat) -> bool {
+        let Some(node) = self.get_node(node_id) else {
+            return false;
+        };
+        let parent_world_rotation = node.parent.and_then(|parent_id| self.world_rotation(&parent_id)).unwrap_or(Quat::IDENTITY);
+        self.get_node_mut_forcely(node_id).transform.set_rotation_quat(parent_world_rotation.inverse() * rotation);
+        true
+    }
+
+    /// Scale a node to a scale in world space. It is lossy like [`Scene::world_scale`].
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - Whether the node exists.
+    pub fn set_world_scale(&mut self, node_id: &NodeHandle, scale: Vec3) -> bool {
+        let Some(parent_world_matrix) = self.parent_world_matrix(node_id) else {
+            return false;
+        };
+        let parent_world_scale = parent_world_matrix.to_scale_rotation_translation().0;
+        self.get_node_mut_forcely(node_id).transform.set_scale(scale / parent_world_scale);
+        true
+    }
+
+    /// Rotate a node so that its forward direction (-Z) points at a target in world space.
+    ///
+    /// # Arguments
+    ///
+    /// * `node_id` - The node to rotate.
+    /// * `target` - The position in world space to look at.
+    /// * `up` - The up direction in world space, usually `Vec3::Y`.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - False if the node does not exist, the target is at the position of the node, or the direction to
+    ///   the target is parallel to `up`.
+    pub fn look_at(&mut self, node_id: &NodeHandle, target: Vec3, up: Vec3) -> bool {
+        let Some(position) = self.world_position(node_id) else {
+            return false;
+        };
+        let back = (position - target).normalize_or_zero();
+        let right = up.normalize_or_zero().cross(back);
+        if right.length_squared() < 1e-8 {
+            return false;
+        }
+        let right = right.normalize();
+        let rotation = Quat::from_mat3(&Mat3::from_cols(right, back.cross(right), back));
+        self.set_world_rotation(node_id, rotation)
+    }
+
+    /// Transform a point from the local space of a node to world space.
+    pub fn transform_point(&self, node_id: &NodeHandle, point: Vec3) -> Option<Vec3> {
+        self.world_matrix(node_id).map(|world_matrix| world_matrix.transform_point3(point))
+    }
+
+    /// Transform a direction from the local space of a node to world space. It is only rotated, so its length is
+    /// kept.
+    pub fn transform_direction(&self, node_id: &NodeHandle, direction: Vec3) -> Option<Vec3> {
+        self.world_rotation(node_id).map(|world_rotation| world_rotation * direction)
+    }
+
+    /// Whether `ancestor` is `node_id` itself or one of its ancestors.
+    pub(crate) fn is_self_or_ancestor(&self, ancestor: &NodeHandle, node_id: &NodeHandle) -> bool {
+        let mut current = Some(*node_id);
+        while let Some(current_id) = current {
+            if current_id == *ancestor {
+                return true;
+            }
+            current = self.get_node(&current_id).and_then(|node| node.parent);
+        }
+        false
+    }
+
+    /// Attach a node to a parent, or make it a root node if `parent` is None. The root nodes are updated.
+    ///
+    /// # Arguments
+    ///
+    /// * `node_id` - The node to reparent.
+    /// * `parent` - The new parent.
+    /// * `keep_world_transform` - Whether to change the local transform so that the node stays at the same position,
+    ///   rotation and scale in world space. Otherwise the local transform is kept, and the node moves with the new parent.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - False if the node or the parent does not exist, or the parent is the node or one of its descendants.
+    pub fn set_parent(&mut self, node_id: &NodeHandle, parent: Option<NodeHandle>, keep_world_transform: bool) -> bool {
+        let Some(world_matrix) = self.world_matrix(node_id) else {
+            return false;
+        };
+        if let Some(parent_id) = &parent
+            && (self.get_node(parent_id).is_none() || self.is_self_or_ancestor(node_id, parent_id))
+        {
+            return false;
+        }
+
+        match parent {
+            Some(parent_id) => {
+                self.attach_to_parent(node_id, parent_id);
+                self.root_nodes.retain(|root_node| root_node != node_id);
+            }
+            None => {
+                self.detach_from_parent(node_id);
+                if !self.root_nodes.contains(node_id) {
+                    self.add(*node_id);
+                }
+            }
+        }
+
+        if keep_world_transform {
+            let parent_world_matrix = self.parent_world_matrix(node_id).unwrap_or(Mat4::IDENTITY);
+            let (scale, rotation, position) = (parent_world_matrix.inverse() * world_matrix).to_scale_rotation_translation();
+            let transform = &mut self.get_node_mut_forcely(node_id).transform;
+            transform.set_position(position);
+            transform.set_rotation_quat(rotation);
+            transform.set_scale(scale);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::Time;
+
+    fn assert_near(a: Vec3, b: Vec3) {
+        assert!(a.abs_diff_eq(b, 1e-4), "{} != {}", a, b);
+    }
+
+    #[test]
+    fn test_world_transform() {
+        let mut scene = Scene::new();
+        let parent = scene.create_node("Parent");
+        let child = scene.create_node("Child");
+        scene.add(parent);
+        scene.set_parent(&child, Some(parent), false);
+        scene.get_node_mut_forcely(&parent).transform.set_position_rotation_scale(
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, std::f32::consts::FRAC_PI_2, 0.0),
+            Vec3::splat(2.0),
+        );
+        scene.get_node_mut_forcely(&child).transform.set_position(Vec3::X);
+
+        // The parent rotates +X to -Z around Y.
+        assert_near(scene.world_position(&child).unwrap(), Vec3::new(1.0, 0.0, -2.0));
+        assert_near(scene.world_scale(&child).unwrap(), Vec3::splat(2.0));
+        assert_near(scene.transform_direction(&child, Vec3::X).unwrap(), Vec3::NEG_Z);
+        assert_near(scene.transform_point(&child, Vec3::X).unwrap(), Vec3::new(1.0, 0.0, -4.0));
+
+        scene.on_update(&mut Time::new());
+        let model_matrix = scene.get_node_forcely(&child).transform.model_matrix;
+        assert_near(model_matrix.w_axis.truncate(), Vec3::new(1.0, 0.0, -2.0));
+        // Static nodes keep their model matrices.
+        scene.on_update(&mut Time::new());
+        assert_eq!(scene.get_node_forcely(&child).transform.model_matrix, model_matrix);
+        // Moving the parent updates the child.
+        scene.get_node_mut_forcely(&parent).transform.set_position(Vec3::ZERO);
+        scene.on_update(&mut Time::new());
+        assert_near(scene.get_node_forcely(&child).transform.model_matrix.w_axis.truncate(), Vec3::new(0.0, 0.0, -2.0));
+
+        assert!(scene.set_world_position(&child, Vec3::new(3.0, 4.0, 5.0)));
+        assert_near(scene.world_position(&child).unwrap(), Vec3::new(3.0, 4.0, 5.0));
+        assert!(scene.look_at(&child, Vec3::new(3.0, 4.0, 0.0), Vec3::Y));
+        assert_near(scene.transform_direction(&child, Vec3::NEG_Z).unwrap(), Vec3::NEG_Z);
+        assert!(scene.look_at(&child, Vec3::new(10.0, 4.0, 5.0), Vec3::Y));
+        assert_near(scene.transform_direction(&child, Vec3::NEG_Z).unwrap(), Vec3::X);
+        assert!(!scene.look_at(&child, Vec3::new(3.0, 10.0, 5.0), Vec3::Y));
+
+        // Reparenting keeps the world transform.
+        assert!(!scene.set_parent(&parent, Some(child), true));
+        assert!(scene.set_parent(&child, None, true));
+        assert_eq!(scene.root_nodes, vec![parent, child]);
+        assert_near(scene.world_position(&child).unwrap(), Vec3::new(3.0, 4.0, 5.0));
+        assert_near(scene.world_scale(&child).unwrap(), Vec3::splat(2.0));
+        assert_near(scene.transform_direction(&child, Vec3::NEG_Z).unwrap(), Vec3::X);
+        scene.on_update(&mut Time::new());
+        assert_near(scene.get_node_forcely(&child).transform.model_matrix.w_axis.truncate(), Vec3::new(3.0, 4.0, 5.0));
+    }
+}
@@ -1,25 +1,74 @@
-use std::cell::RefCell;
+use std::{cell::RefCell, hash::{Hash, Hasher}};
+
+use ahash::{AHashMap, AHasher};
 
 use crate::{
     assets::{
         BuiltinGlobalShaderFeatures, MaterialManager, MeshManager, Sampler, ShaderManager, Texture, TextureFormat, TextureHandle,
         TextureSamplerManager, shaders::shader_property::BuiltinShaderUniformNames
-    }, components::{camera::Camera, mesh_renderer::MeshRenderer}, core::{LayerMask, NodeHandle, SH, scene::Scene}, graphics::{
+    }, components::{camera::Camera, mesh_renderer::MeshRenderer}, core::{CommandBuffer, Layer, Layers, NodeHandle, SH, scene::Scene}, graphics::{
         bind_group::BindGroupID, graphics_context::GraphicsContext, render_states::RenderQueue,
         uniform::{BuiltinUniforms, CameraUniformSyncFlags, GlobalUniformSyncFlags}
-    }, math::Vec4, prelude::LightsGPUData, renderer::{
+    }, math::{Aabb, Frustum, Mat4, UVec4, Vec4}, prelude::{ComponentId, GPULightData, LightsGPUData}, renderer::{
         frame_data::{CameraRenderData, ItemRenderData}, frame_renderer::FrameRenderer,
+        shadow_map::{SHADOW_ATLAS_FORMAT, ShadowCastingLight, ShadowMapper, ShadowView, ShadowViewRenderData, ShadowsGPUData},
     }, time::Time
 };
 
+/// The inputs of a frame shared by all cameras, which are synced to the global uniforms by the first camera using them.
+struct FrameGlobals<'a> {
+    global_uniforms: &'a mut BuiltinUniforms,
+    /// Which global uniforms have been synced in this frame.
+    global_uniform_sync_flags: GlobalUniformSyncFlags,
+    time: &'a Time,
+    lights_gpu_data: &'a LightsGPUData,
+    shadows_gpu_data: &'a ShadowsGPUData,
+    shadow_atlas: TextureHandle,
+    reflection_map: TextureHandle,
+    brdf_lut: TextureHandle,
+    sh: &'a SH,
+}
+
+/// The inputs of a camera in a frame.
+struct CameraFrameInputs<'a> {
+    depth_format: TextureFormat,
+    per_camera_uniforms: RefCell<BuiltinUniforms>,
+    camera_render_data: &'a mut CameraRenderData,
+    /// The renderables which are not culled by the camera, see [`World::cull_renderables`].
+    visible_renderables: &'a [NodeHandle],
+}
+
+/// The count of renderables which are tested against the frustums of cameras in a frame, summed over all cameras.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CullingStats {
+    /// The renderables which are inside or intersect the frustum of a camera, or can not be culled.
+    pub visible: u32,
+    /// The renderables which are outside the frustum of a camera and are not rendered by it.
+    pub culled: u32,
+}
+
 /// A world in the scene. It is a container for scenes.
+/// 
+/// Several scenes can be loaded at the same time, e.g., a persistent scene for UI and player state and a scene for
+/// level content. All loaded scenes are updated and rendered together, each by its own cameras. Lights of all
+/// loaded scenes are applied to all scenes, while the skybox and environment lighting come from the current scene.
 pub struct World {
     /// The scenes in the world. At least one scene is required.
-    /// 
-    /// (Now only one scene is supported. Two or more scenes are not tested.)
     pub scenes: Vec<Scene>,
-    /// The index of the current scene.
+    /// The index of the current (active) scene, which is always loaded.
+    /// 
+    /// Change it by [`World::set_active_scene`] or [`crate::prelude::Engine::switch_scene`].
     pub current_scene_index: usize,
+    /// The indices of loaded scenes in loading order.
+    pub(crate) loaded_scene_indices: Vec<usize>,
+    /// Structural changes queued by [`World::commands`].
+    pub(crate) command_buffer: CommandBuffer,
+    /// The lights collected by [`World::prepare_lights`] for the next render frame.
+    pub(crate) prepared_lights: Option<(LightsGPUData, Vec<ShadowCastingLight>)>,
+    /// The names of layers, shared by all scenes.
+    pub layers: Layers,
+    /// The culling statistics of the last render frame.
+    pub(crate) culling_stats: CullingStats,
 }
 
 impl World {
@@ -47,6 +96,11 @@ impl World {
         let world: World = World {
             scenes: vec![current_scene],
             current_scene_index: 0,
+            loaded_scene_indices: vec![0],
+            command_buffer: CommandBuffer::default(),
+            prepared_lights: None,
+            layers: Layers::new(),
+            culling_stats: CullingStats::default(),
         };
         world
     }
@@ -69,13 +123,164 @@ impl World {
         &self.scenes[self.current_scene_index]
     }
 
+    /// Adds a scene to the world. The scene is not loaded until it is switched to or loaded additively.
+    /// 
+    /// # Returns
+    /// 
+    /// * `usize` - The index of the added scene.
+    pub fn add_scene(&mut self, scene: Scene) -> usize {
+        self.scenes.push(scene);
+        self.scenes.len() - 1
+    }
+
+    /// Returns a reference to the scene at the index.
+    pub fn get_scene(&self, index: usize) -> Option<&Scene> {
+        self.scenes.get(index)
+    }
+
+    /// Returns a mutable reference to the scene at the index.
+    pub fn get_scene_mut(&mut self, index: usize) -> Option<&mut Scene> {
+        self.scenes.get_mut(index)
+    }
+
+    /// Whether the scene at the index is loaded, i.e., it is updated and rendered.
+    pub fn is_scene_loaded(&self, index: usize) -> bool {
+        self.loaded_scene_indices.contains(&index)
+    }
+
+    /// The indices of loaded scenes in loading order.
+    pub fn loaded_scenes(&self) -> &[usize] {
+        &self.loaded_scene_indices
+    }
+
+    /// The count of renderables which are rendered and culled by the frustums of cameras in the last frame.
+    pub fn culling_stats(&self) -> CullingStats {
+        self.culling_stats
+    }
+
+    /// Makes a loaded scene the current scene, without any lifecycle call.
+    /// 
+    /// The current scene provides the skybox and environment lighting, and is the scene returned by [`World::current_scene_mut`].
+    /// 
+    /// # Returns
+    /// 
+    /// * `bool` - Returns `false` if the scene is not loaded.
+    pub fn set_active_scene(&mut self, index: usize) -> bool {
+        if self.is_scene_loaded(index) {
+            self.current_scene_index = index;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Moves a node with its descendants and components from one scene to another, where it becomes a root node.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `node` - The node to move.
+    /// * `from` - The index of the scene which contains the node.
+    /// * `to` - The index of the target scene.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Option<NodeHandle>` - The handle of the node in the target scene, or None if the node or scenes are invalid.
+    pub fn move_node_to_scene(&mut self, node: &NodeHandle, from: usize, to: usize) -> Option<NodeHandle> {
+        if from == to {
+            return self.scenes.get(from).and_then(|scene| scene.get_node(node)).map(|_| *node);
+        }
+        let [source, target] = self.scenes.get_disjoint_mut([from, to]).ok()?;
+        source.get_node(node)?;
+        source.detach_from_parent(node);
+        source.root_nodes.retain(|root_node| root_node != node);
+        let new_node = Self::move_node_recursively(source, target, node);
+        source.destroy_node(node);
+        target.add(new_node);
+        Some(new_node)
+    }
+
+    fn move_node_recursively(source: &mut Scene, target: &mut Scene, node: &NodeHandle) -> NodeHandle {
+        let source_node = source.get_node_forcely(node);
+        let children = source_node.children.clone().unwrap_or_default();
+        let (name, enabled, layer, tags, transform) = (
+            source_node.name.clone(),
+            source_node.enabled,
+            source_node.layer,
+            source_node.tags.clone(),
+            source_node.transform.clone(),
+        );
+
+        let new_node = target.create_node(name);
+        {
+            let target_node = target.get_node_mut_forcely(&new_node);
+            target_node.enabled = enabled;
+            target_node.layer = layer;
+            target_node.tags = tags;
+            target_node.transform = transform;
+        }
+        let component_id = ComponentId::from(node);
+        for component_type_id in source.component_storages.component_types(&component_id) {
+            source.on_component_removing(node, &component_type_id);
+            if source.component_storages.move_component(
+                &component_id,
+                &component_type_id,
+                &mut target.component_storages,
+                ComponentId::from(&new_node),
+            ) {
+                target.on_component_added(&new_node, &component_type_id);
+            }
+        }
+        for child in &children {
+            let new_child = Self::move_node_recursively(source, target, child);
+            target.attach_to_parent(&new_child, new_node);
+        }
+        new_node
+    }
+
+    /// Save the interpolated transforms of all loaded scenes before a fixed update.
+    pub(crate) fn save_previous_transforms(&mut self) {
+        for &scene_index in &self.loaded_scene_indices {
+            self.scenes[scene_index].save_previous_transforms();
+        }
+    }
+
+    /// Marks the scene as loaded. It is initialized by [`World::init_scene`] if the engine is running.
+    /// 
+    /// # Returns
+    /// 
+    /// * `bool` - Returns `true` if the scene exists and was not loaded.
+    pub(crate) fn load_scene(&mut self, index: usize) -> bool {
+        if index < self.scenes.len() && !self.is_scene_loaded(index) {
+            self.loaded_scene_indices.push(index);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Stops and unloads the scene. The current scene can not be unloaded.
+    /// 
+    /// # Returns
+    /// 
+    /// * `bool` - Returns `true` if the scene was loaded and is unloaded now.
+    pub(crate) fn unload_scene(&mut self, index: usize, time: &mut Time) -> bool {
+        if index == self.current_scene_index || !self.is_scene_loaded(index) {
+            return false;
+        }
+        self.loaded_scene_indices.retain(|&loaded_index| loaded_index != index);
+        self.scenes[index].on_stop(time);
+        true
+    }
+
     /// Stops the world.
     /// 
     /// # Arguments
     /// 
     /// * `time` - The time object.
     pub(crate) fn stop(&mut self, time: &mut Time) {
-        self.scenes[self.current_scene_index].on_stop(time);
+        for &scene_index in &self.loaded_scene_indices {
+            self.scenes[scene_index].on_stop(time);
+        }
     }
 
     /// Initializes the world.
@@ -90,9 +295,18 @@ impl World {
     pub(crate) fn on_init(&mut self, graphics_context: &mut GraphicsContext, texture_sampler_manager: &mut TextureSamplerManager,
         shader_manager: &mut ShaderManager, material_manager: &mut MaterialManager, time: &mut Time) {
         log::info!("world on_init");
-        self.scenes[self.current_scene_index].try_init_skybox(graphics_context, texture_sampler_manager, shader_manager, material_manager);
-        self.on_resize(&graphics_context, texture_sampler_manager);
-        self.scenes[self.current_scene_index].on_init(time);
+        for scene_index in self.loaded_scene_indices.clone() {
+            self.init_scene(scene_index, graphics_context, texture_sampler_manager, shader_manager, material_manager, time);
+        }
+    }
+
+    /// Initializes a loaded scene: its skybox, the attachments of its cameras and its lifecycle.
+    pub(crate) fn init_scene(&mut self, scene_index: usize, graphics_context: &mut GraphicsContext, texture_sampler_manager: &mut TextureSamplerManager,
+        shader_manager: &mut ShaderManager, material_manager: &mut MaterialManager, time: &mut Time) {
+        let scene = &mut self.scenes[scene_index];
+        scene.try_init_skybox(graphics_context, texture_sampler_manager, shader_manager, material_manager);
+        Self::resize_scene(scene, graphics_context, texture_sampler_manager);
+        scene.on_init(time);
     }
 
     /// Resizes the world.
@@ -102,13 +316,18 @@ impl World {
     /// * `graphics_context` - The graphics context.
     /// * `texture_sampler_manager` - The texture sampler manager.
     pub(crate) fn on_resize(&mut self, graphics_context: &GraphicsContext, texture_sampler_manager: &mut TextureSamplerManager,) {
-        let physical_size = graphics_context.main_window().get_physical_size();
-        let logical_size = graphics_context.main_window().get_logical_size();
+        for &scene_index in &self.loaded_scene_indices {
+            Self::resize_scene(&mut self.scenes[scene_index], graphics_context, texture_sampler_manager);
+        }
+    }
+
+    fn resize_scene(scene: &mut Scene, graphics_context: &GraphicsContext, texture_sampler_manager: &mut TextureSamplerManager) {
+        let physical_size = graphics_context.get_physical_size();
+        let logical_size = graphics_context.get_logical_size();
 
-        let current_scene = self.current_scene_mut();
-        let cached_cameras = std::mem::take(&mut current_scene.cached_cameras);
+        let cached_cameras = std::mem::take(&mut scene.cached_cameras);
         for camera_node in &cached_cameras {
-            if let Some(camera) = &mut current_scene.get_component_mut::<Camera>(camera_node) {
+            if let Some(camera) = &mut scene.get_component_mut::<Camera>(camera_node) {
                 camera.on_resize(texture_sampler_manager, physical_size, logical_size);
                 log::info!(
                     "World on_resize, physical size: ({}, {})",
@@ -116,7 +335,7 @@ impl World {
                 );
             }
         }
-        current_scene.cached_cameras = cached_cameras;
+        scene.cached_cameras = cached_cameras;
     }
 
     /// Updates the world.
@@ -125,7 +344,97 @@ impl World {
     /// 
     /// * `time` - The time object.
     pub(crate) fn updpate(&mut self, time: &mut Time) {
-        self.current_scene_mut().on_update(time);
+        for &scene_index in &self.loaded_scene_indices {
+            self.scenes[scene_index].on_update(time);
+        }
+    }
+
+    /// Sync the bounds of the meshes into the mesh renderers of loaded scenes, and update their spatial indices.
+    pub(crate) fn sync_mesh_bounds(&mut self, mesh_manager: &MeshManager) {
+        for &scene_index in &self.loaded_scene_indices {
+            self.scenes[scene_index].sync_mesh_bounds(mesh_manager);
+            self.scenes[scene_index].update_spatial_index();
+        }
+    }
+
+    /// Cull the cached renderables of loaded scenes by the frustums of the cameras which are rendered, and reset
+    /// [`World::culling_stats`] to the result of this frame.
+    ///
+    /// # Returns
+    ///
+    /// * `AHashMap<(usize, NodeHandle), Vec<NodeHandle>>` - The visible renderables of each camera, keyed by the scene
+    ///   index and the camera node.
+    pub(crate) fn cull_renderables(&mut self, mesh_manager: &MeshManager) -> AHashMap<(usize, NodeHandle), Vec<NodeHandle>> {
+        self.culling_stats = CullingStats::default();
+        let mut visible_renderables = AHashMap::new();
+        for &scene_index in &self.loaded_scene_indices {
+            let scene = &self.scenes[scene_index];
+            for camera_node_id in &scene.cached_cameras {
+                // Cameras without depth attachment are not rendered.
+                let Some(camera) = scene.get_component::<Camera>(camera_node_id) else {
+                    continue;
+                };
+                if camera.depth_attachment == TextureHandle::INVALID {
+                    continue;
+                }
+                let camera_transform = &scene.node_arena.get_forcely(camera_node_id).transform;
+                let view_projection = camera.get_projection_matrix() * camera.get_world_view_matrix(camera_transform);
+                let frustum = Frustum::from_view_projection(&view_projection);
+                let mut renderables = Vec::new();
+                for renderable_node in &scene.cached_renderables {
+                    let node_ref = scene.node_arena.get_forcely(renderable_node);
+                    if !node_ref.enabled_in_hierarchy || !camera.visible_layers.contains(node_ref.layer) {
+                        continue;
+                    }
+                    let Some(mesh_renderer) = scene.get_component::<MeshRenderer>(renderable_node) else {
+                        continue;
+                    };
+                    if mesh_manager.get_mesh(&mesh_renderer.mesh).is_none() {
+                        continue;
+                    }
+                    // Renderables without bounds, e.g., whose meshes have no vertex, are never culled.
+                    if mesh_renderer.frustum_culling
+                        && let Some((local_aabb, _)) = mesh_renderer.local_bounds
+                        && !frustum.intersects_aabb(&local_aabb.transform(&node_ref.transform.model_matrix))
+                    {
+                        self.culling_stats.culled += 1;
+                        continue;
+                    }
+                    self.culling_stats.visible += 1;
+                    renderables.push(*renderable_node);
+                }
+                visible_renderables.insert((scene_index, *camera_node_id), renderables);
+            }
+        }
+        visible_renderables
+    }
+
+    /// Collect the lights for the next render frame. Lights changed after it are applied in the next frame.
+    pub(crate) fn prepare_lights(&mut self) {
+        self.prepared_lights = Some(self.collect_lights_data());
+    }
+
+    /// Collect the GPU data of enabled lights in all loaded scenes, and the lights which cast shadow.
+    fn collect_lights_data(&self) -> (LightsGPUData, Vec<ShadowCastingLight>) {
+        let mut lights_gpu_data = LightsGPUData::default();
+        let mut shadow_casting_lights = Vec::new();
+        for &scene_index in &self.loaded_scene_indices {
+            let (scene_lights_gpu_data, scene_shadow_casting_lights) = self.scenes[scene_index].collect_lights_data();
+            let light_count = scene_lights_gpu_data.lights_count[0];
+            let light_index_offset = lights_gpu_data.lights_info.len();
+            // Skip the dummy light of scenes without lights.
+            lights_gpu_data.lights_info.extend(scene_lights_gpu_data.lights_info.into_iter().take(light_count as usize));
+            lights_gpu_data.lights_count[0] += light_count;
+            shadow_casting_lights.extend(scene_shadow_casting_lights.into_iter().map(|mut shadow_casting_light| {
+                shadow_casting_light.light_index += light_index_offset;
+                shadow_casting_light
+            }));
+        }
+        if lights_gpu_data.lights_count[0] == 0 {
+            // dummy light which is not used in shader. Just to avoid validation error.
+            lights_gpu_data.lights_info.push(GPULightData::default());
+        }
+        (lights_gpu_data, shadow_casting_lights)
     }
 
     /// Generates a render frame.
@@ -144,105 +453,299 @@ impl World {
         material_manager: &mut MaterialManager, mesh_manager: &mut MeshManager, time: &mut Time,
         frame_renderer: &mut FrameRenderer, global_uniforms: &mut BuiltinUniforms) {
         frame_renderer.frame_render_data.reset();
-        let cur_scene = &mut self.scenes[self.current_scene_index];
-        let (reflection_map, brdf_lut) = cur_scene.get_environment_reflection_info();
-        let sh = cur_scene.sh;
+        let mut visible_renderables = self.cull_renderables(mesh_manager);
+        let (reflection_map, brdf_lut) = self.scenes[self.current_scene_index].get_environment_reflection_info();
+        let sh = self.scenes[self.current_scene_index].sh;
         frame_renderer.frame_render_data.time_data = time.time_data;
-        let mut global_uniform_sync_flags = GlobalUniformSyncFlags::new();
-        let cached_cameras = std::mem::take(&mut cur_scene.cached_cameras);
-        let cached_renderables = std::mem::take(&mut cur_scene.cached_renderables);
-        let lights_gpu_data = cur_scene.collect_lights_data();
-        // let cached_lights = std::mem::take(&mut cur_scene.cached_lights);
-        for camera_node_id in &cached_cameras {
-            let camera_node_ref = cur_scene.node_arena.get_forcely(camera_node_id);
-            let camera_position = camera_node_ref.transform.position;
-            let camera_render_data = if let Some(camera) = cur_scene.get_component::<Camera>(camera_node_id) {
-                if TextureHandle::INVALID != camera.depth_attachment {
-                    let view_matrix =
-                        camera.get_view_matrix(&camera_position);
-                    let projection_matrix = camera.get_projection_matrix();
-                    let camera_render_data = CameraRenderData::new(
-                        camera_node_ref.id,
-                        camera.priority,
-                        view_matrix,
-                        projection_matrix,
-                        camera.depth_attachment,
-                        camera.color_attachment,
-                        camera.physical_view_port,
-                        camera.clear_color,
-                        camera_position,
-                    );
-                    Some((camera_render_data, camera.visible_layers, camera.depth_format, camera.per_camera_uniforms.clone()))
+        let (mut lights_gpu_data, shadow_casting_lights) = self.prepared_lights.take().unwrap_or_else(|| self.collect_lights_data());
+        let (shadow_views, shadows_gpu_data) = {
+            let cur_scene = &self.scenes[self.current_scene_index];
+            let shadow_camera = Self::_find_shadow_camera(cur_scene, &cur_scene.cached_cameras);
+            frame_renderer.shadow_mapper.build_shadow_views(
+                &shadow_casting_lights,
+                shadow_camera,
+                cur_scene.shadow_atlas_size,
+                &mut lights_gpu_data,
+                texture_sampler_manager,
+                graphics_context,
+                global_uniforms,
+            )
+        };
+        let shadow_atlas = frame_renderer.shadow_mapper.atlas.texture;
+        frame_renderer.frame_render_data.shadow_atlas = shadow_atlas;
+        if !shadow_views.is_empty() {
+            Self::_generate_shadow_frame(
+                &mut self.scenes,
+                &self.loaded_scene_indices,
+                &shadow_views,
+                &mut frame_renderer.shadow_mapper,
+                &mut frame_renderer.frame_render_data.shadow_view_data,
+                graphics_context,
+                texture_sampler_manager,
+                shader_manager,
+                material_manager,
+                mesh_manager,
+            );
+        }
+        let mut frame_globals = FrameGlobals {
+            global_uniforms,
+            global_uniform_sync_flags: GlobalUniformSyncFlags::new(),
+            time,
+            lights_gpu_data: &lights_gpu_data,
+            shadows_gpu_data: &shadows_gpu_data,
+            shadow_atlas,
+            reflection_map,
+            brdf_lut,
+            sh: &sh,
+        };
+        for &scene_index in &self.loaded_scene_indices {
+            let cur_scene = &mut self.scenes[scene_index];
+            let cached_cameras = std::mem::take(&mut cur_scene.cached_cameras);
+            for camera_node_id in &cached_cameras {
+                let camera_node_ref = cur_scene.node_arena.get_forcely(camera_node_id);
+                let camera_position = camera_node_ref.transform.model_matrix.w_axis.truncate();
+                let camera_render_data = if let Some(camera) = cur_scene.get_component::<Camera>(camera_node_id) {
+                    if TextureHandle::INVALID != camera.depth_attachment {
+                        let view_matrix =
+                            camera.get_world_view_matrix(&camera_node_ref.transform);
+                        let projection_matrix = camera.get_projection_matrix();
+                        let camera_render_data = CameraRenderData::new(
+                            camera_node_ref.id,
+                            camera.priority,
+                            view_matrix,
+                            projection_matrix,
+                            camera.depth_attachment,
+                            camera.color_attachment,
+                            camera.physical_view_port,
+                            camera.clear_color,
+                            camera_position,
+                        );
+                        Some((camera_render_data, camera.depth_format, camera.per_camera_uniforms.clone()))
+                    } else {
+                        log::warn!(
+                            "Camera {}, {} has no depth attachment!",
+                            camera_node_ref.id, camera_node_ref.name
+                        );
+                        None
+                    }
                 } else {
-                    log::warn!(
-                        "Camera {}, {} has no depth attachment!",
-                        camera_node_ref.id, camera_node_ref.name
-                    );
                     None
-                }
-            } else {
-                None
-            };
-            if let Some((mut camera_render_data, visible_layers, depth_format, per_camera_uniforms)) = camera_render_data {
-                Self::_generate_frame_per_camera(
+                };
+                if let Some((mut camera_render_data, depth_format, per_camera_uniforms)) = camera_render_data {
+                    let renderables = visible_renderables.remove(&(scene_index, *camera_node_id)).unwrap_or_default();
+                    Self::_generate_frame_per_camera(
                         cur_scene,
-                        visible_layers,
-                        depth_format,
-                        per_camera_uniforms,
-                        global_uniforms,
+                        CameraFrameInputs {
+                            depth_format,
+                            per_camera_uniforms,
+                            camera_render_data: &mut camera_render_data,
+                            visible_renderables: &renderables,
+                        },
+                        &mut frame_globals,
                         graphics_context,
                         texture_sampler_manager,
                         shader_manager,
                         material_manager,
                         mesh_manager,
-                        time,
-                        &mut camera_render_data,
-                        &cached_renderables,
-                        &lights_gpu_data,
-                        &mut global_uniform_sync_flags,
-                        reflection_map,
-                        brdf_lut,
-                        &sh,
                     );
-                    frame_renderer
-                        .frame_render_data
-                        .camera_data
-                        .push(camera_render_data);
+                        frame_renderer
+                            .frame_render_data
+                            .camera_data
+                            .push(camera_render_data);
+                }
             }
+            cur_scene.cached_cameras = cached_cameras;
         }
-        cur_scene.cached_cameras = cached_cameras;
-        cur_scene.cached_renderables = cached_renderables;
     }
 
+    /// Find the camera used to fit the cascades of directional light shadows.
+    /// It is the first enabled camera which renders to screen, or the first enabled camera if no camera renders to screen,
+    /// in the order in which the cameras are added to the current scene.
+    ///
+    /// Cascades are fitted to this camera only and shared by all cameras. Other cameras, e.g., the second camera of a
+    /// split screen or a camera of another loaded scene, sample the same cascades, so their directional shadows are
+    /// coarser or missing where they look outside the view of this camera.
+    fn _find_shadow_camera<'a>(current_scene: &'a Scene, cached_cameras: &[NodeHandle]) -> Option<(&'a Camera, Mat4)> {
+        let mut shadow_camera = None;
+        for camera_node_id in cached_cameras {
+            let camera_node_ref = current_scene.node_arena.get_forcely(camera_node_id);
+            if !camera_node_ref.enabled_in_hierarchy {
+                continue;
+            }
+            if let Some(camera) = current_scene.get_component::<Camera>(camera_node_id) {
+                let view_matrix = camera.get_world_view_matrix(&camera_node_ref.transform);
+                if camera.color_attachment == TextureHandle::INVALID {
+                    return Some((camera, view_matrix));
+                }
+                if shadow_camera.is_none() {
+                    shadow_camera = Some((camera, view_matrix));
+                }
+            }
+        }
+        shadow_camera
+    }
+
+    /// Generate the shadow caster items of all shadow views.
+    #[allow(clippy::too_many_arguments)]
+    fn _generate_shadow_frame(
+        scenes: &mut [Scene],
+        scene_indices: &[usize],
+        shadow_views: &[ShadowView],
+        shadow_mapper: &mut ShadowMapper,
+        shadow_view_data: &mut Vec<ShadowViewRenderData>,
+        graphics_context: &mut GraphicsContext,
+        texture_sampler_manager: &mut TextureSamplerManager,
+        shader_manager: &mut ShaderManager,
+        material_manager: &MaterialManager,
+        mesh_manager: &mut MeshManager,
+    ) {
+        let shadow_caster_shader_handle = *shader_manager.get_builtin_shadow_caster_shader().1;
+        {
+            let shader_mut_ref = shader_manager.get_shader_mut_forcely(&shadow_caster_shader_handle);
+            if !shader_mut_ref.is_inited {
+                shader_mut_ref.init(graphics_context);
+                shader_mut_ref.is_inited = true;
+            }
+        }
+        let shader_ref = shader_manager.get_shader_forcely(&shadow_caster_shader_handle);
+
+        let mut view_bind_groups = Vec::with_capacity(shadow_views.len());
+        for (view_index, shadow_view) in shadow_views.iter().enumerate() {
+            let view_uniforms = &mut shadow_mapper.view_uniforms[view_index];
+            view_uniforms.set_matrix4x4(BuiltinShaderUniformNames::_VP_MATRIX, shadow_view.view_projection);
+            view_uniforms.sync_properties(graphics_context, texture_sampler_manager);
+            view_bind_groups.push(view_uniforms.get_bind_group(
+                graphics_context,
+                texture_sampler_manager,
+                &shader_ref.shader_properties.per_camera_properties,
+            ));
+        }
+
+        // The layers and world bounds of the casters, which are culled per shadow view.
+        let mut caster_items = Vec::<(Layer, Option<Aabb>, ItemRenderData)>::new();
+        for &scene_index in scene_indices {
+            let current_scene = &mut scenes[scene_index];
+            let cached_renderables = std::mem::take(&mut current_scene.cached_renderables);
+            for renderable_node in &cached_renderables {
+                let node_ref = current_scene.node_arena.get_forcely(renderable_node);
+                if !node_ref.enabled_in_hierarchy {
+                    continue;
+                }
+                let (model_matrix, layer) = (node_ref.transform.model_matrix, node_ref.layer);
+                if let Some(mesh_renderer) = current_scene.get_component_mut::<MeshRenderer>(renderable_node)
+                    && mesh_renderer.cast_shadow
+                    && let Some(mesh_mut_ref) = mesh_manager.get_mesh_mut(&mesh_renderer.mesh) {
+                    if mesh_mut_ref.vertex_attributes.position.is_empty() {
+                        continue;
+                    }
+                    // Like camera culling, casters without bounds or opted out of culling are never culled.
+                    let world_aabb = match mesh_renderer.local_bounds {
+                        Some((local_aabb, _)) if mesh_renderer.frustum_culling => Some(local_aabb.transform(&model_matrix)),
+                        _ => None,
+                    };
+                    // Casters outside all shadow views cost neither uniform updates nor draw calls.
+                    if !shadow_views.iter().any(|shadow_view| shadow_view.contains_caster(layer, world_aabb.as_ref())) {
+                        continue;
+                    }
+                    if mesh_mut_ref.is_dirty {
+                        mesh_mut_ref.upload(graphics_context);
+                    }
+
+                    let vertex_buffer_layout = mesh_mut_ref.vertex_attributes.compute_vertex_buffer_layout();
+                    // Meshes with different vertex layouts need different pipelines.
+                    let mut hasher = AHasher::default();
+                    shader_ref.hash.hash(&mut hasher);
+                    vertex_buffer_layout.array_stride.hash(&mut hasher);
+                    vertex_buffer_layout.attributes.hash(&mut hasher);
+                    let render_pipeline_hash = hasher.finish();
+                    if !graphics_context.render_pipelines.contains(render_pipeline_hash) {
+                        graphics_context.render_pipelines.create_shadow_caster_pipeline(
+                            render_pipeline_hash,
+                            shader_ref,
+                            &[vertex_buffer_layout],
+                            SHADOW_ATLAS_FORMAT,
+                        );
+                    }
+
+                    mesh_renderer.per_object_uniforms.set_matrix4x4(
+                        BuiltinShaderUniformNames::_MODEL_MATRIX,
+                        model_matrix,
+                    );
+                    mesh_renderer
+                        .per_object_uniforms
+                        .sync_properties(graphics_context, texture_sampler_manager);
+                    let per_object_bind_group_id = mesh_renderer.per_object_uniforms.get_bind_group(
+                        graphics_context,
+                        texture_sampler_manager,
+                        &shader_ref.shader_properties.per_object_properties,
+                    );
+
+                    for (sub_mesh_index, sub_mesh) in mesh_mut_ref.sub_meshes.iter().enumerate() {
+                        // Only opaque items cast shadow.
+                        let is_opaque = mesh_renderer
+                            .materials
+                            .get(sub_mesh_index)
+                            .and_then(|material| material_manager.get_material(material))
+                            .is_some_and(|material| material.render_state.render_queue == RenderQueue::Opaque);
+                        if !is_opaque {
+                            continue;
+                        }
+                        caster_items.push((layer, world_aabb, ItemRenderData::new(
+                            vec![per_object_bind_group_id],
+                            render_pipeline_hash,
+                            mesh_mut_ref.vertex_buffer,
+                            Some(mesh_mut_ref.index_buffer),
+                            mesh_mut_ref.index_data.index_format(),
+                            sub_mesh.index_start,
+                            sub_mesh.index_count,
+                            sub_mesh.base_vertex,
+                        )));
+                    }
+                }
+            }
+            current_scene.cached_renderables = cached_renderables;
+        }
+
+        for (shadow_view, view_bind_group_id) in shadow_views.iter().zip(view_bind_groups) {
+            let item_data = caster_items
+                .iter()
+                .filter(|(layer, world_aabb, _)| shadow_view.contains_caster(*layer, world_aabb.as_ref()))
+                .map(|(_, _, caster_item)| {
+                    let mut item_render_data = caster_item.clone();
+                    item_render_data.bind_group.push(view_bind_group_id);
+                    item_render_data
+                })
+                .collect();
+            let tile = &shadow_view.tile;
+            shadow_view_data.push(ShadowViewRenderData {
+                view_port: Vec4::new(tile.x as f32, tile.y as f32, tile.size as f32, tile.size as f32),
+                item_data,
+            });
+        }
+    }
+
+    /// Generate the render items of the visible renderables of a camera, see [`World::cull_renderables`].
+    #[allow(clippy::too_many_arguments)]
     fn _generate_frame_per_camera(
         current_scene: &mut Scene,
-        visible_layers: LayerMask,
-        depth_format: TextureFormat,
-        per_camera_uniforms: RefCell<BuiltinUniforms>,
-        global_uniforms: &mut BuiltinUniforms,
+        camera_inputs: CameraFrameInputs,
+        frame_globals: &mut FrameGlobals,
         graphics_context: &mut GraphicsContext,
         texture_sampler_manager: &mut TextureSamplerManager,
         shader_manager: &mut ShaderManager,
         material_manager: &mut MaterialManager,
         mesh_manager: &mut MeshManager,
-        time: &mut Time,
-        camera_render_data: &mut CameraRenderData,
-        cached_renderables: &Vec<NodeHandle>,
-        lights_gpu_data: &LightsGPUData,
-        global_uniform_sync_flags: &mut GlobalUniformSyncFlags,
-        reflection_map: TextureHandle,
-        brdf_lut: TextureHandle,
-        sh: &SH,
     ) {
+        let CameraFrameInputs { depth_format, per_camera_uniforms, camera_render_data, visible_renderables } = camera_inputs;
         let mut camera_unifom_sync_flags = CameraUniformSyncFlags::new();
+        // Transparent items with their squared distances to the camera, which are sorted back-to-front below.
+        let mut transparent_items = Vec::<(f32, ItemRenderData)>::new();
         // let current_scene = self.current_scene_mut();
-        for renderable_node in cached_renderables {
+        for renderable_node in visible_renderables {
             let node_mut_ref = current_scene.node_arena.get_mut_forcely(renderable_node);
-            if !node_mut_ref.enabled_in_hierarchy || !visible_layers.contains(node_mut_ref.layer) {
-                continue;
-            }
             let model_matrix = node_mut_ref.transform.model_matrix;
             let normal_matrix = node_mut_ref.transform.normal_matrix;
+            let layer = node_mut_ref.layer;
             if let Some(mesh_renderer) = current_scene.get_component_mut::<MeshRenderer>(renderable_node) 
                 && let Some(mesh_mut_ref) = mesh_manager.get_mesh_mut(&mesh_renderer.mesh){
                 if mesh_mut_ref.is_dirty {
@@ -289,6 +792,12 @@ impl World {
                                     model_matrix,
                                 );
                             }
+                            if builtin_uniform_flags.has_layer_mask {
+                                mesh_renderer.per_object_uniforms.set_vec4u(
+                                    BuiltinShaderUniformNames::_LAYER_MASK,
+                                    UVec4::new(layer.bit(), 0, 0, 0),
+                                );
+                            }
                             if builtin_uniform_flags.has_normal_matrix {
                                 mesh_renderer.per_object_uniforms.set_matrix3x3(
                                     BuiltinShaderUniformNames::_NORMAL_MATRIX,
@@ -428,38 +937,45 @@ impl World {
                         // global uniforms
                         if shader_ref.shader_properties.per_scene_properties.is_valid() {
                             let mut need_sync_global_uniforms = false;
-                            if builtin_uniform_flags.has_time && !global_uniform_sync_flags.has_time_synced {
-                                global_uniforms.set_vec4f(
+                            if builtin_uniform_flags.has_time && !frame_globals.global_uniform_sync_flags.has_time_synced {
+                                frame_globals.global_uniforms.set_vec4f(
                                     BuiltinShaderUniformNames::_TIME,
-                                    time.time_data,
+                                    frame_globals.time.time_data,
                                 );
-                                global_uniform_sync_flags.has_time_synced = true;
+                                frame_globals.global_uniform_sync_flags.has_time_synced = true;
                                 need_sync_global_uniforms = true;
                             }
-                            if builtin_uniform_flags.has_environment_reflection_info() && !global_uniform_sync_flags.has_reflection_maps_synced {
-                                assert_ne!(reflection_map, TextureHandle::INVALID, "Reflection cube map is invalid!");
-                                global_uniforms.set_struct(BuiltinShaderUniformNames::_SH, bytemuck::bytes_of(sh).to_vec());
-                                global_uniforms.set_texture(BuiltinShaderUniformNames::_REFLECTION_CUBE_MAP, reflection_map);
-                                global_uniforms.set_sampler(BuiltinShaderUniformNames::_REFLECTION_CUBE_SAMPLER, Sampler::default_sampler());
-                                global_uniforms.set_texture(BuiltinShaderUniformNames::_BRDF_LUT, brdf_lut);
-                                if reflection_map != Texture::default_cube_texture() {
-                                    global_uniforms.enable_global_feature(BuiltinGlobalShaderFeatures::FEATURE_FLAG_IBL);
+                            if builtin_uniform_flags.has_environment_reflection_info() && !frame_globals.global_uniform_sync_flags.has_reflection_maps_synced {
+                                assert_ne!(frame_globals.reflection_map, TextureHandle::INVALID, "Reflection cube map is invalid!");
+                                frame_globals.global_uniforms.set_struct(BuiltinShaderUniformNames::_SH, bytemuck::bytes_of(frame_globals.sh).to_vec());
+                                frame_globals.global_uniforms.set_texture(BuiltinShaderUniformNames::_REFLECTION_CUBE_MAP, frame_globals.reflection_map);
+                                frame_globals.global_uniforms.set_sampler(BuiltinShaderUniformNames::_REFLECTION_CUBE_SAMPLER, Sampler::default_sampler());
+                                frame_globals.global_uniforms.set_texture(BuiltinShaderUniformNames::_BRDF_LUT, frame_globals.brdf_lut);
+                                if frame_globals.reflection_map != Texture::default_cube_texture() {
+                                    frame_globals.global_uniforms.enable_global_feature(BuiltinGlobalShaderFeatures::FEATURE_FLAG_IBL);
                                 } else {
-                                    global_uniforms.disable_global_feature(BuiltinGlobalShaderFeatures::FEATURE_FLAG_IBL,
-                                        !global_uniform_sync_flags.has_reflection_maps_synced);
+                                    frame_globals.global_uniforms.disable_global_feature(BuiltinGlobalShaderFeatures::FEATURE_FLAG_IBL,
+                                        !frame_globals.global_uniform_sync_flags.has_reflection_maps_synced);
                                 }
-                                global_uniform_sync_flags.has_reflection_maps_synced = true;
+                                frame_globals.global_uniform_sync_flags.has_reflection_maps_synced = true;
                                 need_sync_global_uniforms = true;
                             }
-                            if builtin_uniform_flags.has_lights && !global_uniform_sync_flags.has_lights_synced {
-                                global_uniforms.set_storage(BuiltinShaderUniformNames::_LIGHTING_INFOS, lights_gpu_data.to_vec_u8());
-                                global_uniform_sync_flags.has_lights_synced = true;
+                            if builtin_uniform_flags.has_lights && !frame_globals.global_uniform_sync_flags.has_lights_synced {
+                                frame_globals.global_uniforms.set_storage(BuiltinShaderUniformNames::_LIGHTING_INFOS, frame_globals.lights_gpu_data.to_vec_u8());
+                                frame_globals.global_uniform_sync_flags.has_lights_synced = true;
+                                need_sync_global_uniforms = true;
+                            }
+                            if builtin_uniform_flags.has_shadows && !frame_globals.global_uniform_sync_flags.has_shadows_synced {
+                                frame_globals.global_uniforms.set_struct(BuiltinShaderUniformNames::_SHADOW_INFOS, frame_globals.shadows_gpu_data.to_vec_u8());
+                                frame_globals.global_uniforms.set_texture(BuiltinShaderUniformNames::_SHADOW_ATLAS, frame_globals.shadow_atlas);
+                                frame_globals.global_uniforms.set_sampler(BuiltinShaderUniformNames::_SHADOW_SAMPLER, Sampler::shadow_sampler());
+                                frame_globals.global_uniform_sync_flags.has_shadows_synced = true;
                                 need_sync_global_uniforms = true;
                             }
                             if need_sync_global_uniforms {
-                                global_uniforms.sync_properties(graphics_context, texture_sampler_manager);
+                                frame_globals.global_uniforms.sync_properties(graphics_context, texture_sampler_manager);
                             }
-                            let global_bind_group_id = global_uniforms.get_bind_group(
+                            let global_bind_group_id = frame_globals.global_uniforms.get_bind_group(
                                 graphics_context,
                                 texture_sampler_manager,
                                 &shader_ref.shader_properties.per_scene_properties,
@@ -495,12 +1011,158 @@ impl World {
                                 camera_render_data.skybox_item_data = Some(item_render_data);
                             }
                             RenderQueue::Transparent => {
-                                camera_render_data.transparent_item_data.push(item_render_data);
+                                let distance_squared = model_matrix
+                                    .w_axis
+                                    .truncate()
+                                    .distance_squared(camera_render_data.camera_position);
+                                transparent_items.push((distance_squared, item_render_data));
                             }
                         } 
                     }
                 }
             }
         }
+
+        sort_back_to_front(&mut transparent_items);
+        camera_render_data
+            .transparent_item_data
+            .extend(transparent_items.into_iter().map(|(_, item_render_data)| item_render_data));
+    }
+}
+
+/// Sort transparent items from far to near by their squared distances to the camera, so that they are blended
+/// correctly. The sort is stable, so sub meshes of the same node keep their order.
+fn sort_back_to_front<T>(items: &mut [(f32, T)]) {
+    items.sort_by(|a, b| b.0.total_cmp(&a.0));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{math::color::Color, prelude::Light};
+
+    #[test]
+    fn test_culling_stats() {
+        use crate::{assets::meshes::primitives::cuboid::Cuboid, math::Vec3};
+
+        let mut mesh_manager = MeshManager::new();
+        let cube = mesh_manager.add_mesh(Cuboid::default().into());
+        let mut world = World::new();
+        let scene = &mut world.scenes[0];
+        let camera_node = scene.create_node("Camera");
+        scene.get_node_mut_forcely(&camera_node).transform.set_position(Vec3::new(0.0, 0.0, 5.0));
+        let mut camera = Camera::default();
+        // Cameras without depth attachment are not rendered, and their renderables are not culled.
+        camera.depth_attachment = TextureHandle::new(1);
+        scene.add_component(&camera_node, camera);
+        scene.add(camera_node);
+        let visible_cube = scene.create_node("Visible");
+        scene.add_component(&visible_cube, MeshRenderer::new(cube, vec![]));
+        scene.add(visible_cube);
+        let behind_cube = scene.create_node("Behind");
+        scene.get_node_mut_forcely(&behind_cube).transform.set_position(Vec3::new(0.0, 0.0, 10.0));
+        scene.add_component(&behind_cube, MeshRenderer::new(cube, vec![]));
+        scene.add(behind_cube);
+        scene.on_update(&mut Time::new());
+        world.sync_mesh_bounds(&mesh_manager);
+
+        let visible_renderables = world.cull_renderables(&mesh_manager);
+        assert_eq!(visible_renderables[&(0, camera_node)], vec![visible_cube]);
+        assert_eq!(world.culling_stats(), CullingStats { visible: 1, culled: 1 });
+
+        // The stats are reset every frame instead of being accumulated.
+        world.cull_renderables(&mesh_manager);
+        assert_eq!(world.culling_stats(), CullingStats { visible: 1, culled: 1 });
+        world.scenes[0].get_component_mut::<MeshRenderer>(&behind_cube).unwrap().frustum_culling = false;
+        world.cull_renderables(&mesh_manager);
+        assert_eq!(world.culling_stats(), CullingStats { visible: 2, culled: 0 });
+    }
+
+    #[test]
+    fn test_culling_with_parented_camera() {
+        use crate::{assets::meshes::primitives::cuboid::Cuboid, math::Vec3};
+
+        let mut mesh_manager = MeshManager::new();
+        let cube = mesh_manager.add_mesh(Cuboid::default().into());
+        let mut world = World::new();
+        let scene = &mut world.scenes[0];
+        // The rig is moved and turned around, the camera looks at the rig from behind it in world space.
+        let rig_node = scene.create_node("Rig");
+        {
+            let transform = &mut scene.get_node_mut_forcely(&rig_node).transform;
+            transform.set_position(Vec3::new(20.0, 0.0, 0.0));
+            transform.set_rotation_euler(Vec3::new(0.0, std::f32::consts::PI, 0.0));
+        }
+        scene.add(rig_node);
+        let camera_node = scene.create_node("Camera");
+        scene.get_node_mut_forcely(&camera_node).transform.set_position(Vec3::new(0.0, 0.0, 5.0));
+        let mut camera = Camera::default();
+        camera.depth_attachment = TextureHandle::new(1);
+        scene.add_component(&camera_node, camera);
+        scene.add(camera_node);
+        scene.attach_to_parent(&camera_node, rig_node);
+
+        let mut create_cube = |name: &str, position: Vec3| {
+            let node = scene.create_node(name);
+            scene.get_node_mut_forcely(&node).transform.set_position(position);
+            scene.add_component(&node, MeshRenderer::new(cube, vec![]));
+            scene.add(node);
+            node
+        };
+        let rig_cube = create_cube("Rig Cube", Vec3::new(20.0, 0.0, 0.0));
+        let _origin_cube = create_cube("Origin Cube", Vec3::ZERO);
+        let _behind_cube = create_cube("Behind Cube", Vec3::new(20.0, 0.0, -10.0));
+        scene.on_update(&mut Time::new());
+        world.sync_mesh_bounds(&mesh_manager);
+
+        let visible_renderables = world.cull_renderables(&mesh_manager);
+        assert_eq!(visible_renderables[&(0, camera_node)], vec![rig_cube]);
+        assert_eq!(world.culling_stats(), CullingStats { visible: 1, culled: 2 });
+    }
+
+    #[test]
+    fn test_sort_back_to_front() {
+        let mut items = vec![(1.0, "near"), (100.0, "far"), (25.0, "middle_0"), (25.0, "middle_1"), (0.0, "camera")];
+        sort_back_to_front(&mut items);
+        let names: Vec<_> = items.iter().map(|(_, name)| *name).collect();
+        // Items at the same distance keep their order.
+        assert_eq!(names, ["far", "middle_0", "middle_1", "near", "camera"]);
+    }
+
+    #[test]
+    fn test_multi_scene() {
+        let mut world = World::new();
+        let mut time = Time::new();
+        let level = world.add_scene(Scene::new());
+        assert!(!world.is_scene_loaded(level));
+        assert!(!world.set_active_scene(level));
+
+        assert!(world.load_scene(level));
+        assert!(!world.load_scene(level));
+        assert_eq!(world.loaded_scenes(), &[0, level]);
+        // The current scene can not be unloaded.
+        assert!(!world.unload_scene(0, &mut time));
+        assert!(world.set_active_scene(level));
+        assert!(world.unload_scene(0, &mut time));
+        assert_eq!(world.loaded_scenes(), &[level]);
+
+        let player = world.scenes[0].create_node("Player");
+        let weapon = world.scenes[0].create_node("Weapon");
+        world.scenes[0].add_component(&weapon, Light::new_point_light(5.0, Color::WHITE, 1.0, false));
+        world.scenes[0].attach_to_parent(&weapon, player);
+        world.scenes[0].add(player);
+
+        let moved_player = world.move_node_to_scene(&player, 0, level).unwrap();
+        assert!(world.scenes[0].get_node(&player).is_none());
+        assert!(world.scenes[0].get_node(&weapon).is_none());
+        assert!(world.scenes[0].root_nodes.is_empty());
+        assert!(world.scenes[0].cached_lights.is_empty());
+
+        let level_scene = &world.scenes[level];
+        assert_eq!(level_scene.root_nodes, vec![moved_player]);
+        let moved_weapon = level_scene.get_node_forcely(&moved_player).children.as_ref().unwrap()[0];
+        assert_eq!(level_scene.get_node_forcely(&moved_weapon).name, "Weapon");
+        assert_eq!(level_scene.get_component::<Light>(&moved_weapon).unwrap().get_max_distance(), 5.0);
+        assert_eq!(level_scene.cached_lights, vec![moved_weapon]);
     }
 }
@@ -15,7 +15,7 @@ use crate::{
         buffer::BufferManager,
         render_pipeline::RenderPipelineManager, uniform::{UniformMap, UniformValue},
     },
-    window::Window,
+    window::{Window, WindowSize},
 };
 
 /// Device limits, for example, min_uniform_buffer_offset_alignment.
@@ -60,7 +60,9 @@ impl GraphicsLimits {
 #[allow(dead_code)]
 pub struct GraphicsContext {
     instance: Instance,
-    pub(crate) surface: Surface<'static>,
+    /// The surface of the main window. It is None in headless mode.
+    pub(crate) surface: Option<Surface<'static>>,
+    /// The surface configuration. In headless mode, it describes the offscreen color target.
     pub(crate) surface_config: SurfaceConfiguration,
     pub(crate) device: Rc<Device>,
     pub(crate) queue: Rc<Queue>,
@@ -69,7 +71,10 @@ pub struct GraphicsContext {
     pub(crate) bind_group_manager: BindGroupManager,
     pub(crate) buffer_manager: BufferManager,
     pub(crate) limits: Rc<GraphicsLimits>,
-    _main_window: Window,
+    /// The main window. It is None in headless mode.
+    _main_window: Option<Window>,
+    /// The size of the offscreen color target in headless mode.
+    _headless_size: WindowSize,
 }
 
 impl GraphicsContext {
@@ -94,7 +99,7 @@ impl GraphicsContext {
             }
         }
 
-        let adapter = pollster::block_on(Self::async_request_adapter(&instance, &surface));
+        let adapter = pollster::block_on(Self::async_request_adapter(&instance, Some(&surface), false));
 
         let (device, queue) = pollster::block_on(Self::async_request_device_and_queue(&adapter));
         let limits = GraphicsLimits::new(&device);
@@ -110,7 +115,7 @@ impl GraphicsContext {
        
         let graphics_context = GraphicsContext {
             instance,
-            surface,
+            surface: Some(surface),
             surface_config,
             device,
             queue,
@@ -119,17 +124,100 @@ impl GraphicsContext {
             bind_group_manager: BindGroupManager::new(),
             buffer_manager,
             limits,
-            _main_window: window,
+            _main_window: Some(window),
+            _headless_size: WindowSize::default(),
         };
         graphics_context
     }
 
-    async fn async_request_adapter(instance: &Instance, surface: &Surface<'static>) -> Adapter {
+    /// Create a graphics context without window and surface, which is used in headless mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The width of the offscreen color target in pixels.
+    /// * `height` - The height of the offscreen color target in pixels.
+    /// * `format` - The format of the offscreen color target.
+    /// * `force_fallback_adapter` - Whether to use the software fallback adapter, e.g., on CI machines without GPU.
+    pub(crate) fn new_headless(
+        width: u32,
+        height: u32,
+        format: TextureFormat,
+        force_fallback_adapter: bool,
+    ) -> GraphicsContext {
+        let instance = wgpu::Instance::default();
+        let width = width.max(1);
+        let height = height.max(1);
+        let adapter = pollster::block_on(Self::async_request_adapter(&instance, None, force_fallback_adapter));
+
+        let (device, queue) = pollster::block_on(Self::async_request_device_and_queue(&adapter));
+        let limits = GraphicsLimits::new(&device);
+        // No surface is configured, the configuration only describes the offscreen color target.
+        let surface_config = SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format,
+            width,
+            height,
+            present_mode: wgpu::PresentMode::AutoNoVsync,
+            desired_maximum_frame_latency: 2,
+            alpha_mode: wgpu::CompositeAlphaMode::Auto,
+            view_formats: vec![],
+        };
+
+        let device = Rc::new(device);
+        let queue = Rc::new(queue);
+        let buffer_manager = BufferManager::new(device.clone(), queue.clone(), limits.clone());
+        let render_pipeline_manager = RenderPipelineManager::new(device.clone(), limits.clone());
+
+        GraphicsContext {
+            instance,
+            surface: None,
+            surface_config,
+            device,
+            queue,
+            adapter,
+            render_pipelines: render_pipeline_manager,
+            bind_group_manager: BindGroupManager::new(),
+            buffer_manager,
+            limits,
+            _main_window: None,
+            _headless_size: WindowSize::new(width as f32, height as f32),
+        }
+    }
+
+    /// Check whether an adapter supporting the required features is available for headless mode, e.g., tests can be
+    /// skipped on machines without such an adapter.
+    pub fn is_headless_adapter_available(force_fallback_adapter: bool) -> bool {
+        let instance = wgpu::Instance::default();
+        pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            force_fallback_adapter,
+            compatible_surface: None,
+        }))
+        .is_ok_and(|adapter| {
+            let features = adapter.features();
+            features.contains(Self::required_features(features))
+        })
+    }
+
+    /// The features the engine requires, plus the optional ones supported by the adapter.
+    fn required_features(adapter_features: wgpu::Features) -> wgpu::Features {
+        let mut required_features =
+            wgpu::Features::TEXTURE_FORMAT_16BIT_NORM | wgpu::Features::FLOAT32_FILTERABLE;
+        if adapter_features.contains(wgpu::Features::POLYGON_MODE_LINE) {
+            required_features |= wgpu::Features::POLYGON_MODE_LINE;
+        }
+        if adapter_features.contains(wgpu::Features::POLYGON_MODE_POINT) {
+            required_features |= wgpu::Features::POLYGON_MODE_POINT;
+        }
+        required_features
+    }
+
+    async fn async_request_adapter(instance: &Instance, surface: Option<&Surface<'static>>, force_fallback_adapter: bool) -> Adapter {
         let adapter = instance
             .request_adapter(&wgpu::RequestAdapterOptions {
                 power_preference: wgpu::PowerPreference::HighPerformance,
-                force_fallback_adapter: false,
-                compatible_surface: Some(surface),
+                force_fallback_adapter,
+                compatible_surface: surface,
             })
             .await
             .expect("Failed to find an appropriate adapter");
@@ -153,14 +241,7 @@ impl GraphicsContext {
 
     async fn async_request_device_and_queue(adapter: &Adapter) -> (Device, Queue) {
         let features = adapter.features();
-        let mut required_features =
-            wgpu::Features::TEXTURE_FORMAT_16BIT_NORM | wgpu::Features::FLOAT32_FILTERABLE;
-        if features.contains(wgpu::Features::POLYGON_MODE_LINE) {
-            required_features |= wgpu::Features::POLYGON_MODE_LINE;
-        }
-        if features.contains(wgpu::Features::POLYGON_MODE_POINT) {
-            required_features |= wgpu::Features::POLYGON_MODE_POINT;
-        }
+        let required_features = Self::required_features(features);
         assert!(
             features.contains(required_features),
             "Adapter does not support required features"
@@ -193,42 +274,81 @@ impl GraphicsContext {
             "GraphicsContext on_resize: ({}, {})",
             new_physical_size.width, new_physical_size.height
         );
-        let dpi = self._main_window.force_get_scale_factor();
-        self.surface_config.width = new_physical_size.width.max(1);
-        self.surface_config.height = new_physical_size.height.max(1);
-        self.surface.configure(&self.device, &self.surface_config);
-        let new_logical_size: LogicalSize<u32> = new_physical_size.to_logical(dpi);
-        self._main_window.set_physical_size(
-            new_physical_size.width as f32,
-            new_physical_size.height as f32,
-        );
-        self._main_window.set_logical_size(
-            new_logical_size.width as f32,
-            new_logical_size.height as f32,
-        );
-        self._main_window.request_redraw();
+        if let (Some(main_window), Some(surface)) = (&mut self._main_window, &self.surface) {
+            let dpi = main_window.force_get_scale_factor();
+            self.surface_config.width = new_physical_size.width.max(1);
+            self.surface_config.height = new_physical_size.height.max(1);
+            surface.configure(&self.device, &self.surface_config);
+            let new_logical_size: LogicalSize<u32> = new_physical_size.to_logical(dpi);
+            main_window.set_physical_size(
+                new_physical_size.width as f32,
+                new_physical_size.height as f32,
+            );
+            main_window.set_logical_size(
+                new_logical_size.width as f32,
+                new_logical_size.height as f32,
+            );
+            main_window.request_redraw();
+        }
     }
 
     pub(crate) fn on_dpi_changed(&mut self, new_dpi: f64) {
-        self._main_window.dpi = new_dpi;
+        if let Some(main_window) = &mut self._main_window {
+            main_window.dpi = new_dpi;
+        }
     }
 
+    /// The dpi of the main window. It is always 1.0 in headless mode.
     pub fn dpi(&self) -> f64 {
-        self._main_window.dpi
+        if let Some(main_window) = &self._main_window {
+            main_window.dpi
+        } else {
+            1.0
+        }
     }
 
     pub fn request_redraw(&self) {
-        self._main_window.request_redraw();
+        if let Some(main_window) = &self._main_window {
+            main_window.request_redraw();
+        }
+    }
+
+    /// The main window. It is None in headless mode.
+    pub fn main_window(&self) -> Option<&Window> {
+        self._main_window.as_ref()
     }
 
-    pub fn main_window(&self) -> &Window {
-        &self._main_window
+    /// Whether the graphics context is created without window, i.e., in headless mode.
+    pub fn is_headless(&self) -> bool {
+        self._main_window.is_none()
+    }
+
+    /// The physical size of the main window, or the size of the offscreen color target in headless mode.
+    pub fn get_physical_size(&self) -> &WindowSize {
+        if let Some(main_window) = &self._main_window {
+            main_window.get_physical_size()
+        } else {
+            &self._headless_size
+        }
+    }
+
+    /// The logical size of the main window, or the size of the offscreen color target in headless mode.
+    pub fn get_logical_size(&self) -> &WindowSize {
+        if let Some(main_window) = &self._main_window {
+            main_window.get_logical_size()
+        } else {
+            &self._headless_size
+        }
     }
 
     pub fn get_swapchain_format(&self) -> TextureFormat {
-        let swapchain_capabilities = self.surface.get_capabilities(&self.adapter);
-        let swapchain_format = swapchain_capabilities.formats[0];
-        swapchain_format
+        if let Some(surface) = &self.surface {
+            let swapchain_capabilities = surface.get_capabilities(&self.adapter);
+            let swapchain_format = swapchain_capabilities.formats[0];
+            swapchain_format
+        } else {
+            self.surface_config.format
+        }
     }
 
     /// Create BindGroups for all uniforms.
@@ -4,7 +4,7 @@ use crate::{
     assets::{TextureHandle, TextureSamplerManager, texture_view::TextureView},
     graphics::graphics_context::GraphicsContext,
     math::{Vec4, color::Color},
-    renderer::frame_data::{CameraRenderData, ItemRenderData}
+    renderer::{frame_data::{CameraRenderData, ItemRenderData}, shadow_map::ShadowViewRenderData}
 };
 
 /// Utilities and APIs to draw a Mesh, or a scene.
@@ -34,6 +34,48 @@ impl RenderAPI {
         graphics_context.queue.submit(Some(encoder.finish()));
     }
 
+    /// Render all shadow views into the shadow atlas in a single depth-only render pass.
+    /// Each shadow view is a tile of the atlas, which is selected by the viewport.
+    pub(crate) fn render_shadow_maps(
+        graphics_context: &GraphicsContext,
+        texture_sampler_manager: &mut TextureSamplerManager,
+        shadow_atlas: TextureHandle,
+        shadow_view_data: &Vec<ShadowViewRenderData>,
+    ) {
+        let mut encoder =
+            graphics_context
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: Some("imagic shadow command encoder desc"),
+                });
+        {
+            let depth_view = texture_sampler_manager.ensure_depth_texture_valid(shadow_atlas);
+            let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("imagic shadow pass desc"),
+                color_attachments: &[],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: depth_view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(1.0),
+                        store: wgpu::StoreOp::Store,
+                    }),
+                    stencil_ops: None,
+                }),
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+
+            for shadow_view in shadow_view_data {
+                let view_port = shadow_view.view_port;
+                rpass.set_viewport(view_port.x, view_port.y, view_port.z, view_port.w, 0.0, 1.0);
+                for render_item in &shadow_view.item_data {
+                    Self::_render_item(&mut rpass, render_item, graphics_context);
+                }
+            }
+        }
+        graphics_context.queue.submit(Some(encoder.finish()));
+    }
+
     /// Render a single render pass.
     /// 
     /// - Per-Material uniforms should be a unique bind group.
@@ -96,6 +96,17 @@ impl RenderPipelineManager {
             crate::graphics::render_states::CullMode::None => None,
         };
         
+        let blend_state: Option<wgpu::BlendState> = material.render_state.blend_mode.into();
+        let targets = targets
+            .iter()
+            .map(|target| {
+                target.clone().map(|mut target| {
+                    target.blend = blend_state;
+                    target
+                })
+            })
+            .collect::<Vec<Option<ColorTargetState>>>();
+
         let render_pipeline =
             self.device
                 .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
@@ -111,7 +122,7 @@ impl RenderPipelineManager {
                         module: shader.get_shader_module(),
                         entry_point: Some("fs_main"),
                         compilation_options: Default::default(),
-                        targets: targets,
+                        targets: &targets,
                     }),
                     primitive: wgpu::PrimitiveState {
                         cull_mode: cull_mode,
@@ -120,11 +131,67 @@ impl RenderPipelineManager {
                         ..Default::default()
                     },
                     // depth_stencil: None,
+                    depth_stencil: Some(wgpu::DepthStencilState {
+                        format: depth_format,
+                        depth_write_enabled: material.render_state.depth_write,
+                        depth_compare: material.render_state.depth_compare,
+                        stencil: wgpu::StencilState::default(),
+                        bias: wgpu::DepthBiasState::default(),
+                    }),
+                    multisample: wgpu::MultisampleState::default(),
+                    multiview: None,
+                    cache: None,
+                });
+        self.insert(pipeline_hash, render_pipeline);
+    }
+
+    /// Create a depth-only render pipeline which renders shadow casters into the shadow atlas.
+    pub(crate) fn create_shadow_caster_pipeline(
+        &mut self,
+        pipeline_hash: PipelineHashType,
+        shader: &Shader,
+        vertex_buffer_layouts: &[VertexBufferLayout],
+        depth_format: wgpu::TextureFormat,
+    ) {
+        if cfg!(debug_assertions) {
+            info!("Create shadow caster pipeline with hash: {}", pipeline_hash);
+        }
+
+        let pipeline_layout =
+            self.device
+                .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                    label: Some("shadow caster pipeline layout"),
+                    bind_group_layouts: &shader.get_bind_group_layouts(),
+                    push_constant_ranges: &[],
+                });
+
+        let render_pipeline =
+            self.device
+                .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                    label: Some("create shadow caster pipeline"),
+                    layout: Some(&pipeline_layout),
+                    vertex: wgpu::VertexState {
+                        module: shader.get_shader_module(),
+                        entry_point: Some("vs_main"),
+                        buffers: vertex_buffer_layouts,
+                        compilation_options: Default::default(),
+                    },
+                    // depth only.
+                    fragment: None,
+                    primitive: wgpu::PrimitiveState {
+                        // Both faces are rendered, so that single-sided meshes like quads also cast shadow.
+                        cull_mode: None,
+                        front_face: wgpu::FrontFace::Ccw,
+                        polygon_mode: PolygonMode::Fill,
+                        ..Default::default()
+                    },
                     depth_stencil: Some(wgpu::DepthStencilState {
                         format: depth_format,
                         depth_write_enabled: true,
                         depth_compare: wgpu::CompareFunction::LessEqual,
                         stencil: wgpu::StencilState::default(),
+                        // The pipeline is shared by all lights, so the per-light `ShadowSettings::depth_bias` and
+                        // `ShadowSettings::normal_offset` are applied when sampling the shadow atlas in pbr.wgsl.
                         bias: wgpu::DepthBiasState::default(),
                     }),
                     multisample: wgpu::MultisampleState::default(),
@@ -1,3 +1,5 @@
+use crate::assets::CompareFunction;
+
 
 /// Cull mode when face culling.
 #[repr(C)]
@@ -47,10 +49,115 @@ pub enum RenderQueue {
     Transparent,
 }
 
-#[derive(Default, Hash, PartialEq, Eq, Clone)]
+/// How the output color of the fragment shader is blended with the color in the render target.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum BlendMode {
+    /// No blending, the output color replaces the destination color.
+    #[default]
+    Opaque = 0,
+    /// Traditional alpha blending: `src * src_alpha + dst * (1 - src_alpha)`.
+    Alpha = 1,
+    /// Blending for colors whose rgb are already multiplied by alpha: `src + dst * (1 - src_alpha)`.
+    Premultiplied = 2,
+    /// Additive blending: `src * src_alpha + dst`, e.g., for glows and particles.
+    Additive = 3,
+    /// Multiplicative blending: `src * dst`, e.g., for tinted glass or decals.
+    Multiply = 4,
+}
+
+impl From<BlendMode> for Option<wgpu::BlendState> {
+    fn from(value: BlendMode) -> Self {
+        match value {
+            BlendMode::Opaque => None,
+            BlendMode::Alpha => Some(wgpu::BlendState::ALPHA_BLENDING),
+            BlendMode::Premultiplied => Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+            BlendMode::Additive => Some(wgpu::BlendState {
+                color: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::SrcAlpha,
+                    dst_factor: wgpu::BlendFactor::One,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                alpha: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::Zero,
+                    dst_factor: wgpu::BlendFactor::One,
+                    operation: wgpu::BlendOperation::Add,
+                },
+            }),
+            BlendMode::Multiply => Some(wgpu::BlendState {
+                color: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::Dst,
+                    dst_factor: wgpu::BlendFactor::Zero,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                alpha: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::Zero,
+                    dst_factor: wgpu::BlendFactor::One,
+                    operation: wgpu::BlendOperation::Add,
+                },
+            }),
+        }
+    }
+}
+
+#[derive(Hash, PartialEq, Eq, Clone)]
 pub struct RenderState {
     pub cull_mode: CullMode,
     pub front_face: WindingOrder,
     pub polygon_mode: PolygonMode,
     pub render_queue: RenderQueue,
+    /// How the output color is blended. Call [`crate::prelude::Material::mark_dirty`] after changing it, so that the
+    /// pipeline and the alpha output of builtin shaders are updated.
+    pub blend_mode: BlendMode,
+    /// Whether to write depth. Transparent items usually do not write depth.
+    pub depth_write: bool,
+    /// The comparison function of depth test. Use [`CompareFunction::Always`] to disable depth test.
+    pub depth_compare: CompareFunction,
+}
+
+impl Default for RenderState {
+    fn default() -> Self {
+        Self {
+            cull_mode: CullMode::default(),
+            front_face: WindingOrder::default(),
+            polygon_mode: PolygonMode::default(),
+            render_queue: RenderQueue::default(),
+            blend_mode: BlendMode::default(),
+            depth_write: true,
+            depth_compare: CompareFunction::LessEqual,
+        }
+    }
+}
+
+impl RenderState {
+    /// Create a render state for transparent items, which are blended with `blend_mode`, do not write depth
+    /// and are rendered back-to-front in [`RenderQueue::Transparent`].
+    pub fn transparent(blend_mode: BlendMode) -> Self {
+        Self {
+            render_queue: RenderQueue::Transparent,
+            blend_mode,
+            depth_write: false,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transparent_render_state() {
+        let opaque = RenderState::default();
+        assert!(opaque.depth_write);
+        assert_eq!(opaque.render_queue, RenderQueue::Opaque);
+        assert_eq!(opaque.blend_mode, BlendMode::Opaque);
+
+        let transparent = RenderState::transparent(BlendMode::Additive);
+        assert!(!transparent.depth_write);
+        assert_eq!(transparent.render_queue, RenderQueue::Transparent);
+        assert_eq!(transparent.blend_mode, BlendMode::Additive);
+        // Transparent items are still depth tested against opaque items.
+        assert_eq!(transparent.depth_compare, CompareFunction::LessEqual);
+    }
 }
@@ -11,21 +11,22 @@ pub enum TextureReadError {
     UnsupportedFormat(TextureFormat),
 }
 
-pub enum CubeTextureData {
-    /// For cube texture with format of Rgba8Unorm, etc.
+/// Pixels read back from a texture, one `Vec` per layer, e.g., six faces of a cube texture or one layer of a 2D texture.
+pub enum TextureData {
+    /// For texture with format of Rgba8Unorm, etc.
     U8(Vec<Vec<u8>>),
-    /// For cube texture with format of Rgba32Float, etc.
+    /// For texture with format of Rgba32Float or Rgba16Float, etc.
     F32(Vec<Vec<f32>>),
-    /// For cube texture with format of Rgba16Uint, etc.
+    /// For texture with format of Rgba16Uint, etc.
     U16(Vec<Vec<u16>>),
-    /// For cube texture with format of Rgba32Sint, etc.
+    /// For texture with format of Rgba32Sint, etc.
     I32(Vec<Vec<i32>>),
 }
 
 pub fn read_cube_texture(
     graphics_context: & GraphicsContext,
     cube_texture: &Texture,
-) -> Result<CubeTextureData, TextureReadError> {
+) -> Result<TextureData, TextureReadError> {
     let bytes_per_pixel = cube_texture.bytes_per_pixel();
     let width = cube_texture.size.width;
     let height = cube_texture.size.height;
@@ -78,17 +79,88 @@ pub fn read_cube_texture(
 
     queue.submit(std::iter::once(encoder.finish()));
 
-    read_and_convert_data(device, read_buffer, face_size, cube_texture.size.width, cube_texture.size.height, cube_texture.format)
+    let data = map_read_buffer(device, read_buffer)?;
+    convert_buffer_data(&data, face_size, 6, cube_texture.size.width, cube_texture.size.height, cube_texture.format)
 }
 
-fn read_and_convert_data(
+/// Read back the pixels of the first mip level of a 2D texture, e.g., the offscreen color target in headless mode.
+///
+/// The texture must be created with `TextureUsages::COPY_SRC`.
+pub fn read_texture_2d(
+    graphics_context: & GraphicsContext,
+    texture: &Texture,
+) -> Result<TextureData, TextureReadError> {
+    let bytes_per_pixel = texture.bytes_per_pixel();
+    let width = texture.size.width;
+    let height = texture.size.height;
+    let unpadded_bytes_per_row = bytes_per_pixel * width;
+    let padded_bytes_per_row = padded_bytes_per_row(unpadded_bytes_per_row);
+
+    let device = &graphics_context.device;
+    let queue = &graphics_context.queue;
+
+    let read_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        size: (padded_bytes_per_row * height) as wgpu::BufferAddress,
+        usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+        label: Some("texture_2d_read_buffer"),
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("texture_2d_copy_encoder"),
+    });
+
+    if let Some(gpu_texture) = &texture.gpu_texture {
+        encoder.copy_texture_to_buffer(
+            wgpu::TexelCopyTextureInfo {
+                texture: gpu_texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::TexelCopyBufferInfo {
+                buffer: &read_buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row),
+                    rows_per_image: Some(height),
+                },
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+
+    queue.submit(std::iter::once(encoder.finish()));
+
+    let padded_data = map_read_buffer(device, read_buffer)?;
+    let data = remove_row_padding(&padded_data, unpadded_bytes_per_row, padded_bytes_per_row);
+    let layer_size = data.len() as wgpu::BufferAddress;
+    convert_buffer_data(&data, layer_size, 1, width, height, texture.format)
+}
+
+/// bytes_per_row of a texture copy must be a multiple of COPY_BYTES_PER_ROW_ALIGNMENT, i.e., 256.
+pub(crate) fn padded_bytes_per_row(unpadded_bytes_per_row: u32) -> u32 {
+    unpadded_bytes_per_row.div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT) * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT
+}
+
+/// Remove the padding at the end of each row of the data copied from a texture.
+pub(crate) fn remove_row_padding(padded_data: &[u8], unpadded_bytes_per_row: u32, padded_bytes_per_row: u32) -> Vec<u8> {
+    let row_count = padded_data.len() / padded_bytes_per_row as usize;
+    let mut data = Vec::with_capacity(unpadded_bytes_per_row as usize * row_count);
+    for row in padded_data.chunks_exact(padded_bytes_per_row as usize) {
+        data.extend_from_slice(&row[..unpadded_bytes_per_row as usize]);
+    }
+    data
+}
+
+fn map_read_buffer(
     device: &wgpu::Device,
     read_buffer: wgpu::Buffer,
-    face_size: wgpu::BufferAddress,
-    width: u32,
-    height: u32,
-    format: TextureFormat,
-) -> Result<CubeTextureData, TextureReadError> {
+) -> Result<Vec<u8>, TextureReadError> {
     let buffer_slice = read_buffer.slice(..);
     let (sender, receiver) = std::sync::mpsc::channel();
 
@@ -100,21 +172,22 @@ fn read_and_convert_data(
 
     match receiver.recv() {
         Ok(Ok(())) => {
-            let data = buffer_slice.get_mapped_range();
-            convert_buffer_data(&data, face_size, width, height, format)
+            let data = buffer_slice.get_mapped_range().to_vec();
+            Ok(data)
         }
         Ok(Err(e)) => Err(TextureReadError::MappingFailed(format!("{:?}", e))),
         Err(_) => Err(TextureReadError::MappingFailed("Channel error".to_string())),
     }
 }
 
-fn convert_buffer_data(
+pub(crate) fn convert_buffer_data(
     data: &[u8],
-    face_size: wgpu::BufferAddress,
+    layer_size: wgpu::BufferAddress,
+    layer_count: u32,
     width: u32,
     height: u32,
     format: TextureFormat,
-) -> Result<CubeTextureData, TextureReadError> {
+) -> Result<TextureData, TextureReadError> {
     match format {
         TextureFormat::Rgba8Unorm
         | TextureFormat::Rgba8UnormSrgb
@@ -122,11 +195,11 @@ fn convert_buffer_data(
         | TextureFormat::Rgba8Uint
         | TextureFormat::Rgba8Sint
         | TextureFormat::Bgra8Unorm
-        | TextureFormat::Bgra8UnormSrgb => convert_to_u8(data, face_size),
-        TextureFormat::Rgba32Float => convert_to_f32(data, face_size, width, height),
-        TextureFormat::Rgba16Float => convert_to_f16(data, face_size, width, height),
-        TextureFormat::Rgba32Uint => convert_to_u32(data, face_size, width, height),
-        TextureFormat::Rgba32Sint => convert_to_i32(data, face_size, width, height),
+        | TextureFormat::Bgra8UnormSrgb => convert_to_u8(data, layer_size, layer_count),
+        TextureFormat::Rgba32Float => convert_to_f32(data, layer_size, layer_count, width, height),
+        TextureFormat::Rgba16Float => convert_to_f16(data, layer_size, layer_count, width, height),
+        TextureFormat::Rgba32Uint => convert_to_u32(data, layer_size, layer_count, width, height),
+        TextureFormat::Rgba32Sint => convert_to_i32(data, layer_size, layer_count, width, height),
         _ => Err(TextureReadError::UnsupportedFormat(format)),
     }
 }
@@ -134,10 +207,11 @@ fn convert_buffer_data(
 fn convert_to_u8(
     data: &[u8],
     face_size: wgpu::BufferAddress,
-) -> Result<CubeTextureData, TextureReadError> {
-    let mut faces_data = Vec::with_capacity(6);
+    layer_count: u32,
+) -> Result<TextureData, TextureReadError> {
+    let mut faces_data = Vec::with_capacity(layer_count as usize);
 
-    for face in 0..6 {
+    for face in 0..layer_count {
         let start = (face_size * face as wgpu::BufferAddress) as usize;
         let end = start + face_size as usize;
 
@@ -149,20 +223,21 @@ fn convert_to_u8(
         faces_data.push(face_data);
     }
 
-    Ok(CubeTextureData::U8(faces_data))
+    Ok(TextureData::U8(faces_data))
 }
 
 fn convert_to_f32(
     data: &[u8],
     face_size: wgpu::BufferAddress,
+    layer_count: u32,
     width: u32,
     height: u32,
-) -> Result<CubeTextureData, TextureReadError> {
-    let mut faces_data = Vec::with_capacity(6);
+) -> Result<TextureData, TextureReadError> {
+    let mut faces_data = Vec::with_capacity(layer_count as usize);
     let pixels_per_face = (width * height) as usize;
     let floats_per_face = pixels_per_face * 4; // RGBA
 
-    for face in 0..6 {
+    for face in 0..layer_count {
         let start = (face_size * face as wgpu::BufferAddress) as usize;
         let end = start + face_size as usize;
 
@@ -187,21 +262,22 @@ fn convert_to_f32(
         faces_data.push(face_floats);
     }
 
-    Ok(CubeTextureData::F32(faces_data))
+    Ok(TextureData::F32(faces_data))
 }
 
 fn convert_to_f16(
     data: &[u8],
     face_size: wgpu::BufferAddress,
+    layer_count: u32,
     width: u32,
     height: u32,
-) -> Result<CubeTextureData, TextureReadError> {
+) -> Result<TextureData, TextureReadError> {
     // convert f16 data to f32
-    let mut faces_data = Vec::with_capacity(6);
+    let mut faces_data = Vec::with_capacity(layer_count as usize);
     let pixels_per_face = (width * height) as usize;
     let floats_per_face = pixels_per_face * 4; // RGBA
 
-    for face in 0..6 {
+    for face in 0..layer_count {
         let start = (face_size * face as wgpu::BufferAddress) as usize;
         let end = start + face_size as usize;
 
@@ -225,20 +301,21 @@ fn convert_to_f16(
         faces_data.push(face_floats);
     }
 
-    Ok(CubeTextureData::F32(faces_data))
+    Ok(TextureData::F32(faces_data))
 }
 
 fn convert_to_u32(
     data: &[u8],
     face_size: wgpu::BufferAddress,
+    layer_count: u32,
     width: u32,
     height: u32,
-) -> Result<CubeTextureData, TextureReadError> {
-    let mut faces_data = Vec::with_capacity(6);
+) -> Result<TextureData, TextureReadError> {
+    let mut faces_data = Vec::with_capacity(layer_count as usize);
     let pixels_per_face = (width * height) as usize;
     let values_per_face = pixels_per_face * 4; // RGBA
 
-    for face in 0..6 {
+    for face in 0..layer_count {
         let start = (face_size * face as wgpu::BufferAddress) as usize;
         let end = start + face_size as usize;
 
@@ -257,7 +334,7 @@ fn convert_to_u32(
         faces_data.push(face_u32s);
     }
 
-    Ok(CubeTextureData::U16(
+    Ok(TextureData::U16(
         faces_data
             .into_iter()
             .map(|vec| vec.into_iter().map(|v| v as u16).collect())
@@ -268,14 +345,15 @@ fn convert_to_u32(
 fn convert_to_i32(
     data: &[u8],
     face_size: wgpu::BufferAddress,
+    layer_count: u32,
     width: u32,
     height: u32,
-) -> Result<CubeTextureData, TextureReadError> {
-    let mut faces_data = Vec::with_capacity(6);
+) -> Result<TextureData, TextureReadError> {
+    let mut faces_data = Vec::with_capacity(layer_count as usize);
     let pixels_per_face = (width * height) as usize;
     let values_per_face = pixels_per_face * 4; // RGBA
 
-    for face in 0..6 {
+    for face in 0..layer_count {
         let start = (face_size * face as wgpu::BufferAddress) as usize;
         let end = start + face_size as usize;
 
@@ -294,7 +372,7 @@ fn convert_to_i32(
         faces_data.push(face_i32s);
     }
 
-    Ok(CubeTextureData::I32(faces_data))
+    Ok(TextureData::I32(faces_data))
 }
 
 fn half_to_float_simple(half: u16) -> f32 {
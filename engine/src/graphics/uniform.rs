@@ -41,6 +41,7 @@ pub(crate) struct GlobalUniformSyncFlags {
     pub(crate) has_time_synced: bool,
     pub(crate) has_reflection_maps_synced: bool,
     pub(crate) has_lights_synced: bool,
+    pub(crate) has_shadows_synced: bool,
 }
 
 impl GlobalUniformSyncFlags {
@@ -49,6 +50,7 @@ impl GlobalUniformSyncFlags {
             has_time_synced: false,
             has_reflection_maps_synced: false,
             has_lights_synced: false,
+            has_shadows_synced: false,
         }
     }
 }
@@ -206,6 +208,23 @@ impl UniformValue {
         }
     }
 
+    /// Forget the GPU buffer, so that a new buffer is allocated when the value is written.
+    pub(crate) fn reset_buffer_view(&mut self) {
+        match self {
+            UniformValue::Float(_, buffer_view)
+            | UniformValue::Vec2(_, buffer_view)
+            | UniformValue::Vec3(_, buffer_view)
+            | UniformValue::Vec4(_, buffer_view)
+            | UniformValue::IVec4(_, buffer_view)
+            | UniformValue::UVec4(_, buffer_view)
+            | UniformValue::Mat3(_, buffer_view)
+            | UniformValue::Mat4(_, buffer_view)
+            | UniformValue::Struct(_, buffer_view)
+            | UniformValue::Storage(_, buffer_view) => *buffer_view = None,
+            UniformValue::Texture(_) | UniformValue::Sampler(_) => {}
+        }
+    }
+
     pub fn size(&self) -> u64 {
         match self {
             UniformValue::Float(_value, _) => 4,
@@ -274,6 +293,18 @@ impl Uniforms {
         self.bind_group_index != u32::MAX
     }
 
+    /// Clone the uniform values without the GPU buffers and bind group, which are created again when used.
+    pub(crate) fn clone_without_gpu_resources(&self) -> Self {
+        let mut uniforms = self.clone();
+        for uniform in uniforms.uniforms.values_mut() {
+            uniform.value.reset_buffer_view();
+            uniform.is_dirty = true;
+        }
+        uniforms.bind_group_id = INVALID_BINDGROUP_ID;
+        uniforms.is_dirty = true;
+        uniforms
+    }
+
     /// Whether need to create bind group for this uniforms struct.
     pub(crate) fn should_create_bind_group(&self) -> bool {
         self.uniforms.len() > 0 && self.bind_group_id == INVALID_BINDGROUP_ID
@@ -822,6 +853,14 @@ impl BuiltinUniforms {
         }
     }
 
+    /// Remove all cached bind groups, so that they will be recreated with the latest textures next time.
+    pub(crate) fn clear_bind_groups(&mut self, graphics_context: &mut GraphicsContext) {
+        for bind_group_id in self.bind_groups.values() {
+            graphics_context.remove_bind_group(bind_group_id);
+        }
+        self.bind_groups.clear();
+    }
+
     pub(crate) fn get_bind_group(&mut self, graphics_context: &mut GraphicsContext,
         texture_sampler_manager: &TextureSamplerManager, shader_properties: &ShaderPropertyPacket) -> BindGroupID {
         if !shader_properties.is_valid() {
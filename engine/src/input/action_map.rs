@@ -0,0 +1,197 @@
+use std::{collections::BTreeMap, path::Path};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use winit::keyboard::KeyCode;
+
+use crate::input::gamepad::{GamepadAxis, GamepadButton};
+
+#[derive(Error, Debug)]
+pub enum ActionMapError {
+    #[error("Failed to read or write the bindings file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to parse the bindings: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[error("Failed to serialize the bindings: {0}")]
+    Serialize(#[from] ron::Error),
+}
+
+/// Mouse buttons which can be bound to actions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MouseButtonCode {
+    Left,
+    Right,
+    Middle,
+}
+
+/// Mouse axes which can be bound to axes. Their values are the deltas of current frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MouseAxis {
+    /// The horizontal movement of cursor in logical pixels, right is positive.
+    X,
+    /// The vertical movement of cursor in logical pixels, down is positive.
+    Y,
+    /// The horizontal scroll in lines.
+    ScrollX,
+    /// The vertical scroll in lines.
+    ScrollY,
+}
+
+/// A binding which triggers an action, e.g., `Key(KeyCode::Space)` for "jump".
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ButtonBinding {
+    Key(KeyCode),
+    Mouse(MouseButtonCode),
+    /// The button of any connected gamepad.
+    Gamepad(GamepadButton),
+}
+
+/// A binding which drives an axis, e.g., `Keys { negative: KeyCode::KeyA, positive: KeyCode::KeyD }` for "move_x".
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum AxisBinding {
+    /// -1.0 when `negative` is held down, 1.0 when `positive` is held down and 0.0 when both or none are held down.
+    Keys { negative: KeyCode, positive: KeyCode },
+    /// The same as `Keys` but with gamepad buttons.
+    GamepadButtons { negative: GamepadButton, positive: GamepadButton },
+    /// The mouse axis multiplied by `scale`.
+    Mouse { axis: MouseAxis, scale: f32 },
+    /// The gamepad axis, values whose magnitude is less than `dead_zone` are treated as 0.0.
+    Gamepad { axis: GamepadAxis, dead_zone: f32 },
+}
+
+/// Named actions and axes with their bindings.
+///
+/// An action is triggered if any of its bindings is pressed, and the value of an axis is the value with the largest
+/// magnitude among its bindings. Query them by [`crate::input::InputManager::action`] and
+/// [`crate::input::InputManager::axis`].
+///
+/// The bindings can be saved to and loaded from RON files, so that players can rebind them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ActionMap {
+    #[serde(default)]
+    actions: BTreeMap<String, Vec<ButtonBinding>>,
+    #[serde(default)]
+    axes: BTreeMap<String, Vec<AxisBinding>>,
+}
+
+impl ActionMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a binding to the action. The action is created if it does not exist.
+    pub fn bind_action(&mut self, action: &str, binding: ButtonBinding) -> &mut Self {
+        let bindings = self.actions.entry(action.to_owned()).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+        self
+    }
+
+    /// Replace all the bindings of the action, e.g., when the player rebinds it.
+    pub fn rebind_action(&mut self, action: &str, bindings: Vec<ButtonBinding>) -> &mut Self {
+        self.actions.insert(action.to_owned(), bindings);
+        self
+    }
+
+    /// Remove a binding from the action. The action itself is kept even if it has no binding.
+    pub fn unbind_action(&mut self, action: &str, binding: ButtonBinding) -> &mut Self {
+        if let Some(bindings) = self.actions.get_mut(action) {
+            bindings.retain(|cur_binding| *cur_binding != binding);
+        }
+        self
+    }
+
+    /// Remove the action and all its bindings.
+    pub fn remove_action(&mut self, action: &str) -> &mut Self {
+        self.actions.remove(action);
+        self
+    }
+
+    /// Add a binding to the axis. The axis is created if it does not exist.
+    pub fn bind_axis(&mut self, axis: &str, binding: AxisBinding) -> &mut Self {
+        let bindings = self.axes.entry(axis.to_owned()).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+        self
+    }
+
+    /// Replace all the bindings of the axis.
+    pub fn rebind_axis(&mut self, axis: &str, bindings: Vec<AxisBinding>) -> &mut Self {
+        self.axes.insert(axis.to_owned(), bindings);
+        self
+    }
+
+    /// Remove a binding from the axis.
+    pub fn unbind_axis(&mut self, axis: &str, binding: AxisBinding) -> &mut Self {
+        if let Some(bindings) = self.axes.get_mut(axis) {
+            bindings.retain(|cur_binding| *cur_binding != binding);
+        }
+        self
+    }
+
+    /// Remove the axis and all its bindings.
+    pub fn remove_axis(&mut self, axis: &str) -> &mut Self {
+        self.axes.remove(axis);
+        self
+    }
+
+    /// The bindings of the action, or None if the action does not exist.
+    pub fn action_bindings(&self, action: &str) -> Option<&[ButtonBinding]> {
+        self.actions.get(action).map(|bindings| bindings.as_slice())
+    }
+
+    /// The bindings of the axis, or None if the axis does not exist.
+    pub fn axis_bindings(&self, axis: &str) -> Option<&[AxisBinding]> {
+        self.axes.get(axis).map(|bindings| bindings.as_slice())
+    }
+
+    /// The names of all actions and their bindings.
+    pub fn actions(&self) -> impl Iterator<Item = (&String, &Vec<ButtonBinding>)> {
+        self.actions.iter()
+    }
+
+    /// The names of all axes and their bindings.
+    pub fn axes(&self) -> impl Iterator<Item = (&String, &Vec<AxisBinding>)> {
+        self.axes.iter()
+    }
+
+    /// Parse bindings from a RON string.
+    pub fn from_ron_str(ron_str: &str) -> Result<Self, ActionMapError> {
+        Ok(ron::from_str(ron_str)?)
+    }
+
+    /// Serialize the bindings to a pretty RON string.
+    pub fn to_ron_string(&self) -> Result<String, ActionMapError> {
+        Ok(ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?)
+    }
+
+    /// Load bindings from a RON file.
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self, ActionMapError> {
+        let ron_str = std::fs::read_to_string(path)?;
+        Self::from_ron_str(&ron_str)
+    }
+
+    /// Save the bindings to a RON file.
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), ActionMapError> {
+        std::fs::write(path, self.to_ron_string()?)?;
+        Ok(())
+    }
+}
+
+/// The state of an action in current frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ActionState {
+    pub pressed: bool,
+    pub just_pressed: bool,
+    pub just_released: bool,
+}
+
+impl ActionState {
+    pub(crate) fn update(&mut self, pressed: bool) {
+        self.just_pressed = pressed && !self.pressed;
+        self.just_released = !pressed && self.pressed;
+        self.pressed = pressed;
+    }
+}
@@ -0,0 +1,153 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+/// The id of a connected gamepad, assigned by the gamepad backend.
+pub type GamepadId = u32;
+
+/// Gamepad buttons, named after the layout of Xbox controllers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GamepadButton {
+    South,
+    East,
+    North,
+    West,
+    LeftBumper,
+    RightBumper,
+    LeftTrigger,
+    RightTrigger,
+    Select,
+    Start,
+    Mode,
+    LeftStick,
+    RightStick,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+/// Gamepad axes. Stick values are in [-1, 1], where up and right are positive. Trigger values are in [0, 1].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GamepadAxis {
+    LeftStickX,
+    LeftStickY,
+    RightStickX,
+    RightStickY,
+    LeftTrigger,
+    RightTrigger,
+}
+
+/// The events reported by a gamepad backend.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum GamepadEvent {
+    Connected(GamepadId),
+    Disconnected(GamepadId),
+    ButtonPressed(GamepadId, GamepadButton),
+    ButtonReleased(GamepadId, GamepadButton),
+    AxisChanged(GamepadId, GamepadAxis, f32),
+}
+
+/// The source of gamepad inputs, e.g., a wrapper of `gilrs` or a scripted fake backend for tests.
+///
+/// Imagic does not depend on any gamepad library, set a backend by [`crate::input::InputManager::set_gamepad_backend`].
+pub trait GamepadBackend {
+    /// Return the events happened since last poll. It is called once at the beginning of each frame.
+    fn poll_events(&mut self) -> Vec<GamepadEvent>;
+}
+
+/// A gamepad backend which replays scripted events, one batch per frame. It is useful to test input logic.
+#[derive(Default)]
+pub struct ScriptedGamepadBackend {
+    frames: VecDeque<Vec<GamepadEvent>>,
+}
+
+impl ScriptedGamepadBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append the events of one frame. Pass an empty Vec for frames without any event.
+    pub fn push_frame(&mut self, events: Vec<GamepadEvent>) -> &mut Self {
+        self.frames.push_back(events);
+        self
+    }
+
+    /// The number of frames which have not been replayed.
+    pub fn remaining_frames(&self) -> usize {
+        self.frames.len()
+    }
+}
+
+impl GamepadBackend for ScriptedGamepadBackend {
+    fn poll_events(&mut self) -> Vec<GamepadEvent> {
+        self.frames.pop_front().unwrap_or_default()
+    }
+}
+
+/// The state of all connected gamepads.
+#[derive(Default)]
+pub struct GamepadData {
+    connected_gamepads: HashSet<GamepadId>,
+    pressed_buttons: HashSet<(GamepadId, GamepadButton)>,
+    axes: HashMap<(GamepadId, GamepadAxis), f32>,
+}
+
+impl GamepadData {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The ids of connected gamepads.
+    pub fn connected_gamepads(&self) -> impl Iterator<Item = &GamepadId> {
+        self.connected_gamepads.iter()
+    }
+
+    /// Whether the button of the given gamepad is being held down.
+    pub fn is_button_pressed(&self, gamepad: GamepadId, button: GamepadButton) -> bool {
+        self.pressed_buttons.contains(&(gamepad, button))
+    }
+
+    /// Whether the button of any gamepad is being held down.
+    pub fn is_button_pressed_on_any(&self, button: GamepadButton) -> bool {
+        self.pressed_buttons.iter().any(|(_, pressed_button)| *pressed_button == button)
+    }
+
+    /// The value of the axis of the given gamepad, 0.0 if it has never been reported.
+    pub fn axis(&self, gamepad: GamepadId, axis: GamepadAxis) -> f32 {
+        self.axes.get(&(gamepad, axis)).copied().unwrap_or(0.0)
+    }
+
+    /// The value of the axis with the largest magnitude among all gamepads.
+    pub fn axis_on_any(&self, axis: GamepadAxis) -> f32 {
+        self.axes
+            .iter()
+            .filter(|((_, cur_axis), _)| *cur_axis == axis)
+            .map(|(_, value)| *value)
+            .fold(0.0, |max, value| if value.abs() > max.abs() { value } else { max })
+    }
+
+    pub(crate) fn on_event(&mut self, event: GamepadEvent) {
+        match event {
+            GamepadEvent::Connected(gamepad) => {
+                self.connected_gamepads.insert(gamepad);
+            }
+            GamepadEvent::Disconnected(gamepad) => {
+                self.connected_gamepads.remove(&gamepad);
+                self.pressed_buttons.retain(|(cur_gamepad, _)| *cur_gamepad != gamepad);
+                self.axes.retain(|(cur_gamepad, _), _| *cur_gamepad != gamepad);
+            }
+            GamepadEvent::ButtonPressed(gamepad, button) => {
+                self.connected_gamepads.insert(gamepad);
+                self.pressed_buttons.insert((gamepad, button));
+            }
+            GamepadEvent::ButtonReleased(gamepad, button) => {
+                self.pressed_buttons.remove(&(gamepad, button));
+            }
+            GamepadEvent::AxisChanged(gamepad, axis, value) => {
+                self.connected_gamepads.insert(gamepad);
+                self.axes.insert((gamepad, axis), value);
+            }
+        }
+    }
+}
@@ -1,4 +1,19 @@
-use crate::math::Vec2;
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
+
+use winit::keyboard::{KeyCode, ModifiersState};
+
+use crate::{
+    input::{
+        action_map::{ActionMap, ActionState, AxisBinding, ButtonBinding, MouseAxis, MouseButtonCode},
+        gamepad::{GamepadBackend, GamepadData},
+        input_recorder::{InputEvent, InputRecorder, InputRecording, InputReplayer},
+    },
+    math::Vec2,
+    time::Time,
+};
 
 pub struct MouseData {
     // pub last_mouse_pos: Vec2,
@@ -7,6 +22,10 @@ pub struct MouseData {
     pub is_left_button_down: bool,
     pub is_right_button_down: bool,
     pub is_middle_button_down: bool,
+    /// The cursor movement in logical pixels in current frame.
+    pub delta: Vec2,
+    /// The scroll in lines in current frame.
+    pub scroll_delta: Vec2,
 }
 
 impl Default for MouseData {
@@ -18,6 +37,8 @@ impl Default for MouseData {
             is_left_button_down: false,
             is_right_button_down: false,
             is_middle_button_down: false,
+            delta: Vec2::ZERO,
+            scroll_delta: Vec2::ZERO,
         }
     }
 }
@@ -28,15 +49,80 @@ impl MouseData {
     }
 }
 
+/// The keyboard state of current frame.
+#[derive(Default)]
+pub struct KeyboardData {
+    pressed_keys: HashSet<KeyCode>,
+    just_pressed_keys: HashSet<KeyCode>,
+    just_released_keys: HashSet<KeyCode>,
+    modifiers: ModifiersState,
+    text_input: String,
+}
+
+impl KeyboardData {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the key is being held down.
+    pub fn is_key_pressed(&self, key_code: KeyCode) -> bool {
+        self.pressed_keys.contains(&key_code)
+    }
+
+    /// Whether the key is pressed down in current frame. Key repeats are not counted.
+    pub fn is_key_just_pressed(&self, key_code: KeyCode) -> bool {
+        self.just_pressed_keys.contains(&key_code)
+    }
+
+    /// Whether the key is released in current frame.
+    pub fn is_key_just_released(&self, key_code: KeyCode) -> bool {
+        self.just_released_keys.contains(&key_code)
+    }
+
+    /// All the keys being held down.
+    pub fn pressed_keys(&self) -> impl Iterator<Item = &KeyCode> {
+        self.pressed_keys.iter()
+    }
+
+    /// The state of modifier keys, e.g., Shift, Control, Alt and Super.
+    pub fn modifiers(&self) -> ModifiersState {
+        self.modifiers
+    }
+
+    /// The characters typed in current frame, which respect keyboard layout, Shift and key repeats.
+    pub fn text_input(&self) -> &str {
+        &self.text_input
+    }
+}
+
 pub struct InputManager {
    pub mouse_data: MouseData,
-
+   pub keyboard_data: KeyboardData,
+   pub gamepad_data: GamepadData,
+   /// The bindings of named actions and axes.
+   pub action_map: ActionMap,
+   action_states: HashMap<String, ActionState>,
+   gamepad_backend: Option<Box<dyn GamepadBackend>>,
+   has_cursor_pos: bool,
+   recorder: Option<InputRecorder>,
+   replayer: Option<InputReplayer>,
+   /// The frame delta override of Time to restore after a replay is stopped manually.
+   frame_delta_override_to_restore: Option<Option<f32>>,
 }
 
 impl Default for InputManager {
     fn default() -> Self {
         Self {
             mouse_data: MouseData::default(),
+            keyboard_data: KeyboardData::default(),
+            gamepad_data: GamepadData::default(),
+            action_map: ActionMap::default(),
+            action_states: HashMap::new(),
+            gamepad_backend: None,
+            has_cursor_pos: false,
+            recorder: None,
+            replayer: None,
+            frame_delta_override_to_restore: None,
         }
     }
 }
@@ -46,11 +132,101 @@ impl InputManager {
         Self::default()
     }
 
+    /// Apply an input event from the window, and record it if recording.
+    ///
+    /// Events are ignored while replaying, so that the replayed session is not disturbed by the user.
+    pub fn handle_event(&mut self, event: InputEvent) {
+        if self.replayer.is_some() {
+            return;
+        }
+        if let Some(recorder) = &mut self.recorder {
+            recorder.record_event(&event);
+        }
+        self.apply_event(event);
+    }
+
+    fn apply_event(&mut self, event: InputEvent) {
+        let cursor_pos = self.mouse_data.cur_mouse_pos;
+        match event {
+            InputEvent::MouseMoved { x, y } => self.on_mouse_move(&Vec2::new(x, y)),
+            InputEvent::MouseButtonPressed(MouseButtonCode::Left) => self.on_mouse_left_button_down(&cursor_pos),
+            InputEvent::MouseButtonPressed(MouseButtonCode::Right) => self.on_mouse_right_button_down(&cursor_pos),
+            InputEvent::MouseButtonPressed(MouseButtonCode::Middle) => self.on_mouse_middle_button_down(&cursor_pos),
+            InputEvent::MouseButtonReleased(MouseButtonCode::Left) => self.on_mouse_left_button_up(&cursor_pos),
+            InputEvent::MouseButtonReleased(MouseButtonCode::Right) => self.on_mouse_right_button_up(&cursor_pos),
+            InputEvent::MouseButtonReleased(MouseButtonCode::Middle) => self.on_mouse_middle_button_up(&cursor_pos),
+            InputEvent::MouseScrolled { x, y } => self.on_mouse_scroll(&Vec2::new(x, y)),
+            InputEvent::KeyPressed(key_code) => self.on_key_pressed(key_code),
+            InputEvent::KeyReleased(key_code) => self.on_key_released(key_code),
+            InputEvent::ModifiersChanged(modifiers) => self.on_modifiers_changed(modifiers),
+            InputEvent::TextInput(text) => self.on_text_input(&text),
+            InputEvent::FocusLost => self.on_focus_lost(),
+            InputEvent::Gamepad(gamepad_event) => self.gamepad_data.on_event(gamepad_event),
+        }
+    }
+
+    /// Start recording input events and frame delta times. The old recording, if any, is discarded.
+    pub fn start_recording(&mut self) {
+        self.recorder = Some(InputRecorder::new(None));
+    }
+
+    /// Start recording, the recording is saved to the file when recording stops or the engine stops.
+    pub fn start_recording_to_file<P: Into<PathBuf>>(&mut self, path: P) {
+        self.recorder = Some(InputRecorder::new(Some(path.into())));
+    }
+
+    /// Stop recording and return the recording. It is also saved if it is started by [`InputManager::start_recording_to_file`].
+    pub fn stop_recording(&mut self) -> Option<InputRecording> {
+        let recorder = self.recorder.take()?;
+        if let Some(save_path) = &recorder.save_path {
+            match recorder.recording.save_to_file(save_path) {
+                Ok(_) => log::info!("Input recording saved to {}", save_path.display()),
+                Err(err) => log::error!("Failed to save input recording to {}: {}", save_path.display(), err),
+            }
+        }
+        Some(recorder.recording)
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+
+    /// Replay a recording from next frame. The input states are reset, inputs from the window and the gamepad backend
+    /// are ignored, and Time advances by the recorded delta times until the replay finishes.
+    pub fn start_replay(&mut self, recording: InputRecording) {
+        self.mouse_data = MouseData::default();
+        self.keyboard_data = KeyboardData::default();
+        self.gamepad_data = GamepadData::default();
+        self.action_states.clear();
+        self.has_cursor_pos = false;
+        self.recorder = None;
+        self.replayer = Some(InputReplayer::new(recording));
+    }
+
+    /// Stop replaying before the recording ends.
+    pub fn stop_replay(&mut self) {
+        if let Some(replayer) = self.replayer.take() {
+            self.frame_delta_override_to_restore = replayer.previous_frame_delta_override;
+        }
+    }
+
+    pub fn is_replaying(&self) -> bool {
+        self.replayer.is_some()
+    }
+
     pub fn on_mouse_move(&mut self, new_pos: &Vec2) {
         // self.mouse_data.last_mouse_pos = self.mouse_data.cur_mouse_pos;
+        if self.has_cursor_pos {
+            self.mouse_data.delta += *new_pos - self.mouse_data.cur_mouse_pos;
+        }
+        self.has_cursor_pos = true;
         self.set_cur_mouse_pos(new_pos);
     }
 
+    pub fn on_mouse_scroll(&mut self, delta: &Vec2) {
+        self.mouse_data.scroll_delta += *delta;
+    }
+
     pub fn on_mouse_left_button_down(&mut self, new_pos: &Vec2) {
         self.mouse_data.is_left_button_down = true;
         self.set_cur_mouse_pos(new_pos);
@@ -84,6 +260,182 @@ impl InputManager {
         self.set_cur_mouse_pos(new_pos);
     }
 
+    /// Whether the key is being held down.
+    pub fn is_key_pressed(&self, key_code: KeyCode) -> bool {
+        self.keyboard_data.is_key_pressed(key_code)
+    }
+
+    /// Whether the key is pressed down in current frame.
+    pub fn is_key_just_pressed(&self, key_code: KeyCode) -> bool {
+        self.keyboard_data.is_key_just_pressed(key_code)
+    }
+
+    /// Whether the key is released in current frame.
+    pub fn is_key_just_released(&self, key_code: KeyCode) -> bool {
+        self.keyboard_data.is_key_just_released(key_code)
+    }
+
+    pub fn on_key_pressed(&mut self, key_code: KeyCode) {
+        if self.keyboard_data.pressed_keys.insert(key_code) {
+            self.keyboard_data.just_pressed_keys.insert(key_code);
+        }
+    }
+
+    pub fn on_key_released(&mut self, key_code: KeyCode) {
+        if self.keyboard_data.pressed_keys.remove(&key_code) {
+            self.keyboard_data.just_released_keys.insert(key_code);
+        }
+    }
+
+    pub fn on_modifiers_changed(&mut self, modifiers: ModifiersState) {
+        self.keyboard_data.modifiers = modifiers;
+    }
+
+    pub fn on_text_input(&mut self, text: &str) {
+        self.keyboard_data.text_input.push_str(text);
+    }
+
+    /// Release all keys, e.g., when the window loses focus and key up events will never arrive.
+    pub fn on_focus_lost(&mut self) {
+        let keyboard_data = &mut self.keyboard_data;
+        keyboard_data.just_released_keys.extend(keyboard_data.pressed_keys.drain());
+        keyboard_data.modifiers = ModifiersState::empty();
+    }
+
+    /// Set the source of gamepad inputs, which replaces the old one.
+    pub fn set_gamepad_backend<T: GamepadBackend + 'static>(&mut self, gamepad_backend: T) {
+        self.gamepad_backend = Some(Box::new(gamepad_backend));
+    }
+
+    /// Remove the gamepad backend and return it.
+    pub fn take_gamepad_backend(&mut self) -> Option<Box<dyn GamepadBackend>> {
+        self.gamepad_backend.take()
+    }
+
+    /// Whether the action is triggered, i.e., any of its bindings is held down.
+    pub fn action(&self, action: &str) -> bool {
+        self.action_state(action).pressed
+    }
+
+    /// Whether the action starts to be triggered in current frame.
+    pub fn action_just_pressed(&self, action: &str) -> bool {
+        self.action_state(action).just_pressed
+    }
+
+    /// Whether the action stops being triggered in current frame.
+    pub fn action_just_released(&self, action: &str) -> bool {
+        self.action_state(action).just_released
+    }
+
+    /// The state of the action in current frame. Unknown actions are never triggered.
+    pub fn action_state(&self, action: &str) -> ActionState {
+        self.action_states.get(action).copied().unwrap_or_default()
+    }
+
+    /// The value of the axis in current frame, i.e., the value with the largest magnitude among its bindings.
+    /// Unknown axes are always 0.0.
+    pub fn axis(&self, axis: &str) -> f32 {
+        self.action_map.axis_bindings(axis).map_or(0.0, |bindings| {
+            bindings
+                .iter()
+                .map(|binding| self.evaluate_axis_binding(binding))
+                .fold(0.0, |max, value| if value.abs() > max.abs() { value } else { max })
+        })
+    }
+
+    /// Set the recorded delta time of the frame to be replayed to Time. It is called before Time is updated.
+    pub(crate) fn on_time_update(&mut self, time: &mut Time) {
+        if let Some(replayer) = &mut self.replayer {
+            let previous_frame_delta_override = *replayer.previous_frame_delta_override.get_or_insert(time.frame_delta_override());
+            if let Some(delta_time) = replayer.next_delta_time() {
+                time.set_frame_delta_override(Some(delta_time));
+            } else {
+                time.set_frame_delta_override(previous_frame_delta_override);
+                self.replayer = None;
+                log::info!("Input replay finished.");
+            }
+        } else if let Some(frame_delta_override) = self.frame_delta_override_to_restore.take() {
+            time.set_frame_delta_override(frame_delta_override);
+        }
+    }
+
+    /// Apply the replayed or polled events and update action states. It is called at the beginning of each frame.
+    ///
+    /// Call it with [`InputManager::on_frame_end`] to drive an input manager without engine, e.g., in tests.
+    pub fn on_frame_begin(&mut self, delta_time: f32) {
+        if let Some(replayer) = &mut self.replayer {
+            for event in replayer.next_events() {
+                self.apply_event(event);
+            }
+        } else {
+            let gamepad_events = match &mut self.gamepad_backend {
+                Some(gamepad_backend) => gamepad_backend.poll_events(),
+                None => Vec::new(),
+            };
+            for event in gamepad_events {
+                self.handle_event(InputEvent::Gamepad(event));
+            }
+            if let Some(recorder) = &mut self.recorder {
+                recorder.record_frame(delta_time);
+            }
+        }
+
+        self.action_states.retain(|action, _| self.action_map.action_bindings(action).is_some());
+        for (action, bindings) in self.action_map.actions() {
+            let pressed = bindings.iter().any(|binding| self.is_button_binding_pressed(binding));
+            self.action_states.entry(action.clone()).or_default().update(pressed);
+        }
+    }
+
+    /// Clear the states which only last for one frame. It is called at the end of each frame.
+    pub fn on_frame_end(&mut self) {
+        self.keyboard_data.just_pressed_keys.clear();
+        self.keyboard_data.just_released_keys.clear();
+        self.keyboard_data.text_input.clear();
+        self.mouse_data.delta = Vec2::ZERO;
+        self.mouse_data.scroll_delta = Vec2::ZERO;
+    }
+
+    fn is_button_binding_pressed(&self, binding: &ButtonBinding) -> bool {
+        match binding {
+            // A key tapped within one frame still triggers the action for that frame.
+            ButtonBinding::Key(key_code) => {
+                self.keyboard_data.is_key_pressed(*key_code) || self.keyboard_data.is_key_just_pressed(*key_code)
+            }
+            ButtonBinding::Mouse(MouseButtonCode::Left) => self.mouse_data.is_left_button_down,
+            ButtonBinding::Mouse(MouseButtonCode::Right) => self.mouse_data.is_right_button_down,
+            ButtonBinding::Mouse(MouseButtonCode::Middle) => self.mouse_data.is_middle_button_down,
+            ButtonBinding::Gamepad(button) => self.gamepad_data.is_button_pressed_on_any(*button),
+        }
+    }
+
+    fn evaluate_axis_binding(&self, binding: &AxisBinding) -> f32 {
+        let direction = |negative: bool, positive: bool| (positive as i32 - negative as i32) as f32;
+        match binding {
+            AxisBinding::Keys { negative, positive } => direction(
+                self.keyboard_data.is_key_pressed(*negative),
+                self.keyboard_data.is_key_pressed(*positive),
+            ),
+            AxisBinding::GamepadButtons { negative, positive } => direction(
+                self.gamepad_data.is_button_pressed_on_any(*negative),
+                self.gamepad_data.is_button_pressed_on_any(*positive),
+            ),
+            AxisBinding::Mouse { axis, scale } => {
+                let value = match axis {
+                    MouseAxis::X => self.mouse_data.delta.x,
+                    MouseAxis::Y => self.mouse_data.delta.y,
+                    MouseAxis::ScrollX => self.mouse_data.scroll_delta.x,
+                    MouseAxis::ScrollY => self.mouse_data.scroll_delta.y,
+                };
+                value * scale
+            }
+            AxisBinding::Gamepad { axis, dead_zone } => {
+                let value = self.gamepad_data.axis_on_any(*axis);
+                if value.abs() < *dead_zone { 0.0 } else { value }
+            }
+        }
+    }
+
     fn set_cur_mouse_pos(&mut self, new_pos: &Vec2) {
         self.mouse_data.cur_mouse_pos.x = new_pos.x;
         self.mouse_data.cur_mouse_pos.y = new_pos.y;
@@ -93,4 +445,143 @@ impl InputManager {
         self.mouse_data.last_mouse_pos.x = new_pos.x;
         self.mouse_data.last_mouse_pos.y = new_pos.y;
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::gamepad::{GamepadAxis, GamepadButton, GamepadEvent, ScriptedGamepadBackend};
+    #[test]
+    fn test_key_states() {
+        let mut input_manager = InputManager::new();
+        input_manager.on_key_pressed(KeyCode::KeyW);
+        // key repeats are not just pressed again.
+        input_manager.on_key_pressed(KeyCode::KeyW);
+        assert!(input_manager.is_key_pressed(KeyCode::KeyW));
+        assert!(input_manager.is_key_just_pressed(KeyCode::KeyW));
+
+        input_manager.on_frame_end();
+        assert!(input_manager.is_key_pressed(KeyCode::KeyW));
+        assert!(!input_manager.is_key_just_pressed(KeyCode::KeyW));
+
+        input_manager.on_key_released(KeyCode::KeyW);
+        assert!(!input_manager.is_key_pressed(KeyCode::KeyW));
+        assert!(input_manager.is_key_just_released(KeyCode::KeyW));
+
+        input_manager.on_frame_end();
+        assert!(!input_manager.is_key_just_released(KeyCode::KeyW));
+    }
+
+    #[test]
+    fn test_actions_and_axes() {
+        let mut input_manager = InputManager::new();
+        input_manager
+            .action_map
+            .bind_action("jump", ButtonBinding::Key(KeyCode::Space))
+            .bind_action("jump", ButtonBinding::Gamepad(GamepadButton::South))
+            .bind_axis("move_x", AxisBinding::Keys { negative: KeyCode::KeyA, positive: KeyCode::KeyD })
+            .bind_axis("move_x", AxisBinding::Gamepad { axis: GamepadAxis::LeftStickX, dead_zone: 0.2 });
+        let mut gamepad_backend = ScriptedGamepadBackend::new();
+        gamepad_backend
+            .push_frame(vec![GamepadEvent::ButtonPressed(0, GamepadButton::South)])
+            .push_frame(vec![GamepadEvent::AxisChanged(0, GamepadAxis::LeftStickX, 0.1)])
+            .push_frame(vec![
+                GamepadEvent::ButtonReleased(0, GamepadButton::South),
+                GamepadEvent::AxisChanged(0, GamepadAxis::LeftStickX, -0.5),
+            ]);
+        input_manager.set_gamepad_backend(gamepad_backend);
+
+        input_manager.on_frame_begin(0.016);
+        assert!(input_manager.action("jump"));
+        assert!(input_manager.action_just_pressed("jump"));
+        input_manager.on_frame_end();
+
+        input_manager.on_frame_begin(0.016);
+        input_manager.on_key_pressed(KeyCode::KeyD);
+        assert!(input_manager.action("jump"));
+        assert!(!input_manager.action_just_pressed("jump"));
+        // the stick is in the dead zone, so the key wins.
+        assert_eq!(input_manager.axis("move_x"), 1.0);
+        input_manager.on_frame_end();
+
+        input_manager.on_key_released(KeyCode::KeyD);
+        input_manager.on_frame_begin(0.016);
+        assert!(!input_manager.action("jump"));
+        assert!(input_manager.action_just_released("jump"));
+        assert_eq!(input_manager.axis("move_x"), -0.5);
+        assert!(!input_manager.action("unknown"));
+        assert_eq!(input_manager.axis("unknown"), 0.0);
+    }
+
+    #[test]
+    fn test_record_and_replay() {
+        let frames: Vec<(f32, Vec<InputEvent>)> = vec![
+            (0.016, vec![InputEvent::MouseMoved { x: 10.0, y: 10.0 }]),
+            (0.020, vec![InputEvent::MouseButtonPressed(MouseButtonCode::Left), InputEvent::KeyPressed(KeyCode::KeyW)]),
+            (0.017, vec![InputEvent::MouseMoved { x: 15.0, y: 12.0 }, InputEvent::KeyReleased(KeyCode::KeyW)]),
+        ];
+        let mut input_manager = InputManager::new();
+        input_manager.start_recording();
+        for (delta_time, events) in &frames {
+            for event in events {
+                input_manager.handle_event(event.clone());
+            }
+            input_manager.on_frame_begin(*delta_time);
+            input_manager.on_frame_end();
+        }
+        let recording = input_manager.stop_recording().unwrap();
+        let recording = InputRecording::from_ron_str(&recording.to_ron_string().unwrap()).unwrap();
+        assert_eq!(recording.frames.len(), frames.len());
+
+        let mut time = Time::new();
+        let mut input_manager = InputManager::new();
+        input_manager.start_replay(recording);
+        let mut replayed_states = Vec::new();
+        for _ in 0..frames.len() {
+            input_manager.on_time_update(&mut time);
+            time.on_update();
+            // live events are ignored while replaying.
+            input_manager.handle_event(InputEvent::KeyPressed(KeyCode::KeyQ));
+            input_manager.on_frame_begin(time.delta());
+            replayed_states.push((
+                time.delta(),
+                input_manager.mouse_data.delta,
+                input_manager.mouse_data.is_left_button_down,
+                input_manager.is_key_pressed(KeyCode::KeyW),
+                input_manager.is_key_pressed(KeyCode::KeyQ),
+            ));
+            input_manager.on_frame_end();
+        }
+        for ((delta_time, _), replayed_state) in frames.iter().zip(&replayed_states) {
+            assert!((replayed_state.0 - delta_time).abs() < 1e-5);
+            assert!(!replayed_state.4);
+        }
+        assert_eq!(replayed_states[0].1, Vec2::ZERO);
+        assert!(replayed_states[1].2 && replayed_states[1].3);
+        assert_eq!(replayed_states[2].1, Vec2::new(5.0, 2.0));
+        assert!(replayed_states[2].2 && !replayed_states[2].3);
+
+        // the replay finishes and Time falls back to wall clock.
+        input_manager.on_time_update(&mut time);
+        assert!(!input_manager.is_replaying());
+        assert_eq!(time.frame_delta_override(), None);
+    }
+
+    #[test]
+    fn test_action_map_ron() {
+        let mut action_map = ActionMap::new();
+        action_map
+            .bind_action("jump", ButtonBinding::Key(KeyCode::Space))
+            .bind_action("fire", ButtonBinding::Mouse(MouseButtonCode::Left))
+            .bind_axis("look_x", AxisBinding::Mouse { axis: MouseAxis::X, scale: 0.1 });
+        let ron_str = action_map.to_ron_string().unwrap();
+        assert_eq!(ActionMap::from_ron_str(&ron_str).unwrap(), action_map);
+
+        let parsed = ActionMap::from_ron_str(r#"(actions: {"jump": [Key(Space), Gamepad(South)]})"#).unwrap();
+        assert_eq!(
+            parsed.action_bindings("jump"),
+            Some([ButtonBinding::Key(KeyCode::Space), ButtonBinding::Gamepad(GamepadButton::South)].as_slice())
+        );
+        assert!(ActionMap::from_ron_str("(actions: {\"jump\": [Key(NoSuchKey)]})").is_err());
+    }
+}
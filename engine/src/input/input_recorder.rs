@@ -0,0 +1,141 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use winit::keyboard::{KeyCode, ModifiersState};
+
+use crate::input::{action_map::MouseButtonCode, gamepad::GamepadEvent};
+
+#[derive(Error, Debug)]
+pub enum InputRecordingError {
+    #[error("Failed to read or write the recording file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to parse the recording: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[error("Failed to serialize the recording: {0}")]
+    Serialize(#[from] ron::Error),
+}
+
+/// An input event which flows into [`crate::input::InputManager`]. All inputs are applied as such events, so that
+/// they can be recorded and replayed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum InputEvent {
+    /// The cursor moves to the logical position.
+    MouseMoved { x: f32, y: f32 },
+    MouseButtonPressed(MouseButtonCode),
+    MouseButtonReleased(MouseButtonCode),
+    /// Scroll in lines.
+    MouseScrolled { x: f32, y: f32 },
+    KeyPressed(KeyCode),
+    KeyReleased(KeyCode),
+    ModifiersChanged(ModifiersState),
+    TextInput(String),
+    FocusLost,
+    Gamepad(GamepadEvent),
+}
+
+/// The input events applied in one frame and the delta time of that frame.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    pub delta_time: f32,
+    pub events: Vec<InputEvent>,
+}
+
+/// A recorded input session, which can be saved to and loaded from a RON file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InputRecording {
+    pub frames: Vec<RecordedFrame>,
+}
+
+impl InputRecording {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse a recording from a RON string.
+    pub fn from_ron_str(ron_str: &str) -> Result<Self, InputRecordingError> {
+        Ok(ron::from_str(ron_str)?)
+    }
+
+    /// Serialize the recording to a compact RON string.
+    pub fn to_ron_string(&self) -> Result<String, InputRecordingError> {
+        Ok(ron::to_string(self)?)
+    }
+
+    /// Load a recording from a RON file.
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self, InputRecordingError> {
+        let ron_str = std::fs::read_to_string(path)?;
+        Self::from_ron_str(&ron_str)
+    }
+
+    /// Save the recording to a RON file.
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), InputRecordingError> {
+        std::fs::write(path, self.to_ron_string()?)?;
+        Ok(())
+    }
+}
+
+/// Collects input events frame by frame.
+pub(crate) struct InputRecorder {
+    pub(crate) recording: InputRecording,
+    pending_events: Vec<InputEvent>,
+    /// If it is Some, the recording is saved to this file when recording stops.
+    pub(crate) save_path: Option<PathBuf>,
+}
+
+impl InputRecorder {
+    pub(crate) fn new(save_path: Option<PathBuf>) -> Self {
+        Self {
+            recording: InputRecording::new(),
+            pending_events: Vec::new(),
+            save_path,
+        }
+    }
+
+    pub(crate) fn record_event(&mut self, event: &InputEvent) {
+        self.pending_events.push(event.clone());
+    }
+
+    /// Close the current frame with the events recorded since last frame.
+    pub(crate) fn record_frame(&mut self, delta_time: f32) {
+        self.recording.frames.push(RecordedFrame {
+            delta_time,
+            events: std::mem::take(&mut self.pending_events),
+        });
+    }
+}
+
+/// Feeds the frames of a recording back one by one.
+pub(crate) struct InputReplayer {
+    recording: InputRecording,
+    next_frame: usize,
+    /// The frame delta override of Time before the replay, which is restored when the replay finishes.
+    pub(crate) previous_frame_delta_override: Option<Option<f32>>,
+}
+
+impl InputReplayer {
+    pub(crate) fn new(recording: InputRecording) -> Self {
+        Self {
+            recording,
+            next_frame: 0,
+            previous_frame_delta_override: None,
+        }
+    }
+
+    /// The delta time of the frame to be replayed, or None if the replay is finished.
+    pub(crate) fn next_delta_time(&self) -> Option<f32> {
+        self.recording.frames.get(self.next_frame).map(|frame| frame.delta_time)
+    }
+
+    /// Take the events of the frame to be replayed and move to the next frame.
+    pub(crate) fn next_events(&mut self) -> Vec<InputEvent> {
+        let events = self
+            .recording
+            .frames
+            .get_mut(self.next_frame)
+            .map(|frame| std::mem::take(&mut frame.events))
+            .unwrap_or_default();
+        self.next_frame += 1;
+        events
+    }
+}
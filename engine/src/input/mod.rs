@@ -1 +1,10 @@
-pub mod input_manager;
\ No newline at end of file
+pub mod input_manager;
+pub mod action_map;
+pub mod gamepad;
+pub mod input_recorder;
+
+pub use input_manager::*;
+pub use action_map::*;
+pub use gamepad::*;
+pub use input_recorder::*;
+pub use winit::keyboard::{KeyCode, ModifiersState};
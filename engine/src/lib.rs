@@ -12,6 +12,7 @@
 //! - Integrated UI framework powered by EGUI
 //! - Support gltf 2.0 partially
 //! - Perspective camera and orbit camera controller.
+//! - Headless mode which renders into an offscreen color target and captures frames to PNG/HDR images.
 //! 
 //! # Examples
 //! There are some examples in folder [`engine\examples\`](https://github.com/cgdog/imagic/tree/main/engine/examples) on GitHub.
@@ -29,6 +30,7 @@
 //!     let options = EngineOptions {
 //!         window_size: WindowSize::new(800.0, 500.0),
 //!         app_name: "lxy gltf demo",
+//!         ..Default::default()
 //!     };
 //!     let mut engine = Engine::new(options);
 //!     // 2. add nodes to scene or add Behaviors, or anything else.
@@ -72,4 +74,5 @@ pub mod prelude {
     pub use crate::window::*;
     pub use crate::time::*;
     pub use crate::graphics::*;
+    pub use crate::input::*;
 }
\ No newline at end of file
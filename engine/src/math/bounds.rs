@@ -0,0 +1,147 @@
+use crate::math::{Mat4, Vec3};
+
+/// An axis-aligned bounding box.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Aabb {
+    pub fn new(min: Vec3, max: Vec3) -> Self {
+        Self { min, max }
+    }
+
+    /// Create the box of a center and the half size along each axis.
+    pub fn from_center_half_extents(center: Vec3, half_extents: Vec3) -> Self {
+        Self::new(center - half_extents, center + half_extents)
+    }
+
+    /// The smallest box containing the points, or None if there is no point.
+    pub fn from_points<'a>(points: impl IntoIterator<Item = &'a Vec3>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = *points.next()?;
+        Some(points.fold(Self::new(first, first), |aabb, point| Self::new(aabb.min.min(*point), aabb.max.max(*point))))
+    }
+
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    /// Half of the size along each axis.
+    pub fn half_extents(&self) -> Vec3 {
+        (self.max - self.min) * 0.5
+    }
+
+    pub fn size(&self) -> Vec3 {
+        self.max - self.min
+    }
+
+    /// The smallest box containing both boxes.
+    pub fn merge(&self, other: &Aabb) -> Aabb {
+        Self::new(self.min.min(other.min), self.max.max(other.max))
+    }
+
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        point.cmpge(self.min).all() && point.cmple(self.max).all()
+    }
+
+    /// Whether the other box is inside this box.
+    pub fn contains_aabb(&self, other: &Aabb) -> bool {
+        other.min.cmpge(self.min).all() && other.max.cmple(self.max).all()
+    }
+
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        self.min.cmple(other.max).all() && self.max.cmpge(other.min).all()
+    }
+
+    /// The box containing this box transformed by a matrix, e.g., from local space to world space. It is larger
+    /// than the transformed corners if the matrix rotates the box.
+    pub fn transform(&self, matrix: &Mat4) -> Aabb {
+        let center = matrix.transform_point3(self.center());
+        let half_extents = self.half_extents();
+        let abs_matrix = [matrix.x_axis.truncate().abs(), matrix.y_axis.truncate().abs(), matrix.z_axis.truncate().abs()];
+        let world_half_extents =
+            abs_matrix[0] * half_extents.x + abs_matrix[1] * half_extents.y + abs_matrix[2] * half_extents.z;
+        Self::from_center_half_extents(center, world_half_extents)
+    }
+
+    /// The sphere through the corners of the box.
+    pub fn bounding_sphere(&self) -> BoundingSphere {
+        BoundingSphere::new(self.center(), self.half_extents().length())
+    }
+}
+
+/// A bounding sphere.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingSphere {
+    pub center: Vec3,
+    pub radius: f32,
+}
+
+impl BoundingSphere {
+    pub fn new(center: Vec3, radius: f32) -> Self {
+        Self { center, radius }
+    }
+
+    /// The sphere centered at the center of the bounding box of the points which contains all points, or None if
+    /// there is no point. It is tighter than [`Aabb::bounding_sphere`].
+    pub fn from_points<'a>(points: impl IntoIterator<Item = &'a Vec3> + Clone) -> Option<Self> {
+        let center = Aabb::from_points(points.clone())?.center();
+        let radius_squared = points.into_iter().map(|point| point.distance_squared(center)).fold(0.0, f32::max);
+        Some(Self::new(center, radius_squared.sqrt()))
+    }
+
+    /// The sphere containing this sphere transformed by a matrix. The radius is scaled by the largest scale.
+    pub fn transform(&self, matrix: &Mat4) -> BoundingSphere {
+        let max_scale = matrix.x_axis.truncate().length()
+            .max(matrix.y_axis.truncate().length())
+            .max(matrix.z_axis.truncate().length());
+        Self::new(matrix.transform_point3(self.center), self.radius * max_scale)
+    }
+
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        point.distance_squared(self.center) <= self.radius * self.radius
+    }
+
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        self.contains_point(self.center.clamp(aabb.min, aabb.max))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Quat;
+
+    #[test]
+    fn test_bounds() {
+        let points = [Vec3::new(-1.0, 0.0, 0.0), Vec3::new(1.0, 2.0, 0.0), Vec3::new(0.0, 1.0, 3.0)];
+        let aabb = Aabb::from_points(&points).unwrap();
+        assert_eq!(aabb, Aabb::new(Vec3::new(-1.0, 0.0, 0.0), Vec3::new(1.0, 2.0, 3.0)));
+        assert_eq!(aabb.center(), Vec3::new(0.0, 1.0, 1.5));
+        assert!(Aabb::from_points(&[]).is_none());
+        assert!(aabb.contains_point(Vec3::new(0.5, 0.5, 0.5)) && !aabb.contains_point(Vec3::new(0.0, 3.0, 0.0)));
+
+        let sphere = BoundingSphere::from_points(&points).unwrap();
+        assert!(points.iter().all(|point| sphere.contains_point(*point)));
+        assert!(sphere.radius <= aabb.bounding_sphere().radius);
+
+        // Rotating 90 degrees around Y swaps X and Z.
+        let matrix = Mat4::from_scale_rotation_translation(
+            Vec3::splat(2.0),
+            Quat::from_rotation_y(std::f32::consts::FRAC_PI_2),
+            Vec3::new(10.0, 0.0, 0.0),
+        );
+        let world_aabb = aabb.transform(&matrix);
+        assert!(world_aabb.min.abs_diff_eq(Vec3::new(10.0, 0.0, -2.0), 1e-4));
+        assert!(world_aabb.max.abs_diff_eq(Vec3::new(16.0, 4.0, 2.0), 1e-4));
+        let world_sphere = sphere.transform(&matrix);
+        assert!((world_sphere.radius - sphere.radius * 2.0).abs() < 1e-4);
+        assert!(world_aabb.intersects(&Aabb::new(Vec3::new(15.0, 3.0, 1.0), Vec3::splat(20.0))));
+        assert!(!world_aabb.intersects(&Aabb::new(Vec3::splat(17.0), Vec3::splat(20.0))));
+        assert!(aabb.contains_aabb(&Aabb::new(Vec3::ZERO, Vec3::ONE)) && !aabb.contains_aabb(&world_aabb));
+        assert!(BoundingSphere::new(Vec3::new(3.0, 1.0, 0.0), 2.0).intersects_aabb(&aabb));
+        assert!(!BoundingSphere::new(Vec3::new(2.0, 3.0, 4.0), 1.0).intersects_aabb(&aabb));
+    }
+}
@@ -1,11 +1,12 @@
 use bytemuck::{Pod, Zeroable};
+use serde::{Deserialize, Serialize};
 
 use crate::math::{Vec3, Vec4};
 
 pub type ColorChannelType = f32;
 
 /// Color
-#[derive(Debug, Clone, Copy, PartialEq, Pod, Zeroable)]
+#[derive(Debug, Clone, Copy, PartialEq, Pod, Zeroable, Serialize, Deserialize)]
 #[repr(C)]
 pub struct Color {
     pub r: ColorChannelType,
@@ -0,0 +1,82 @@
+use crate::math::{Aabb, BoundingSphere, Mat4, Vec3, Vec4};
+
+/// The six planes of a view frustum, whose normals point inside.
+///
+/// The planes are in the space before the view projection matrix, e.g., world space for the view projection matrix
+/// of a camera.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frustum {
+    /// The left, right, bottom, top, near and far planes. `xyz` is the normalized normal and `w` is the distance, so
+    /// a point is inside the plane if `dot(normal, point) + w >= 0`.
+    pub planes: [Vec4; 6],
+}
+
+impl Frustum {
+    /// Extract the planes from a view projection matrix whose clip space depth is in `[0, 1]`, like the matrices of
+    /// [`crate::prelude::Camera`].
+    pub fn from_view_projection(view_projection: &Mat4) -> Self {
+        let rows = [view_projection.row(0), view_projection.row(1), view_projection.row(2), view_projection.row(3)];
+        let planes = [
+            rows[3] + rows[0],
+            rows[3] - rows[0],
+            rows[3] + rows[1],
+            rows[3] - rows[1],
+            rows[2],
+            rows[3] - rows[2],
+        ]
+        .map(|plane| plane / plane.truncate().length());
+        Self { planes }
+    }
+
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        self.planes.iter().all(|plane| plane.truncate().dot(point) + plane.w >= 0.0)
+    }
+
+    /// Whether the box is inside or intersects the frustum. It is conservative, so a box near a corner of the frustum
+    /// may be reported as intersecting while it is outside.
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        self.planes.iter().all(|plane| {
+            let normal = plane.truncate();
+            // The corner farthest along the normal.
+            let positive_vertex = Vec3::select(normal.cmpge(Vec3::ZERO), aabb.max, aabb.min);
+            normal.dot(positive_vertex) + plane.w >= 0.0
+        })
+    }
+
+    /// Whether the sphere is inside or intersects the frustum. It is conservative like [`Frustum::intersects_aabb`].
+    pub fn intersects_sphere(&self, bounding_sphere: &BoundingSphere) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.truncate().dot(bounding_sphere.center) + plane.w >= -bounding_sphere.radius)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frustum() {
+        // Looking at -Z from the origin.
+        let view_projection = Mat4::perspective_rh(std::f32::consts::FRAC_PI_2, 1.0, 0.1, 100.0);
+        let frustum = Frustum::from_view_projection(&view_projection);
+        assert!(frustum.contains_point(Vec3::new(0.0, 0.0, -10.0)));
+        assert!(frustum.contains_point(Vec3::new(9.0, -9.0, -10.0)));
+        assert!(!frustum.contains_point(Vec3::new(11.0, 0.0, -10.0)));
+        assert!(!frustum.contains_point(Vec3::new(0.0, 0.0, 10.0)));
+        assert!(!frustum.contains_point(Vec3::new(0.0, 0.0, -0.05)));
+        assert!(!frustum.contains_point(Vec3::new(0.0, 0.0, -101.0)));
+
+        let behind = Aabb::from_center_half_extents(Vec3::new(0.0, 0.0, 5.0), Vec3::ONE);
+        let straddling = Aabb::from_center_half_extents(Vec3::new(11.0, 0.0, -10.0), Vec3::splat(2.0));
+        assert!(!frustum.intersects_aabb(&behind));
+        assert!(frustum.intersects_aabb(&straddling));
+        assert!(!frustum.intersects_sphere(&behind.bounding_sphere()));
+        assert!(frustum.intersects_sphere(&BoundingSphere::new(Vec3::new(0.0, 0.0, 0.5), 1.0)));
+
+        let orthographic = Mat4::orthographic_rh(-1.0, 1.0, -1.0, 1.0, 0.0, 10.0) * Mat4::from_translation(Vec3::new(-5.0, 0.0, 0.0));
+        let frustum = Frustum::from_view_projection(&orthographic);
+        assert!(frustum.contains_point(Vec3::new(5.5, 0.0, -5.0)));
+        assert!(!frustum.contains_point(Vec3::new(0.0, 0.0, -5.0)));
+    }
+}
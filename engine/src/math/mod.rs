@@ -18,6 +18,12 @@ pub type EulerRot = glam::EulerRot;
 pub mod color;
 pub mod spherical_coordinate;
 pub mod spherical_harmonics;
+pub mod bounds;
+pub mod frustum;
+pub mod ray;
 
 pub use color::*;
-pub use spherical_coordinate::*;
\ No newline at end of file
+pub use spherical_coordinate::*;
+pub use bounds::*;
+pub use frustum::*;
+pub use ray::*;
\ No newline at end of file
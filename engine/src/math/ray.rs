@@ -0,0 +1,108 @@
+use crate::math::{Aabb, BoundingSphere, Mat4, Vec3};
+
+/// A half-line from an origin, e.g., from a camera through a pixel, see
+/// [`crate::prelude::Camera::screen_point_to_ray`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ray {
+    pub origin: Vec3,
+    /// The normalized direction.
+    pub direction: Vec3,
+}
+
+impl Ray {
+    /// Create a ray. The direction is normalized.
+    pub fn new(origin: Vec3, direction: Vec3) -> Self {
+        Self { origin, direction: direction.normalize_or_zero() }
+    }
+
+    /// The point at a distance along the ray.
+    pub fn at(&self, distance: f32) -> Vec3 {
+        self.origin + self.direction * distance
+    }
+
+    /// The ray transformed by a matrix, e.g., from world space to the local space of a mesh. Distances along the
+    /// transformed ray are different from distances along this ray if the matrix scales.
+    pub fn transform(&self, matrix: &Mat4) -> Ray {
+        Self::new(matrix.transform_point3(self.origin), matrix.transform_vector3(self.direction))
+    }
+
+    /// The distance to where the ray enters the box, which is 0 if the origin is inside the box.
+    pub fn intersect_aabb(&self, aabb: &Aabb) -> Option<f32> {
+        let inverse_direction = self.direction.recip();
+        let t0 = (aabb.min - self.origin) * inverse_direction;
+        let t1 = (aabb.max - self.origin) * inverse_direction;
+        // `f32::max` and `f32::min` ignore the NaNs of axes which are parallel to the ray.
+        let t_enter = t0.min(t1).max_element().max(0.0);
+        let t_exit = t0.max(t1).min_element();
+        (t_enter <= t_exit).then_some(t_enter)
+    }
+
+    /// The distance to where the ray enters the sphere, which is 0 if the origin is inside the sphere.
+    pub fn intersect_sphere(&self, bounding_sphere: &BoundingSphere) -> Option<f32> {
+        let to_center = bounding_sphere.center - self.origin;
+        let projection = to_center.dot(self.direction);
+        let distance_squared = to_center.length_squared() - projection * projection;
+        let radius_squared = bounding_sphere.radius * bounding_sphere.radius;
+        if distance_squared > radius_squared {
+            return None;
+        }
+        let half_chord = (radius_squared - distance_squared).sqrt();
+        let t_exit = projection + half_chord;
+        (t_exit >= 0.0).then_some((projection - half_chord).max(0.0))
+    }
+
+    /// The distance to where the ray hits a triangle from either side, by the Möller–Trumbore algorithm.
+    pub fn intersect_triangle(&self, a: Vec3, b: Vec3, c: Vec3) -> Option<f32> {
+        let edge1 = b - a;
+        let edge2 = c - a;
+        let p = self.direction.cross(edge2);
+        let determinant = edge1.dot(p);
+        if determinant.abs() < f32::EPSILON {
+            return None;
+        }
+        let inverse_determinant = 1.0 / determinant;
+        let to_origin = self.origin - a;
+        let u = to_origin.dot(p) * inverse_determinant;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+        let q = to_origin.cross(edge1);
+        let v = self.direction.dot(q) * inverse_determinant;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+        let distance = edge2.dot(q) * inverse_determinant;
+        (distance >= 0.0).then_some(distance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ray() {
+        let ray = Ray::new(Vec3::new(0.0, 0.0, 10.0), Vec3::new(0.0, 0.0, -2.0));
+        assert_eq!(ray.direction, Vec3::NEG_Z);
+        assert_eq!(ray.at(4.0), Vec3::new(0.0, 0.0, 6.0));
+
+        let aabb = Aabb::from_center_half_extents(Vec3::ZERO, Vec3::ONE);
+        assert_eq!(ray.intersect_aabb(&aabb), Some(9.0));
+        assert_eq!(Ray::new(Vec3::ZERO, Vec3::X).intersect_aabb(&aabb), Some(0.0));
+        assert_eq!(Ray::new(Vec3::new(0.0, 2.0, 10.0), Vec3::NEG_Z).intersect_aabb(&aabb), None);
+        assert_eq!(Ray::new(Vec3::new(0.0, 0.0, -2.0), Vec3::NEG_Z).intersect_aabb(&aabb), None);
+
+        assert_eq!(ray.intersect_sphere(&BoundingSphere::new(Vec3::ZERO, 2.0)), Some(8.0));
+        assert_eq!(ray.intersect_sphere(&BoundingSphere::new(Vec3::new(0.0, 0.0, 20.0), 2.0)), None);
+
+        let (a, b, c) = (Vec3::new(-1.0, -1.0, 0.0), Vec3::new(1.0, -1.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+        assert_eq!(ray.intersect_triangle(a, b, c), Some(10.0));
+        // Both sides are hit.
+        assert_eq!(Ray::new(Vec3::new(0.0, 0.0, -1.0), Vec3::Z).intersect_triangle(a, b, c), Some(1.0));
+        assert_eq!(Ray::new(Vec3::new(2.0, 0.0, 10.0), Vec3::NEG_Z).intersect_triangle(a, b, c), None);
+
+        let local_ray = ray.transform(&Mat4::from_scale(Vec3::splat(0.5)));
+        assert_eq!(local_ray.origin, Vec3::new(0.0, 0.0, 5.0));
+        assert_eq!(local_ray.direction, Vec3::NEG_Z);
+    }
+}
@@ -0,0 +1,209 @@
+use std::path::Path;
+
+use thiserror::Error;
+
+use crate::{
+    assets::TextureFormat,
+    graphics::texture_buffer_converter::{TextureData, TextureReadError},
+};
+
+#[derive(Error, Debug)]
+pub enum FrameCaptureError {
+    #[error("Frame capture is only supported in headless mode")]
+    NotHeadless,
+    #[error("The offscreen color target has not been created")]
+    NoColorTarget,
+    #[error("Failed to read back the frame: {0}")]
+    ReadFailed(String),
+    #[error("Unsupported image file extension: {0}, only png and hdr are supported")]
+    UnsupportedExtension(String),
+    #[error("Failed to save the image: {0}")]
+    SaveFailed(#[from] image::ImageError),
+}
+
+/// The pixels of a frame rendered in headless mode.
+pub struct CapturedFrame {
+    pub width: u32,
+    pub height: u32,
+    /// The format of the offscreen color target which the frame is read back from.
+    pub format: TextureFormat,
+    pub(crate) data: TextureData,
+}
+
+impl CapturedFrame {
+    pub(crate) fn new(width: u32, height: u32, format: TextureFormat, data: TextureData) -> Self {
+        Self {
+            width,
+            height,
+            format,
+            data,
+        }
+    }
+
+    /// Convert the frame to 8 bit sRGB pixels, which are what PNG files store.
+    ///
+    /// Float pixels are treated as linear colors, clamped to [0, 1] and encoded to sRGB.
+    pub fn to_rgba8(&self) -> image::RgbaImage {
+        let pixels: Vec<u8> = match &self.data {
+            TextureData::U8(layers) => {
+                let mut pixels = layers[0].clone();
+                if matches!(self.format, TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb) {
+                    for pixel in pixels.chunks_exact_mut(4) {
+                        pixel.swap(0, 2);
+                    }
+                }
+                pixels
+            }
+            TextureData::F32(layers) => layers[0]
+                .chunks_exact(4)
+                .flat_map(|pixel| {
+                    [
+                        linear_to_srgb(pixel[0]),
+                        linear_to_srgb(pixel[1]),
+                        linear_to_srgb(pixel[2]),
+                        (pixel[3].clamp(0.0, 1.0) * 255.0 + 0.5) as u8,
+                    ]
+                })
+                .collect(),
+            TextureData::U16(layers) => layers[0].iter().map(|value| (*value).min(255) as u8).collect(),
+            TextureData::I32(layers) => layers[0].iter().map(|value| (*value).clamp(0, 255) as u8).collect(),
+        };
+        image::RgbaImage::from_raw(self.width, self.height, pixels)
+            .expect("The size of captured pixels does not match the frame size")
+    }
+
+    /// Convert the frame to linear float pixels, which are what HDR files store. Alpha is dropped.
+    ///
+    /// 8 bit pixels of sRGB formats are decoded to linear colors.
+    pub fn to_rgb32f(&self) -> image::Rgb32FImage {
+        let pixels: Vec<f32> = match &self.data {
+            TextureData::U8(layers) => {
+                let is_srgb = self.format.is_srgb();
+                let is_bgra = matches!(self.format, TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb);
+                layers[0]
+                    .chunks_exact(4)
+                    .flat_map(|pixel| {
+                        let rgb = if is_bgra {
+                            [pixel[2], pixel[1], pixel[0]]
+                        } else {
+                            [pixel[0], pixel[1], pixel[2]]
+                        };
+                        rgb.map(|value| {
+                            let value = value as f32 / 255.0;
+                            if is_srgb { srgb_to_linear(value) } else { value }
+                        })
+                    })
+                    .collect()
+            }
+            TextureData::F32(layers) => layers[0]
+                .chunks_exact(4)
+                .flat_map(|pixel| [pixel[0], pixel[1], pixel[2]])
+                .collect(),
+            TextureData::U16(layers) => layers[0]
+                .chunks_exact(4)
+                .flat_map(|pixel| [pixel[0] as f32, pixel[1] as f32, pixel[2] as f32])
+                .collect(),
+            TextureData::I32(layers) => layers[0]
+                .chunks_exact(4)
+                .flat_map(|pixel| [pixel[0] as f32, pixel[1] as f32, pixel[2] as f32])
+                .collect(),
+        };
+        image::Rgb32FImage::from_raw(self.width, self.height, pixels)
+            .expect("The size of captured pixels does not match the frame size")
+    }
+
+    /// Save the frame to an image file. The file format is decided by the extension of the path:
+    /// - `.png`: 8 bit sRGB image, see [`CapturedFrame::to_rgba8`].
+    /// - `.hdr`: Radiance HDR image with linear float colors, see [`CapturedFrame::to_rgb32f`].
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), FrameCaptureError> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_ascii_lowercase())
+            .unwrap_or_default();
+        match extension.as_str() {
+            "png" => self.to_rgba8().save_with_format(path, image::ImageFormat::Png)?,
+            "hdr" => self.to_rgb32f().save_with_format(path, image::ImageFormat::Hdr)?,
+            _ => return Err(FrameCaptureError::UnsupportedExtension(extension)),
+        }
+        Ok(())
+    }
+}
+
+impl From<TextureReadError> for FrameCaptureError {
+    fn from(value: TextureReadError) -> Self {
+        FrameCaptureError::ReadFailed(value.to_string())
+    }
+}
+
+fn linear_to_srgb(value: f32) -> u8 {
+    let value = value.clamp(0.0, 1.0);
+    let srgb = if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    };
+    (srgb * 255.0 + 0.5) as u8
+}
+
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::texture_buffer_converter::{convert_buffer_data, padded_bytes_per_row, remove_row_padding};
+
+    #[test]
+    fn test_remove_row_padding() {
+        let (width, height) = (3, 2);
+        let unpadded_bytes_per_row = 4 * width;
+        // A row which is already aligned is not padded.
+        assert_eq!(padded_bytes_per_row(256), 256);
+        let padded_bytes_per_row = padded_bytes_per_row(unpadded_bytes_per_row);
+        assert_eq!(padded_bytes_per_row, 256);
+
+        let pixels: Vec<u8> = (0..(unpadded_bytes_per_row * height) as u8).collect();
+        let mut padded_data = vec![0xAB; (padded_bytes_per_row * height) as usize];
+        for (row, padded_row) in pixels.chunks_exact(unpadded_bytes_per_row as usize).zip(padded_data.chunks_exact_mut(256)) {
+            padded_row[..row.len()].copy_from_slice(row);
+        }
+        let data = remove_row_padding(&padded_data, unpadded_bytes_per_row, padded_bytes_per_row);
+        assert_eq!(data, pixels);
+
+        let texture_data = convert_buffer_data(&data, data.len() as u64, 1, width, height, TextureFormat::Rgba8Unorm).unwrap();
+        let frame = CapturedFrame::new(width, height, TextureFormat::Rgba8Unorm, texture_data);
+        assert_eq!(frame.to_rgba8().into_raw(), pixels);
+    }
+
+    #[test]
+    fn test_png_encoding() {
+        // Bgra pixels are swizzled to rgba.
+        let pixels = vec![10, 20, 30, 255, 40, 50, 60, 128];
+        let frame = CapturedFrame::new(2, 1, TextureFormat::Bgra8UnormSrgb, TextureData::U8(vec![pixels]));
+        let path = std::env::temp_dir().join(format!("imagic_frame_capture_{}.png", std::process::id()));
+        frame.save(&path).unwrap();
+        let loaded = image::open(&path).unwrap().to_rgba8();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.dimensions(), (2, 1));
+        assert_eq!(loaded.into_raw(), vec![30, 20, 10, 255, 60, 50, 40, 128]);
+
+        // Linear float pixels are clamped and encoded to sRGB.
+        let pixels = vec![0.0, 0.5, 1.0, 1.0, -1.0, 2.0, 0.0031308, 0.5];
+        let frame = CapturedFrame::new(2, 1, TextureFormat::Rgba32Float, TextureData::F32(vec![pixels]));
+        let pixels = frame.to_rgba8().into_raw();
+        assert_eq!(pixels[0], 0);
+        assert!(pixels[1].abs_diff(188) <= 1);
+        assert_eq!(pixels[2..6], [255, 255, 0, 255]);
+        assert!(pixels[6].abs_diff(10) <= 1);
+        assert_eq!(pixels[7], 128);
+
+        assert!(matches!(frame.save("frame.jpg"), Err(FrameCaptureError::UnsupportedExtension(extension)) if extension == "jpg"));
+    }
+}
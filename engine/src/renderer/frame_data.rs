@@ -3,6 +3,7 @@ use crate::{
     core::node::NodeHandle,
     graphics::{bind_group::BindGroupID, buffer_view::BufferView, render_pipeline::PipelineHashType},
     math::{Mat4, Vec3, Vec4, color::Color},
+    renderer::shadow_map::ShadowViewRenderData,
 };
 
 /// Data used to render a item in the scene.
@@ -94,6 +95,10 @@ impl CameraRenderData {
 pub(crate) struct FrameRenderData {
     pub camera_data: Vec<CameraRenderData>,
     pub(crate) time_data: Vec4,
+    /// The shadow atlas which shadow views are rendered into.
+    pub(crate) shadow_atlas: TextureHandle,
+    /// The shadow views rendered before all cameras.
+    pub(crate) shadow_view_data: Vec<ShadowViewRenderData>,
 }
 
 impl Default for FrameRenderData {
@@ -101,6 +106,8 @@ impl Default for FrameRenderData {
         Self {
             camera_data: vec![],
             time_data: Vec4::ZERO,
+            shadow_atlas: TextureHandle::INVALID,
+            shadow_view_data: vec![],
         }
     }
 }
@@ -108,5 +115,6 @@ impl Default for FrameRenderData {
 impl FrameRenderData {
     pub fn reset(&mut self) {
         self.camera_data.clear();
+        self.shadow_view_data.clear();
     }
 }
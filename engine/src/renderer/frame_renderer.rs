@@ -1,7 +1,8 @@
 use crate::{
+    assets::TextureHandle,
     behaviors::behavior_wrapper::BehaviorWrapper, core::LogicContext, graphics::{graphics_context::GraphicsContext,
         render_api::RenderAPI
-    }, renderer::{frame_data::FrameRenderData, ui_renderer::UIRenderer}
+    }, renderer::{frame_data::FrameRenderData, shadow_map::ShadowMapper, ui_renderer::UIRenderer}
 };
 
 /// The frame renderer of the engine.
@@ -10,6 +11,9 @@ use crate::{
 pub struct FrameRenderer {
     pub(crate) frame_render_data: FrameRenderData,
     pub(crate) ui_renderer: Option<UIRenderer>,
+    pub(crate) shadow_mapper: ShadowMapper,
+    /// The color target which cameras render into in headless mode.
+    pub(crate) offscreen_color_target: TextureHandle,
 }
 
 impl FrameRenderer {
@@ -17,6 +21,8 @@ impl FrameRenderer {
         Self {
             frame_render_data: FrameRenderData::default(),
             ui_renderer: None,
+            shadow_mapper: ShadowMapper::new(),
+            offscreen_color_target: TextureHandle::INVALID,
         }
     }
 
@@ -26,39 +32,78 @@ impl FrameRenderer {
         graphics_context: & GraphicsContext,
         behavior_wrappers: &mut Vec<BehaviorWrapper>,
     ) {
-        if let Ok(surface_texture) = graphics_context.surface.get_current_texture() {
-            // TODO: 不需要每帧创建 surface_texture_view?
-            let surface_texture_view = surface_texture
-                .texture
-                .create_view(&wgpu::TextureViewDescriptor::default());
+        if let Some(surface) = &graphics_context.surface {
+            if let Ok(surface_texture) = surface.get_current_texture() {
+                // TODO: 不需要每帧创建 surface_texture_view?
+                let surface_texture_view = surface_texture
+                    .texture
+                    .create_view(&wgpu::TextureViewDescriptor::default());
+                self.render_to_view(logic_context, graphics_context, &surface_texture_view);
+                self.render_ui(logic_context, graphics_context, &surface_texture_view, behavior_wrappers);
+                surface_texture.present();
+            }
+        } else if let Some(offscreen_color_target) = logic_context
+            .texture_sampler_manager
+            .get_texture(&self.offscreen_color_target)
+            && let Some(offscreen_color_target_view) = &offscreen_color_target.view
+        {
+            // headless mode: render into the offscreen color target without UI.
+            let offscreen_color_target_view = offscreen_color_target_view.view.clone();
+            self.render_to_view(logic_context, graphics_context, &offscreen_color_target_view);
+        }
+    }
 
-            self.frame_render_data
-                .camera_data
-                .sort_by(|camera_data_a, camera_data_b| {
-                    camera_data_a.priority.cmp(&camera_data_b.priority)
-                });
+    /// Render shadow maps and all cameras into the given view, which is the surface texture or the offscreen color target.
+    fn render_to_view(
+        &mut self,
+        logic_context: &mut LogicContext,
+        graphics_context: & GraphicsContext,
+        target_view: &wgpu::TextureView,
+    ) {
+        self.frame_render_data
+            .camera_data
+            .sort_by(|camera_data_a, camera_data_b| {
+                camera_data_a.priority.cmp(&camera_data_b.priority)
+            });
 
-            let surface_texture_view_ref = &surface_texture_view;
-            for camera_render_data in &self.frame_render_data.camera_data {
-                // TODO: support render texture
-                RenderAPI::render(
-                    graphics_context,
-                    logic_context.texture_sampler_manager,
-                    surface_texture_view_ref,
-                    camera_render_data,
-                );
-            }
+        if !self.frame_render_data.shadow_view_data.is_empty() {
+            RenderAPI::render_shadow_maps(
+                graphics_context,
+                logic_context.texture_sampler_manager,
+                self.frame_render_data.shadow_atlas,
+                &self.frame_render_data.shadow_view_data,
+            );
+        }
+
+        for camera_render_data in &self.frame_render_data.camera_data {
+            // TODO: support render texture
+            RenderAPI::render(
+                graphics_context,
+                logic_context.texture_sampler_manager,
+                target_view,
+                camera_render_data,
+            );
+        }
+    }
+
+    fn render_ui(
+        &mut self,
+        logic_context: &mut LogicContext,
+        graphics_context: & GraphicsContext,
+        target_view: &wgpu::TextureView,
+        behavior_wrappers: &mut Vec<BehaviorWrapper>,
+    ) {
+        if let Some(main_window) = graphics_context.main_window() {
             if self.ui_renderer.is_none() {
                 self.ui_renderer = Some(UIRenderer::new(
                     &graphics_context.device,
                     graphics_context.surface_config.format,
-                    graphics_context.main_window().get_ref(),
+                    main_window.get_ref(),
                 ));
             }
             if let Some(ui_renderer) = &mut self.ui_renderer {
-                ui_renderer.draw(logic_context, graphics_context, surface_texture_view_ref, behavior_wrappers);
+                ui_renderer.draw(logic_context, graphics_context, main_window.get_ref(), target_view, behavior_wrappers);
             }
-            surface_texture.present();
         }
     }
 }
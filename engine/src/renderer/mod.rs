@@ -1,3 +1,5 @@
 pub mod frame_renderer;
 pub mod frame_data;
-pub mod ui_renderer;
\ No newline at end of file
+pub mod ui_renderer;
+pub mod shadow_map;
+pub mod frame_capture;
\ No newline at end of file
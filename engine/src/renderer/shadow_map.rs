@@ -0,0 +1,586 @@
+use bytemuck::{Pod, Zeroable};
+
+use crate::{
+    assets::{TextureDimension, TextureFormat, TextureHandle, TextureSamplerManager},
+    components::{Camera, LightType, LightsGPUData, ShadowSettings},
+    core::{Layer, LayerMask},
+    graphics::{graphics_context::GraphicsContext, uniform::BuiltinUniforms},
+    math::{Aabb, BoundingSphere, Frustum, Mat4, Vec3, Vec4},
+    renderer::frame_data::ItemRenderData,
+};
+
+/// The max count of shadow views (cascades, spot light maps and cube map faces) in one frame.
+/// It must be equal to the array size of `ShadowsInfo.views` in shaders.
+pub(crate) const MAX_SHADOW_VIEWS: usize = 16;
+
+/// The max cascade count of a directional light.
+pub(crate) const MAX_SHADOW_CASCADES: u32 = 4;
+
+/// The depth format of the shadow atlas.
+pub(crate) const SHADOW_ATLAS_FORMAT: TextureFormat = TextureFormat::Depth32Float;
+
+/// The default size in pixels of the shadow atlas.
+pub(crate) const DEFAULT_SHADOW_ATLAS_SIZE: u32 = 4096;
+
+/// A square tile in the shadow atlas, in pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct ShadowAtlasTile {
+    pub(crate) x: u32,
+    pub(crate) y: u32,
+    pub(crate) size: u32,
+}
+
+/// The shadow atlas is a single depth texture which contains the shadow maps of all lights.
+///
+/// Tiles are allocated by a shelf packer every frame. Requests are sorted by size before allocation,
+/// so that power-of-two tiles are packed without holes.
+pub(crate) struct ShadowAtlas {
+    pub(crate) size: u32,
+    pub(crate) texture: TextureHandle,
+    cursor_x: u32,
+    shelf_y: u32,
+    shelf_height: u32,
+}
+
+impl ShadowAtlas {
+    pub(crate) fn new() -> Self {
+        Self {
+            size: 0,
+            texture: TextureHandle::INVALID,
+            cursor_x: 0,
+            shelf_y: 0,
+            shelf_height: 0,
+        }
+    }
+
+    /// Make sure the atlas texture exists and has the given size.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - Returns `true` if the atlas texture is (re)created, `false` otherwise.
+    pub(crate) fn ensure_texture(&mut self, texture_sampler_manager: &mut TextureSamplerManager, size: u32) -> bool {
+        if self.texture != TextureHandle::INVALID && self.size == size {
+            return false;
+        }
+        if self.texture != TextureHandle::INVALID {
+            texture_sampler_manager.remove_texture(&self.texture);
+        }
+        self.texture = texture_sampler_manager.create_attachment(
+            size,
+            size,
+            1,
+            TextureDimension::D2,
+            1,
+            SHADOW_ATLAS_FORMAT,
+        );
+        self.size = size;
+        true
+    }
+
+    /// Free all tiles.
+    pub(crate) fn reset(&mut self) {
+        self.cursor_x = 0;
+        self.shelf_y = 0;
+        self.shelf_height = 0;
+    }
+
+    /// Allocate a square tile.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The size of the tile in pixels.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<ShadowAtlasTile>` - The allocated tile, or `None` if the atlas is full.
+    pub(crate) fn allocate(&mut self, size: u32) -> Option<ShadowAtlasTile> {
+        if size == 0 || size > self.size {
+            return None;
+        }
+        if self.cursor_x + size > self.size {
+            // start a new shelf.
+            self.shelf_y += self.shelf_height;
+            self.cursor_x = 0;
+            self.shelf_height = 0;
+        }
+        if self.shelf_y + size > self.size {
+            return None;
+        }
+        let tile = ShadowAtlasTile {
+            x: self.cursor_x,
+            y: self.shelf_y,
+            size,
+        };
+        self.cursor_x += size;
+        self.shelf_height = self.shelf_height.max(size);
+        Some(tile)
+    }
+}
+
+/// A light which casts shadow, collected from the scene.
+pub(crate) struct ShadowCastingLight {
+    /// The index of the light in [`LightsGPUData::lights_info`].
+    pub(crate) light_index: usize,
+    pub(crate) light_type: LightType,
+    /// The world position of the light.
+    pub(crate) position: Vec3,
+    /// The normalized world direction in which the light rays travel.
+    pub(crate) direction: Vec3,
+    pub(crate) settings: ShadowSettings,
+    /// The layers of the nodes which cast the shadow.
+    pub(crate) culling_mask: LayerMask,
+}
+
+/// One view rendered into the shadow atlas, e.g., one cascade of a directional light.
+pub(crate) struct ShadowView {
+    pub(crate) view_projection: Mat4,
+    pub(crate) tile: ShadowAtlasTile,
+    /// The layers of the nodes rendered into the view.
+    pub(crate) culling_mask: LayerMask,
+    /// The frustum of `view_projection`, casters outside it are not rendered into the view.
+    pub(crate) frustum: Frustum,
+    /// The sphere of the range of a point light, casters outside it cast no shadow from the light.
+    pub(crate) range: Option<BoundingSphere>,
+}
+
+impl ShadowView {
+    /// Whether a caster is rendered into the view.
+    ///
+    /// # Arguments
+    ///
+    /// * `layer` - The layer of the caster.
+    /// * `world_aabb` - The world bounds of the caster, `None` if the caster is never culled.
+    pub(crate) fn contains_caster(&self, layer: Layer, world_aabb: Option<&Aabb>) -> bool {
+        if !self.culling_mask.contains(layer) {
+            return false;
+        }
+        let Some(world_aabb) = world_aabb else {
+            return true;
+        };
+        self.range.is_none_or(|range| range.intersects_aabb(world_aabb)) && self.frustum.intersects_aabb(world_aabb)
+    }
+}
+
+/// Data used to render the shadow casters of a shadow view.
+pub(crate) struct ShadowViewRenderData {
+    /// The viewport in the shadow atlas, in pixels.
+    pub(crate) view_port: Vec4,
+    pub(crate) item_data: Vec<ItemRenderData>,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Pod, Zeroable)]
+pub(crate) struct GPUShadowViewData {
+    pub(crate) view_projection: [f32; 16],
+    /// xy: uv offset of the tile in the atlas, zw: uv scale of the tile.
+    pub(crate) atlas_rect: [f32; 4],
+    /// x: depth bias, y: normal offset in world units, z: texel size of the atlas in uv,
+    /// w: 1.0 if the normal offset should be scaled by the distance to the light (perspective projection), 0.0 otherwise.
+    pub(crate) params: [f32; 4],
+}
+
+#[derive(Default)]
+pub(crate) struct ShadowsGPUData {
+    pub(crate) views: Vec<GPUShadowViewData>,
+}
+
+impl ShadowsGPUData {
+    pub(crate) fn to_vec_u8(&self) -> Vec<u8> {
+        let mut views = [GPUShadowViewData::zeroed(); MAX_SHADOW_VIEWS];
+        for (index, view) in self.views.iter().take(MAX_SHADOW_VIEWS).enumerate() {
+            views[index] = *view;
+        }
+        bytemuck::cast_slice(&views).to_vec()
+    }
+}
+
+/// Builds shadow views of all shadow casting lights and allocates their tiles in the shadow atlas.
+pub(crate) struct ShadowMapper {
+    pub(crate) atlas: ShadowAtlas,
+    /// The view projection uniforms of each shadow view.
+    pub(crate) view_uniforms: Vec<BuiltinUniforms>,
+    /// Whether the overflow warning has been logged since the overflow started, to avoid logging every frame.
+    has_warned_overflow: bool,
+}
+
+impl ShadowMapper {
+    pub(crate) fn new() -> Self {
+        Self {
+            atlas: ShadowAtlas::new(),
+            view_uniforms: Vec::new(),
+            has_warned_overflow: false,
+        }
+    }
+
+    /// Build the shadow views of this frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `shadow_casting_lights` - The lights which cast shadow.
+    /// * `camera` - The camera and its view matrix used to fit the cascades of directional lights.
+    /// * `atlas_size` - The size of the shadow atlas.
+    /// * `lights_gpu_data` - The lights data whose shadow flags will be filled.
+    /// * `texture_sampler_manager` - The texture sampler manager.
+    /// * `graphics_context` - The graphics context.
+    /// * `global_uniforms` - The global uniforms, whose bind groups are invalidated when the atlas is recreated.
+    ///
+    /// # Returns
+    ///
+    /// * `(Vec<ShadowView>, ShadowsGPUData)` - The shadow views to render and their GPU data.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn build_shadow_views(
+        &mut self,
+        shadow_casting_lights: &[ShadowCastingLight],
+        camera: Option<(&Camera, Mat4)>,
+        atlas_size: u32,
+        lights_gpu_data: &mut LightsGPUData,
+        texture_sampler_manager: &mut TextureSamplerManager,
+        graphics_context: &mut GraphicsContext,
+        global_uniforms: &mut BuiltinUniforms,
+    ) -> (Vec<ShadowView>, ShadowsGPUData) {
+        // Shaders always bind the atlas, so a tiny one is created even if no light casts shadow.
+        let required_atlas_size = if !shadow_casting_lights.is_empty() {
+            atlas_size.max(1)
+        } else if self.atlas.texture != TextureHandle::INVALID {
+            self.atlas.size
+        } else {
+            1
+        };
+        if self.atlas.ensure_texture(texture_sampler_manager, required_atlas_size) {
+            global_uniforms.clear_bind_groups(graphics_context);
+        }
+        self.atlas.reset();
+
+        let mut shadow_views = Vec::new();
+        let mut shadows_gpu_data = ShadowsGPUData::default();
+        let mut overflow_reason = None;
+
+        // Allocate larger tiles first to pack the atlas tightly.
+        let mut sorted_lights: Vec<&ShadowCastingLight> = shadow_casting_lights.iter().collect();
+        sorted_lights.sort_by_key(|light| std::cmp::Reverse(light.settings.resolution));
+
+        for shadow_casting_light in sorted_lights {
+            let settings = &shadow_casting_light.settings;
+            let resolution = settings.resolution.max(1).next_power_of_two();
+            let light_views = match &shadow_casting_light.light_type {
+                LightType::Directional {} => {
+                    if let Some((camera, view_matrix)) = camera {
+                        Self::compute_directional_views(shadow_casting_light, camera, &view_matrix, resolution)
+                    } else {
+                        Vec::new()
+                    }
+                }
+                LightType::Spot { max_distance, outer_angle, .. } => {
+                    Self::compute_spot_views(shadow_casting_light, *max_distance, *outer_angle, resolution)
+                }
+                LightType::Point { max_distance } => {
+                    Self::compute_point_views(shadow_casting_light, *max_distance, resolution)
+                }
+                LightType::Area { .. } => Vec::new(),
+            };
+            if light_views.is_empty() {
+                continue;
+            }
+            if shadow_views.len() + light_views.len() > MAX_SHADOW_VIEWS {
+                overflow_reason = Some("too many shadow views");
+                continue;
+            }
+
+            let mut tiles = Vec::with_capacity(light_views.len());
+            for _ in 0..light_views.len() {
+                if let Some(tile) = self.atlas.allocate(resolution) {
+                    tiles.push(tile);
+                } else {
+                    break;
+                }
+            }
+            if tiles.len() != light_views.len() {
+                overflow_reason = Some("the shadow atlas is full");
+                continue;
+            }
+
+            let range = match &shadow_casting_light.light_type {
+                LightType::Point { max_distance } => {
+                    Some(BoundingSphere::new(shadow_casting_light.position, *max_distance))
+                }
+                _ => None,
+            };
+            let first_view_index = shadow_views.len() as u32;
+            let texel_size = 1.0 / self.atlas.size as f32;
+            for ((view_projection, normal_offset_scale, is_perspective), tile) in light_views.into_iter().zip(tiles) {
+                shadows_gpu_data.views.push(GPUShadowViewData {
+                    view_projection: view_projection.to_cols_array(),
+                    atlas_rect: [
+                        tile.x as f32 * texel_size,
+                        tile.y as f32 * texel_size,
+                        tile.size as f32 * texel_size,
+                        tile.size as f32 * texel_size,
+                    ],
+                    params: [
+                        settings.depth_bias,
+                        settings.normal_offset * normal_offset_scale,
+                        texel_size,
+                        if is_perspective { 1.0 } else { 0.0 },
+                    ],
+                });
+                shadow_views.push(ShadowView {
+                    view_projection,
+                    tile,
+                    culling_mask: shadow_casting_light.culling_mask,
+                    frustum: Frustum::from_view_projection(&view_projection),
+                    range,
+                });
+            }
+            let light_data = &mut lights_gpu_data.lights_info[shadow_casting_light.light_index];
+            light_data.flags[1] = shadow_views.len() as u32 - first_view_index;
+            light_data.flags[2] = first_view_index;
+            light_data.flags[3] = settings.pcf_radius;
+        }
+
+        self.update_overflow_warning(overflow_reason);
+
+        while self.view_uniforms.len() < shadow_views.len() {
+            let label = format!("ShadowView{}", self.view_uniforms.len());
+            self.view_uniforms.push(BuiltinUniforms::new(label));
+        }
+
+        (shadow_views, shadows_gpu_data)
+    }
+
+    /// Log a warning when the overflow starts, and warn again if it starts again after it is cleared.
+    fn update_overflow_warning(&mut self, overflow_reason: Option<&str>) {
+        match overflow_reason {
+            Some(reason) if !self.has_warned_overflow => {
+                log::warn!("Some lights do not cast shadow because {}.", reason);
+                self.has_warned_overflow = true;
+            }
+            Some(_) => {}
+            None => self.has_warned_overflow = false,
+        }
+    }
+
+    /// Compute the split distances of cascades, which blend uniform and logarithmic splits.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<f32>` - `cascade_count + 1` distances from `near` to `far`.
+    pub(crate) fn compute_cascade_splits(near: f32, far: f32, cascade_count: u32, lambda: f32) -> Vec<f32> {
+        let mut splits = Vec::with_capacity(cascade_count as usize + 1);
+        splits.push(near);
+        for i in 1..cascade_count {
+            let p = i as f32 / cascade_count as f32;
+            let log_split = near * (far / near).powf(p);
+            let uniform_split = near + (far - near) * p;
+            splits.push(lambda * log_split + (1.0 - lambda) * uniform_split);
+        }
+        splits.push(far);
+        splits
+    }
+
+    fn up_vector_of(direction: Vec3) -> Vec3 {
+        if direction.y.abs() > 0.99 {
+            Vec3::Z
+        } else {
+            Vec3::Y
+        }
+    }
+
+    /// Each view is (view projection matrix, scale of normal offset, is perspective projection).
+    fn compute_directional_views(
+        shadow_casting_light: &ShadowCastingLight,
+        camera: &Camera,
+        camera_view_matrix: &Mat4,
+        resolution: u32,
+    ) -> Vec<(Mat4, f32, bool)> {
+        let settings = &shadow_casting_light.settings;
+        let direction = shadow_casting_light.direction;
+        let up = Self::up_vector_of(direction);
+        let far = camera.far.min(settings.max_shadow_distance).max(camera.near + 0.001);
+        let cascade_count = settings.cascade_count.clamp(1, MAX_SHADOW_CASCADES);
+        let splits = Self::compute_cascade_splits(camera.near, far, cascade_count, settings.cascade_split_lambda);
+        // Shadow casters behind the cascade bounding sphere still need to be rendered.
+        let caster_extension = settings.max_shadow_distance;
+        let light_rotation = Mat4::look_at_rh(Vec3::ZERO, direction, up);
+        let inverse_light_rotation = light_rotation.inverse();
+
+        let mut views = Vec::with_capacity(cascade_count as usize);
+        for i in 0..cascade_count as usize {
+            let projection = camera.get_projection_matrix_with_range(splits[i], splits[i + 1]);
+            let inverse_view_projection = (projection * *camera_view_matrix).inverse();
+            let mut corners = [Vec3::ZERO; 8];
+            let mut center = Vec3::ZERO;
+            for (index, corner) in corners.iter_mut().enumerate() {
+                let ndc = Vec4::new(
+                    if index & 1 == 0 { -1.0 } else { 1.0 },
+                    if index & 2 == 0 { -1.0 } else { 1.0 },
+                    if index & 4 == 0 { 0.0 } else { 1.0 },
+                    1.0,
+                );
+                let world = inverse_view_projection * ndc;
+                *corner = world.truncate() / world.w;
+                center += *corner;
+            }
+            center /= 8.0;
+            let mut radius = 0.0f32;
+            for corner in &corners {
+                radius = radius.max(corner.distance(center));
+            }
+            // Quantize the radius and snap the center to texels to avoid shimmering when the camera moves.
+            radius = (radius * 16.0).ceil() / 16.0;
+            let texel_world_size = 2.0 * radius / resolution as f32;
+            let mut center_in_light_space = light_rotation.transform_point3(center);
+            center_in_light_space.x = (center_in_light_space.x / texel_world_size).floor() * texel_world_size;
+            center_in_light_space.y = (center_in_light_space.y / texel_world_size).floor() * texel_world_size;
+            let center = inverse_light_rotation.transform_point3(center_in_light_space);
+
+            let eye = center - direction * (radius + caster_extension);
+            let view = Mat4::look_at_rh(eye, center, up);
+            let projection = Mat4::orthographic_rh(-radius, radius, -radius, radius, 0.0, 2.0 * radius + caster_extension);
+            views.push((projection * view, texel_world_size, false));
+        }
+        views
+    }
+
+    fn compute_spot_views(
+        shadow_casting_light: &ShadowCastingLight,
+        max_distance: f32,
+        outer_angle: f32,
+        resolution: u32,
+    ) -> Vec<(Mat4, f32, bool)> {
+        let settings = &shadow_casting_light.settings;
+        let position = shadow_casting_light.position;
+        let direction = shadow_casting_light.direction;
+        let fov = (2.0 * outer_angle).clamp(0.01, std::f32::consts::PI - 0.01);
+        let near = settings.near_plane.max(0.001);
+        let far = max_distance.max(near + 0.001);
+        let view = Mat4::look_at_rh(position, position + direction, Self::up_vector_of(direction));
+        let projection = Mat4::perspective_rh(fov, 1.0, near, far);
+        // texel size in world units at the distance of 1.0 from the light.
+        let texel_size_at_unit_distance = 2.0 * (fov * 0.5).tan() / resolution as f32;
+        vec![(projection * view, texel_size_at_unit_distance, true)]
+    }
+
+    /// The six faces are in the order of +X, -X, +Y, -Y, +Z, -Z, which must be the same as `pbr.wgsl`.
+    fn compute_point_views(
+        shadow_casting_light: &ShadowCastingLight,
+        max_distance: f32,
+        resolution: u32,
+    ) -> Vec<(Mat4, f32, bool)> {
+        let settings = &shadow_casting_light.settings;
+        let position = shadow_casting_light.position;
+        let near = settings.near_plane.max(0.001);
+        let far = max_distance.max(near + 0.001);
+        let projection = Mat4::perspective_rh(std::f32::consts::FRAC_PI_2, 1.0, near, far);
+        let texel_size_at_unit_distance = 2.0 / resolution as f32;
+        let faces = [
+            (Vec3::X, Vec3::NEG_Y),
+            (Vec3::NEG_X, Vec3::NEG_Y),
+            (Vec3::Y, Vec3::Z),
+            (Vec3::NEG_Y, Vec3::NEG_Z),
+            (Vec3::Z, Vec3::NEG_Y),
+            (Vec3::NEG_Z, Vec3::NEG_Y),
+        ];
+        faces
+            .iter()
+            .map(|(face_direction, up)| {
+                let view = Mat4::look_at_rh(position, position + *face_direction, *up);
+                (projection * view, texel_size_at_unit_distance, true)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shadow_atlas_allocate() {
+        let mut atlas = ShadowAtlas::new();
+        atlas.size = 1024;
+        assert_eq!(atlas.allocate(0), None);
+        assert_eq!(atlas.allocate(2048), None);
+
+        // Tiles fit in the first shelf.
+        assert_eq!(atlas.allocate(512), Some(ShadowAtlasTile { x: 0, y: 0, size: 512 }));
+        assert_eq!(atlas.allocate(512), Some(ShadowAtlasTile { x: 512, y: 0, size: 512 }));
+        // The shelf is full, so the next tile starts a new shelf below the tallest tile.
+        assert_eq!(atlas.allocate(256), Some(ShadowAtlasTile { x: 0, y: 512, size: 256 }));
+        assert_eq!(atlas.allocate(256), Some(ShadowAtlasTile { x: 256, y: 512, size: 256 }));
+        assert_eq!(atlas.allocate(512), Some(ShadowAtlasTile { x: 512, y: 512, size: 512 }));
+        // No shelf is left.
+        assert_eq!(atlas.allocate(256), None);
+
+        atlas.reset();
+        assert_eq!(atlas.allocate(1024), Some(ShadowAtlasTile { x: 0, y: 0, size: 1024 }));
+        assert_eq!(atlas.allocate(1), None);
+    }
+
+    #[test]
+    fn test_compute_cascade_splits() {
+        let assert_splits_eq = |splits: &[f32], expected: &[f32]| {
+            assert_eq!(splits.len(), expected.len());
+            for (split, expected_split) in splits.iter().zip(expected) {
+                assert!((split - expected_split).abs() < 1e-3, "{:?} != {:?}", splits, expected);
+            }
+        };
+        // Uniform splits.
+        assert_splits_eq(&ShadowMapper::compute_cascade_splits(1.0, 100.0, 4, 0.0), &[1.0, 25.75, 50.5, 75.25, 100.0]);
+        // Logarithmic splits.
+        assert_splits_eq(&ShadowMapper::compute_cascade_splits(1.0, 100.0, 4, 1.0), &[1.0, 3.1623, 10.0, 31.6228, 100.0]);
+        assert_eq!(ShadowMapper::compute_cascade_splits(0.1, 50.0, 1, 0.75), vec![0.1, 50.0]);
+
+        let splits = ShadowMapper::compute_cascade_splits(0.1, 50.0, 4, 0.75);
+        assert_eq!(splits.len(), 5);
+        assert_eq!(splits[0], 0.1);
+        assert_eq!(splits[4], 50.0);
+        assert!(splits.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn test_shadow_view_contains_caster() {
+        let max_distance = 5.0;
+        let point_light = ShadowCastingLight {
+            light_index: 0,
+            light_type: LightType::Point { max_distance },
+            position: Vec3::ZERO,
+            direction: Vec3::NEG_Y,
+            settings: ShadowSettings::default(),
+            culling_mask: LayerMask::new(Layer::Default.bit()),
+        };
+        // The +X face of the cube shadow map.
+        let (view_projection, _, _) = ShadowMapper::compute_point_views(&point_light, max_distance, 256)[0];
+        let shadow_view = ShadowView {
+            view_projection,
+            tile: ShadowAtlasTile { x: 0, y: 0, size: 256 },
+            culling_mask: point_light.culling_mask,
+            frustum: Frustum::from_view_projection(&view_projection),
+            range: Some(BoundingSphere::new(point_light.position, max_distance)),
+        };
+        let unit_box_at = |center: Vec3| Aabb::from_center_half_extents(center, Vec3::splat(0.5));
+
+        assert!(shadow_view.contains_caster(Layer::Default, Some(&unit_box_at(Vec3::new(3.0, 0.0, 0.0)))));
+        // Casters in other faces, out of the light range or in other layers are culled.
+        assert!(!shadow_view.contains_caster(Layer::Default, Some(&unit_box_at(Vec3::new(-3.0, 0.0, 0.0)))));
+        assert!(!shadow_view.contains_caster(Layer::Default, Some(&unit_box_at(Vec3::new(10.0, 0.0, 0.0)))));
+        assert!(!shadow_view.contains_caster(Layer::UI, Some(&unit_box_at(Vec3::new(3.0, 0.0, 0.0)))));
+        // The corners of the face frustum are out of the light range.
+        let corner_box = unit_box_at(Vec3::new(4.0, 3.5, 3.5));
+        assert!(shadow_view.frustum.intersects_aabb(&corner_box));
+        assert!(!shadow_view.contains_caster(Layer::Default, Some(&corner_box)));
+        // Casters without bounds are never culled.
+        assert!(shadow_view.contains_caster(Layer::Default, None));
+    }
+
+    #[test]
+    fn test_overflow_warning() {
+        let mut shadow_mapper = ShadowMapper::new();
+        shadow_mapper.update_overflow_warning(Some("the shadow atlas is full"));
+        assert!(shadow_mapper.has_warned_overflow);
+        shadow_mapper.update_overflow_warning(Some("too many shadow views"));
+        assert!(shadow_mapper.has_warned_overflow);
+        // The warning is logged again once the overflow is cleared.
+        shadow_mapper.update_overflow_warning(None);
+        assert!(!shadow_mapper.has_warned_overflow);
+    }
+}
@@ -60,10 +60,10 @@ impl UIRenderer {
         &mut self,
         logic_context: &mut LogicContext,
         graphics_context: & GraphicsContext,
+        window: &WindowWinit,
         window_surface_view: &TextureView,
         behavior_wrappers: &mut Vec<BehaviorWrapper>,
     ) {
-        let window= graphics_context.main_window().get_ref();
         let device: &Device = &graphics_context.device;
         let queue: &Queue = &graphics_context.queue;
 
@@ -2,11 +2,27 @@ use std::time::Instant;
 
 use crate::math::Vec4;
 
+/// The default delta (in seconds) of fixed updates.
+pub const DEFAULT_FIXED_TIMESTEP: f32 = 1.0 / 50.0;
+/// The default max number of fixed updates per frame.
+pub const DEFAULT_MAX_FIXED_SUBSTEPS: u32 = 8;
+
 pub struct Time {
     start: Instant,
     last_frame_time: f32,
     scale: f32,
     enable_time_scale_flag: bool,
+    /// If it is Some, every frame advances the time by this delta (in seconds) instead of the wall clock time,
+    /// which makes rendering deterministic, e.g., in headless mode.
+    frame_delta_override: Option<f32>,
+    /// The delta (in seconds) of every fixed update.
+    fixed_timestep: f32,
+    /// The max number of fixed updates per frame, so that a slow frame does not cause more and more fixed updates.
+    max_fixed_substeps: u32,
+    /// The scaled time (in seconds) which has not been consumed by fixed updates.
+    fixed_accumulator: f64,
+    frame_count: u64,
+    is_paused: bool,
     /// x: time since started, y: delta time, z: scaled delta time, w: sin(time)
     pub(crate) time_data: Vec4,
 }
@@ -19,6 +35,12 @@ impl Time {
             last_frame_time: 0.0,
             scale: 1.0,
             enable_time_scale_flag: false,
+            frame_delta_override: None,
+            fixed_timestep: DEFAULT_FIXED_TIMESTEP,
+            max_fixed_substeps: DEFAULT_MAX_FIXED_SUBSTEPS,
+            fixed_accumulator: 0.0,
+            frame_count: 0,
+            is_paused: false,
             time_data: Vec4::ZERO,
         }
     }
@@ -29,38 +51,131 @@ impl Time {
         self.last_frame_time = 0.0;
         self.scale = 1.0;
         self.enable_time_scale_flag = false;
+        self.fixed_accumulator = 0.0;
+        self.frame_count = 0;
+        self.is_paused = false;
         self.time_data.x = 0.0;
         self.time_data.y = 0.0;
         self.time_data.z = 0.0;
         self.time_data.w = 0.0;
     }
 
-    /// Time (in seconds) elapsed since game starts.
+    /// Time (in seconds) elapsed since game starts, excluding the paused time.
     pub fn elapsed(&self) -> f32 {
         self.time_data.x
     }
 
-    /// Delta time inseconds.
+    /// Delta time inseconds. It is zero while paused.
     pub fn delta(&self) -> f32 {
         self.time_data.y
     }
 
+    /// Delta time multiplied by the time scale if the time scale is enabled, otherwise it equals to [`Time::delta`].
     pub fn scaled_delta(&self) -> f32 {
         self.time_data.z
     }
 
+    /// Wall clock time (in seconds) elapsed since game starts, including the paused time.
+    pub fn real_elapsed(&self) -> f32 {
+        self.last_frame_time
+    }
+
     pub fn sin_time(&self) -> f32 {
         self.time_data.w
     }
 
     pub fn on_update(&mut self) {
-        self.time_data.x = self.start.elapsed().as_secs_f32();
-        self.time_data.y = self.time_data.x - self.last_frame_time;
-        if self.enable_time_scale_flag {
-            self.time_data.z = self.time_data.y * self.scale;
-        }
-        self.last_frame_time = self.time_data.x;
+        let frame_time = if let Some(frame_delta_override) = self.frame_delta_override {
+            self.last_frame_time + frame_delta_override
+        } else {
+            self.start.elapsed().as_secs_f32()
+        };
+        let delta = if self.is_paused { 0.0 } else { frame_time - self.last_frame_time };
+        self.last_frame_time = frame_time;
+        self.time_data.x += delta;
+        self.time_data.y = delta;
+        self.time_data.z = if self.enable_time_scale_flag { delta * self.scale } else { delta };
         self.time_data.w = self.time_data.x.sin();
+        self.fixed_accumulator += self.time_data.z as f64;
+        self.frame_count += 1;
+    }
+
+    /// Consume the accumulated time by whole fixed timesteps.
+    /// 
+    /// # Returns
+    /// 
+    /// * `u32` - The number of fixed updates to run in this frame, at most [`Time::max_fixed_substeps`].
+    pub(crate) fn consume_fixed_steps(&mut self) -> u32 {
+        let fixed_timestep = self.fixed_timestep as f64;
+        // Tolerate rounding errors, e.g., when the frame delta equals to the fixed timestep.
+        let steps = ((self.fixed_accumulator + 1e-6) / fixed_timestep).floor();
+        if steps > self.max_fixed_substeps as f64 {
+            self.fixed_accumulator %= fixed_timestep;
+            self.max_fixed_substeps
+        } else {
+            self.fixed_accumulator = (self.fixed_accumulator - steps * fixed_timestep).max(0.0);
+            steps as u32
+        }
+    }
+
+    /// How far (from 0 to 1) the current frame is between the last fixed update and the next one.
+    /// It is used to interpolate transforms for rendering.
+    pub fn interpolation_alpha(&self) -> f32 {
+        (self.fixed_accumulator / self.fixed_timestep as f64).clamp(0.0, 1.0) as f32
+    }
+
+    /// The delta (in seconds) of every fixed update, see [`crate::prelude::Behavior::on_fixed_update`].
+    pub fn fixed_timestep(&self) -> f32 {
+        self.fixed_timestep
+    }
+
+    /// Set the delta (in seconds) of every fixed update. It is 1/50 second by default.
+    pub fn set_fixed_timestep(&mut self, fixed_timestep: f32) {
+        if fixed_timestep > 0.0 {
+            self.fixed_timestep = fixed_timestep;
+        } else {
+            log::warn!("Invalid fixed timestep {}, it must be positive.", fixed_timestep);
+        }
+    }
+
+    /// The max number of fixed updates per frame.
+    pub fn max_fixed_substeps(&self) -> u32 {
+        self.max_fixed_substeps
+    }
+
+    /// Set the max number of fixed updates per frame. The remaining time is dropped when a frame is too slow.
+    pub fn set_max_fixed_substeps(&mut self, max_fixed_substeps: u32) {
+        self.max_fixed_substeps = max_fixed_substeps;
+    }
+
+    /// The number of frames since game starts.
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    /// Pause the time. The deltas are zero while paused, so no fixed update runs.
+    pub fn pause(&mut self) {
+        self.is_paused = true;
+    }
+
+    /// Resume the paused time.
+    pub fn resume(&mut self) {
+        self.is_paused = false;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.is_paused
+    }
+
+    /// Advance the time by the given delta (in seconds) every frame instead of the wall clock time.
+    /// Pass None to use the wall clock time again. It is unrelated to the fixed timestep of fixed updates.
+    pub fn set_frame_delta_override(&mut self, frame_delta_override: Option<f32>) {
+        self.frame_delta_override = frame_delta_override;
+    }
+
+    /// The frame delta override (in seconds) set by [`Time::set_frame_delta_override`], None if the wall clock time is used.
+    pub fn frame_delta_override(&self) -> Option<f32> {
+        self.frame_delta_override
     }
 
     pub fn set_scale(&mut self, scale: f32) {
@@ -74,4 +189,45 @@ impl Time {
     pub fn disable_time_scale(&mut self) {
         self.enable_time_scale_flag = false;
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_steps() {
+        let mut time = Time::new();
+        time.set_frame_delta_override(Some(0.05));
+        time.set_fixed_timestep(0.02);
+
+        time.on_update();
+        assert_eq!(time.consume_fixed_steps(), 2);
+        assert!((time.interpolation_alpha() - 0.5).abs() < 1e-4);
+        time.on_update();
+        assert_eq!(time.consume_fixed_steps(), 3);
+        assert!(time.interpolation_alpha() < 1e-4);
+
+        // Scaled time drives fixed updates.
+        time.set_scale(0.4);
+        time.enable_time_scale();
+        time.on_update();
+        assert!((time.scaled_delta() - 0.02).abs() < 1e-6);
+        assert_eq!(time.consume_fixed_steps(), 1);
+        time.disable_time_scale();
+
+        time.pause();
+        time.on_update();
+        assert_eq!(time.delta(), 0.0);
+        assert_eq!(time.consume_fixed_steps(), 0);
+        assert!((time.elapsed() - 0.15).abs() < 1e-6);
+        assert!((time.real_elapsed() - 0.2).abs() < 1e-6);
+        time.resume();
+
+        // A slow frame is capped by the max substeps.
+        time.set_frame_delta_override(Some(1.0));
+        time.on_update();
+        assert_eq!(time.consume_fixed_steps(), DEFAULT_MAX_FIXED_SUBSTEPS);
+        assert!(time.interpolation_alpha() < 1.0);
+        assert_eq!(time.frame_count(), 5);
+    }
+}
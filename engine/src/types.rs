@@ -94,6 +94,13 @@ macro_rules! impl_component {
             // $crate::impl_as_any!();
         }
     };
+    ($struct_name:ident, Clone) => {
+        impl $crate::components::component::Component for $struct_name {
+            fn clone_component(&self) -> Option<Self> {
+                Some(self.clone())
+            }
+        }
+    };
 }
 
 pub use impl_as_any;
@@ -7,7 +7,7 @@ use crate::{
     math::{Vec3, spherical_harmonics::SphericalHarmonics},
     prelude::{
         graphics_context::GraphicsContext,
-        texture_buffer_converter::{CubeTextureData, read_cube_texture},
+        texture_buffer_converter::{TextureData, read_cube_texture},
     },
 };
 
@@ -113,7 +113,7 @@ impl CubeMapToSHTools {
         let cube_texture_content = read_cube_texture(graphics_context, cube_texture);
         match cube_texture_content {
             Ok(content) => match content {
-                CubeTextureData::U8(cube_data) => {
+                TextureData::U8(cube_data) => {
                     let cube_data_float = cube_data
                         .iter()
                         .map(|face_data| {
@@ -131,11 +131,11 @@ impl CubeMapToSHTools {
                         .collect::<Vec<Vec<f32>>>();
                     self.generate_sh_from_f32(cube_data_float, cube_texture.size.width, 3)
                 }
-                CubeTextureData::F32(cube_data) => {
+                TextureData::F32(cube_data) => {
                     self.generate_sh_from_f32(cube_data, cube_texture.size.width, 4)
                 }
-                CubeTextureData::U16(_cube_data) => todo!(),
-                CubeTextureData::I32(_cube_data) => todo!(),
+                TextureData::U16(_cube_data) => todo!(),
+                TextureData::I32(_cube_data) => todo!(),
             },
             Err(e) => panic!("{}", e),
         }
@@ -1,6 +1,6 @@
 use crate::{
     assets::{
-        TextureDimension, TextureFormat, TextureHandle, TextureUsages,
+        MaterialManager, MeshManager, ShaderManager, TextureDimension, TextureFormat, TextureHandle, TextureUsages,
         environment::skybox::Skybox, materials::material::Material,
         meshes::{mesh::Mesh, primitives::cuboid::Cuboid},
         sampler::Sampler
@@ -38,59 +38,75 @@ impl SkyboxBuilder {
         texture_format: TextureFormat,
         skybox_builder_options: SkyboxBuilderOptions,
     ) ->crate::prelude::NodeHandle {
-        // create cubemap texture and sampler.
-        let skybox_texture_handle: TextureHandle;
+        // create cubemap texture.
         let input_texture_handle: TextureHandle;
-        let mut is_inpunt_cube_map = false;
-        let skybox_sampler = Sampler::default_sampler();
+        let is_inpunt_cube_map: bool;
         {
             let cube_map_data_length = cube_map_data.len();
-            let is_cube_map = cube_map_data_length == 6;
+            // cube_map_data_length == 1 means an equirectangular texture, more steps are needed when initialization.
+            is_inpunt_cube_map = cube_map_data_length == 6;
             input_texture_handle = engine.texture_sampler_manager.create_texture_from_image_with_usages(
                 cube_map_data,
                 TextureDimension::D2,
                 texture_format,
                 skybox_builder_options.is_flip_y,
-                is_cube_map,
+                is_inpunt_cube_map,
                 TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_SRC | TextureUsages::COPY_DST,
             );
-            if is_cube_map {
-                skybox_texture_handle = input_texture_handle;
-                is_inpunt_cube_map = true;
-            } else {
-                // cube_map_data_length == 1，more steps are needed when initialization.
-                skybox_texture_handle = TextureHandle::INVALID;
-            };
         }
 
+        let (mesh_renderer, skybox_component) = Self::create_skybox_components(
+            input_texture_handle,
+            is_inpunt_cube_map,
+            &mut engine.shader_manager,
+            &mut engine.material_manager,
+            &mut engine.mesh_manager,
+        );
+
+        // create cuboid that represents Skybox geometry.
+        let scene = engine.world.current_scene_mut();
+        let cuboid_node = scene.create_node("Skybox");
+        scene.add_component(&cuboid_node, mesh_renderer);
+        scene.add_component(&cuboid_node, skybox_component);
+        scene.cached_skybox_ = cuboid_node;
+        scene.add(cuboid_node.clone());
+        cuboid_node
+    }
+
+    /// Create the MeshRenderer (a cuboid with skybox material) and the Skybox component of a skybox node.
+    ///
+    /// # Arguments
+    ///
+    /// * `input_texture_handle` - The skybox texture, either a cube map or an equirectangular 2D texture.
+    /// * `is_input_cube_map` - Whether the input texture is a cube map.
+    pub(crate) fn create_skybox_components(
+        input_texture_handle: TextureHandle,
+        is_input_cube_map: bool,
+        shader_manager: &mut ShaderManager,
+        material_manager: &mut MaterialManager,
+        mesh_manager: &mut MeshManager,
+    ) -> (MeshRenderer, Skybox) {
+        let skybox_sampler = Sampler::default_sampler();
         // create skybox material
-        let (_, shader_skybox_handle) = engine.shader_manager.get_builtin_skybox_shader();
-        let mut material_skybox = Material::new(*shader_skybox_handle, &mut engine.shader_manager);
+        let (_, shader_skybox_handle) = shader_manager.get_builtin_skybox_shader();
+        let mut material_skybox = Material::new(*shader_skybox_handle, shader_manager);
         {
-            if is_inpunt_cube_map {
-                material_skybox.set_texture("skybox_cube_texture", skybox_texture_handle);
+            if is_input_cube_map {
+                material_skybox.set_texture("skybox_cube_texture", input_texture_handle);
             }
             material_skybox.set_sampler("skybox_cube_sampler", skybox_sampler);
             // note here.
             material_skybox.render_state.cull_mode = CullMode::Front;
             material_skybox.render_state.render_queue = RenderQueue::Skybox;
         }
+        let material_skybox_handle = material_manager.add_material(material_skybox);
 
-        let material_skybox_handle = engine.material_manager.add_material(material_skybox);
-
-        // create cuboid that represents Skybox geometry.
-        let scene = engine.world.current_scene_mut();
-        let cuboid_node = scene.create_node("Skybox");
-        {
-            let mesh: Mesh = Cuboid::default().into();
-            let mesh_handle = engine.mesh_manager.add_mesh(mesh);
-            let mesh_renderer = MeshRenderer::new(mesh_handle, vec![material_skybox_handle]);
-            scene.add_component(&cuboid_node, mesh_renderer);
-            let skybox_component = Skybox::new(input_texture_handle, is_inpunt_cube_map);
-            scene.add_component(&cuboid_node, skybox_component);
-        }
-        scene.cached_skybox_ = cuboid_node;
-        scene.add(cuboid_node.clone());
-        cuboid_node
+        let mesh: Mesh = Cuboid::default().into();
+        let mesh_handle = mesh_manager.add_mesh(mesh);
+        let mut mesh_renderer = MeshRenderer::new(mesh_handle, vec![material_skybox_handle]);
+        // The skybox is drawn around the camera by its shader, wherever the node is.
+        mesh_renderer.frustum_culling = false;
+        let skybox_component = Skybox::new(input_texture_handle, is_input_cube_map);
+        (mesh_renderer, skybox_component)
     }
 }
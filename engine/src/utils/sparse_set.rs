@@ -1,3 +1,203 @@
-pub struct SparseSet {
+/// A generational key of a [`SparseSet`].
+///
+/// A key whose generation differs from the stored one does not match, so a stale key never aliases a value inserted
+/// later at the same index.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SparseKey {
+    /// The index into the sparse array.
+    pub index: u32,
+    /// The generation of the index.
+    pub generation: u32,
+}
 
-}
\ No newline at end of file
+impl SparseKey {
+    pub fn new(index: u32, generation: u32) -> Self {
+        Self { index, generation }
+    }
+}
+
+impl std::fmt::Display for SparseKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}v{}", self.index, self.generation)
+    }
+}
+
+const EMPTY: u32 = u32::MAX;
+
+/// A map from [`SparseKey`]s to values which are packed in contiguous memory.
+///
+/// The sparse array maps the index of a key to the position of its value in the dense arrays, so lookups are plain
+/// array accesses. Removal swaps the last value into the hole, which keeps the values packed but does not keep
+/// their order.
+pub struct SparseSet<T> {
+    sparse: Vec<u32>,
+    dense: Vec<T>,
+    keys: Vec<SparseKey>,
+}
+
+impl<T> Default for SparseSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SparseSet<T> {
+    /// Create a new empty SparseSet.
+    pub fn new() -> Self {
+        Self {
+            sparse: Vec::new(),
+            dense: Vec::new(),
+            keys: Vec::new(),
+        }
+    }
+
+    /// The number of values.
+    pub fn len(&self) -> usize {
+        self.dense.len()
+    }
+
+    /// Whether there is no value.
+    pub fn is_empty(&self) -> bool {
+        self.dense.is_empty()
+    }
+
+    /// The position of the value of `key` in the dense arrays.
+    #[inline]
+    pub fn dense_index(&self, key: &SparseKey) -> Option<usize> {
+        let dense_index = *self.sparse.get(key.index as usize)?;
+        if dense_index != EMPTY && self.keys[dense_index as usize] == *key {
+            Some(dense_index as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Whether `key` has a value.
+    pub fn contains(&self, key: &SparseKey) -> bool {
+        self.dense_index(key).is_some()
+    }
+
+    /// Insert a value.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<T>` - The old value of `key`. A value of an older generation at the same index is dropped.
+    pub fn insert(&mut self, key: SparseKey, value: T) -> Option<T> {
+        let index = key.index as usize;
+        if index >= self.sparse.len() {
+            self.sparse.resize(index + 1, EMPTY);
+        }
+        let dense_index = self.sparse[index];
+        if dense_index == EMPTY {
+            self.sparse[index] = self.dense.len() as u32;
+            self.dense.push(value);
+            self.keys.push(key);
+            None
+        } else {
+            let old_key = std::mem::replace(&mut self.keys[dense_index as usize], key);
+            let old_value = std::mem::replace(&mut self.dense[dense_index as usize], value);
+            (old_key == key).then_some(old_value)
+        }
+    }
+
+    /// Remove the value of `key`.
+    pub fn remove(&mut self, key: &SparseKey) -> Option<T> {
+        let dense_index = self.dense_index(key)?;
+        self.sparse[key.index as usize] = EMPTY;
+        self.keys.swap_remove(dense_index);
+        let value = self.dense.swap_remove(dense_index);
+        if let Some(moved_key) = self.keys.get(dense_index) {
+            self.sparse[moved_key.index as usize] = dense_index as u32;
+        }
+        Some(value)
+    }
+
+    pub fn get(&self, key: &SparseKey) -> Option<&T> {
+        self.dense_index(key).map(|dense_index| &self.dense[dense_index])
+    }
+
+    pub fn get_mut(&mut self, key: &SparseKey) -> Option<&mut T> {
+        self.dense_index(key).map(|dense_index| &mut self.dense[dense_index])
+    }
+
+    /// The values in dense order.
+    pub fn values(&self) -> &[T] {
+        &self.dense
+    }
+
+    /// The values in dense order.
+    pub fn values_mut(&mut self) -> &mut [T] {
+        &mut self.dense
+    }
+
+    /// The keys in the same order as [`SparseSet::values`].
+    pub fn keys(&self) -> &[SparseKey] {
+        &self.keys
+    }
+
+    /// Iterate over the keys and values in dense order.
+    pub fn iter(&self) -> impl Iterator<Item = (&SparseKey, &T)> {
+        self.keys.iter().zip(self.dense.iter())
+    }
+
+    /// Iterate over the keys and mutable values in dense order.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&SparseKey, &mut T)> {
+        self.keys.iter().zip(self.dense.iter_mut())
+    }
+
+    /// Pointer to the value of `key`, which does not borrow the whole set, so pointers to several values can be held
+    /// at the same time.
+    ///
+    /// # Safety
+    ///
+    /// `this` must be valid. The pointer is only valid for reads, and until the set is modified.
+    pub(crate) unsafe fn get_ptr(this: *const Self, key: &SparseKey) -> Option<*const T> {
+        unsafe {
+            let dense_index = (*this).dense_index(key)?;
+            Some((*this).dense.as_ptr().add(dense_index))
+        }
+    }
+
+    /// Mutable version of [`SparseSet::get_ptr`].
+    ///
+    /// # Safety
+    ///
+    /// `this` must be valid for writes. The pointer is valid until the set is modified.
+    pub(crate) unsafe fn get_mut_ptr(this: *mut Self, key: &SparseKey) -> Option<*mut T> {
+        unsafe {
+            let dense_index = (*this).dense_index(key)?;
+            Some((*this).dense.as_mut_ptr().add(dense_index))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sparse_set() {
+        let mut set = SparseSet::new();
+        let a = SparseKey::new(3, 0);
+        let b = SparseKey::new(0, 0);
+        let c = SparseKey::new(7, 1);
+        assert!(set.insert(a, "a").is_none());
+        assert!(set.insert(b, "b").is_none());
+        assert!(set.insert(c, "c").is_none());
+        assert_eq!(set.values(), &["a", "b", "c"]);
+
+        assert_eq!(set.remove(&a), Some("a"));
+        assert_eq!(set.remove(&a), None);
+        assert_eq!(set.values(), &["c", "b"]);
+        assert_eq!(set.get(&c), Some(&"c"));
+
+        // A stale key does not alias the value of a newer generation.
+        let new_b = SparseKey::new(0, 1);
+        assert_eq!(set.insert(new_b, "new b"), None);
+        assert_eq!(set.get(&b), None);
+        assert!(set.remove(&b).is_none());
+        assert_eq!(set.get(&new_b), Some(&"new b"));
+        assert_eq!(set.insert(new_b, "newer b"), Some("new b"));
+        assert_eq!(set.len(), 2);
+    }
+}
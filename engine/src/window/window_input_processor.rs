@@ -5,7 +5,7 @@ use winit::{
     keyboard::{KeyCode, PhysicalKey},
 };
 
-use crate::input::input_manager::InputManager;
+use crate::input::{action_map::MouseButtonCode, input_manager::InputManager, input_recorder::InputEvent};
 
 // use crate::input::{InputManager, MouseEvent, MouseEventType};
 
@@ -18,6 +18,8 @@ pub(crate) struct WindowInputProcessor {
 
 
 impl WindowInputProcessor {
+    /// The logical pixels of a scroll line, which is used to convert the pixel scroll deltas of touchpads.
+    const SCROLL_PIXELS_PER_LINE: f64 = 20.0;
 
     pub(crate) fn new() -> Self {
         Self {
@@ -26,6 +28,7 @@ impl WindowInputProcessor {
     }
 
     /// Process window input events.
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn process_window_input(
         &mut self,
         event: WindowEvent,
@@ -33,6 +36,8 @@ impl WindowInputProcessor {
         dpi: f64,
         input_manager: &mut InputManager,
         is_ui_interacting: bool,
+        is_ui_using_keyboard: bool,
+        exit_on_escape: bool,
     ) {
         match event {
             WindowEvent::KeyboardInput {
@@ -40,7 +45,13 @@ impl WindowInputProcessor {
                 event,
                 is_synthetic: _,
             } => {
-                self.process_keyboard_event(event, event_loop, input_manager);
+                self.process_keyboard_event(event, event_loop, input_manager, is_ui_using_keyboard, exit_on_escape);
+            }
+            WindowEvent::ModifiersChanged(modifiers) => {
+                input_manager.handle_event(InputEvent::ModifiersChanged(modifiers.state()));
+            }
+            WindowEvent::Focused(false) => {
+                input_manager.handle_event(InputEvent::FocusLost);
             }
             // TODO: process mouse input
             WindowEvent::MouseInput {
@@ -83,31 +94,42 @@ impl WindowInputProcessor {
     }
 
     /// Process keyboard inputs.
+    /// 
+    /// Key presses and text are ignored while UI is using keyboard, but key releases are always recorded
+    /// so that no key gets stuck.
     fn process_keyboard_event(
         &mut self,
         event: KeyEvent,
         event_loop: &winit::event_loop::ActiveEventLoop,
-        _input_manager: &InputManager,
+        input_manager: &mut InputManager,
+        is_ui_using_keyboard: bool,
+        exit_on_escape: bool,
     ) {
-        match event {
-            KeyEvent {
-                physical_key,
-                logical_key: _,
-                text: _,
-                location: _,
-                state,
-                repeat: _,
-                ..
-            } => match physical_key {
-                PhysicalKey::Code(key_code) => {
-                    if key_code == KeyCode::Escape && state == ElementState::Released {
+        let KeyEvent {
+            physical_key,
+            text,
+            state,
+            ..
+        } = event;
+        if state == ElementState::Pressed && !is_ui_using_keyboard && let Some(text) = &text {
+            input_manager.handle_event(InputEvent::TextInput(text.to_string()));
+        }
+        if let PhysicalKey::Code(key_code) = physical_key {
+            match state {
+                ElementState::Pressed => {
+                    if !is_ui_using_keyboard {
+                        input_manager.handle_event(InputEvent::KeyPressed(key_code));
+                    }
+                }
+                ElementState::Released => {
+                    input_manager.handle_event(InputEvent::KeyReleased(key_code));
+                    if exit_on_escape && key_code == KeyCode::Escape {
                         log::info!("Press Escape");
                         // self._event_dispatcher.borrow_mut().emit(Events::EVENT_WINDOW_CLOSED);
                         event_loop.exit();
                     }
                 }
-                _ => (),
-            },
+            }
         }
     }
 
@@ -126,13 +148,13 @@ impl WindowInputProcessor {
                     //     ),
                     // );
 
-                    input_manager.on_mouse_left_button_down(&self._cursor_logical_pos);
+                    input_manager.handle_event(InputEvent::MouseButtonPressed(MouseButtonCode::Left));
                     // info!("Left mouse button is pressed at: ({}, {})", self._cursor_logical_pos.x, self._cursor_logical_pos.y);
                     // input_manager
                     //     .trigger_mouse_input_event(MouseEvent::new(self.cursor_logical_pos, MouseEventType::LeftPressed));
                 }
                 ElementState::Released => {
-                    input_manager.on_mouse_left_button_up(&self._cursor_logical_pos);
+                    input_manager.handle_event(InputEvent::MouseButtonReleased(MouseButtonCode::Left));
                     // self._event_dispatcher.borrow_mut().emit(
                     //     GeneralMouseEventData::new_left_button_up_event(
                     //         self._cursor_logical_pos,
@@ -145,7 +167,7 @@ impl WindowInputProcessor {
             },
             MouseButton::Right => match state {
                 ElementState::Pressed => {
-                    input_manager.on_mouse_right_button_down(&self._cursor_logical_pos);
+                    input_manager.handle_event(InputEvent::MouseButtonPressed(MouseButtonCode::Right));
                     // self._event_dispatcher.borrow_mut().emit(
                     //     GeneralMouseEventData::new_right_button_down_event(
                     //         self._cursor_logical_pos,
@@ -154,7 +176,7 @@ impl WindowInputProcessor {
                     // info!("Right mouse button is pressed at: ({}, {})", self._cursor_logical_pos.x, self._cursor_logical_pos.y);
                 }
                 ElementState::Released => {
-                    input_manager.on_mouse_right_button_up(&self._cursor_logical_pos);
+                    input_manager.handle_event(InputEvent::MouseButtonReleased(MouseButtonCode::Right));
                     // self._event_dispatcher.borrow_mut().emit(
                     //     GeneralMouseEventData::new_right_button_up_event(
                     //         self._cursor_logical_pos,
@@ -165,11 +187,11 @@ impl WindowInputProcessor {
             },
             MouseButton::Middle => match state {
                 ElementState::Pressed => {
-                    input_manager.on_mouse_middle_button_down(&self._cursor_logical_pos);
+                    input_manager.handle_event(InputEvent::MouseButtonPressed(MouseButtonCode::Middle));
                     log::info!("Middle mouse button is pressed at: ({}, {})", self._cursor_logical_pos.x, self._cursor_logical_pos.y);
                 }
                 ElementState::Released => {
-                    input_manager.on_mouse_middle_button_up(&self._cursor_logical_pos);
+                    input_manager.handle_event(InputEvent::MouseButtonReleased(MouseButtonCode::Middle));
                     log::info!("Middle mouse button is released at: ({}, {})", self._cursor_logical_pos.x, self._cursor_logical_pos.y);
                 }
             },
@@ -187,7 +209,10 @@ impl WindowInputProcessor {
         let logical_x = pos_x / dpi;
         let logical_y = pos_y / dpi;
         self._cursor_logical_pos = Vec2::new(logical_x as f32, logical_y as f32);
-        input_manager.on_mouse_move(&self._cursor_logical_pos);
+        input_manager.handle_event(InputEvent::MouseMoved {
+            x: self._cursor_logical_pos.x,
+            y: self._cursor_logical_pos.y,
+        });
 
         // log::info!("window input processor, mouse pos: {}", self._cursor_logical_pos);
         // input_manager.trigger_mouse_input_event(MouseEvent::new(
@@ -200,33 +225,23 @@ impl WindowInputProcessor {
         &mut self,
         delta: MouseScrollDelta,
         phase: TouchPhase,
-        _dpi: f64,
-        _input_manager: &mut InputManager,
+        dpi: f64,
+        input_manager: &mut InputManager,
     ) {
         match phase {
             TouchPhase::Started => {
                 // log::info!("Scroll started.");// no output on Windows.
             }
             TouchPhase::Moved => match delta {
-                #[allow(unused)]
                 MouseScrollDelta::LineDelta(x, y) => {
-                    // log::info!("LineDelta: {x}, {y}");
-                    // TODO: Windows execute the branch. Select a proper speed.
                     // (x, y) is (0, 1) or (0, -1), or other intergers on Windows.
-                    // let speed = 10.0;
-                    // input_manager.trigger_mouse_input_event(MouseEvent::new(
-                    //     self.cursor_logical_pos,
-                    //     MouseEventType::Scroll(Vec2::new(x as f32 * speed, y as f32 * speed)),
-                    // ));
+                    input_manager.handle_event(InputEvent::MouseScrolled { x, y });
                 }
-                MouseScrollDelta::PixelDelta(_pos) => {
-                    // let x = pos.x / dpi;
-                    // let y = pos.y / dpi;
-                    // info!("PixelDelta: {x}, {y}");
-                    // input_manager.trigger_mouse_input_event(MouseEvent::new(
-                    //     self.cursor_logical_pos,
-                    //     MouseEventType::Scroll(Vec2::new(x as f32, y as f32)),
-                    // ));
+                MouseScrollDelta::PixelDelta(pos) => {
+                    // Touchpads report pixels, convert them to lines so that all devices share the same unit.
+                    let x = pos.x / dpi / Self::SCROLL_PIXELS_PER_LINE;
+                    let y = pos.y / dpi / Self::SCROLL_PIXELS_PER_LINE;
+                    input_manager.handle_event(InputEvent::MouseScrolled { x: x as f32, y: y as f32 });
                 }
             },
             TouchPhase::Ended | TouchPhase::Cancelled => {
@@ -0,0 +1,221 @@
+//! Golden-image test harness.
+//!
+//! A scene is rendered in headless mode on wgpu's fallback adapter, then the captured frame is compared with the
+//! reference image under `tests/golden/<name>.png` with a perceptual tolerance.
+//!
+//! - If `IMAGIC_UPDATE_GOLDEN=1` is set, the captured frame is saved as the new reference image instead.
+//! - If the reference image does not exist, the captured frame is written to `target/tmp/golden/` and the test fails.
+//! - On mismatch, `<name>_actual.png` and `<name>_diff.png` are written to `target/tmp/golden/` and the test fails.
+//! - If no fallback adapter is available, the test fails. Tests which render are marked `#[ignore]`, so that they
+//!   are reported as ignored by `cargo test`, and are run by `cargo test -- --include-ignored` on machines with a
+//!   software renderer, e.g., lavapipe.
+
+use std::{
+    path::PathBuf,
+    sync::{Mutex, MutexGuard},
+};
+
+use imagic::{graphics::graphics_context::GraphicsContext, prelude::*};
+
+/// The width and height of the frames rendered by golden tests.
+pub const GOLDEN_IMAGE_SIZE: f32 = 256.0;
+/// The number of frames rendered before the capture, so that resources created lazily are ready.
+const FRAME_COUNT: u32 = 3;
+/// Set this environment variable to `1` to regenerate all reference images.
+const UPDATE_GOLDEN_ENV: &str = "IMAGIC_UPDATE_GOLDEN";
+
+/// Golden tests create their own graphics devices, run them one by one to limit the memory usage of the fallback adapter.
+static GOLDEN_TEST_LOCK: Mutex<()> = Mutex::new(());
+
+/// The tolerance used to compare two images.
+#[derive(Debug, Clone, Copy)]
+pub struct Tolerance {
+    /// The perceptual color difference in [0, 1] below which two pixels are considered identical.
+    pub pixel_threshold: f32,
+    /// The ratio of mismatched pixels in [0, 1] allowed before the images are considered different.
+    pub max_mismatch_ratio: f32,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self {
+            pixel_threshold: 0.1,
+            max_mismatch_ratio: 0.005,
+        }
+    }
+}
+
+/// The result of comparing an image with its reference.
+pub struct ImageComparison {
+    pub mismatched_pixels: u32,
+    pub total_pixels: u32,
+    /// The reference image in gray with mismatched pixels highlighted in red.
+    pub diff_image: image::RgbaImage,
+}
+
+impl ImageComparison {
+    pub fn mismatch_ratio(&self) -> f32 {
+        self.mismatched_pixels as f32 / self.total_pixels.max(1) as f32
+    }
+}
+
+/// Render the scene built by `build_scene` headlessly and compare it with the reference image named `name`.
+pub fn run_golden_test(name: &str, build_scene: impl FnOnce(&mut Engine)) {
+    run_golden_test_with_tolerance(name, Tolerance::default(), build_scene);
+}
+
+/// The same as [`run_golden_test`] but with a custom tolerance.
+pub fn run_golden_test_with_tolerance(name: &str, tolerance: Tolerance, build_scene: impl FnOnce(&mut Engine)) {
+    let _guard = lock_golden_tests();
+    assert!(
+        GraphicsContext::is_headless_adapter_available(true),
+        "Golden test {}: no fallback adapter is available, install a software renderer such as lavapipe",
+        name
+    );
+
+    let actual = render_scene(build_scene);
+    let reference_path = golden_dir().join(format!("{}.png", name));
+    if std::env::var(UPDATE_GOLDEN_ENV).is_ok_and(|value| value == "1") {
+        std::fs::create_dir_all(golden_dir()).expect("Failed to create the golden image directory");
+        actual.save(&reference_path).expect("Failed to save the reference image");
+        return;
+    }
+
+    let output_dir = output_dir();
+    let actual_path = output_dir.join(format!("{}_actual.png", name));
+    if !reference_path.exists() {
+        std::fs::create_dir_all(&output_dir).expect("Failed to create the golden output directory");
+        actual.save(&actual_path).expect("Failed to save the actual image");
+        panic!(
+            "Golden test {}: reference image {} does not exist, actual image saved to {}. Run with {}=1 to accept it",
+            name,
+            reference_path.display(),
+            actual_path.display(),
+            UPDATE_GOLDEN_ENV
+        );
+    }
+    let reference = image::open(&reference_path)
+        .unwrap_or_else(|err| panic!("Failed to load reference image {}: {}", reference_path.display(), err))
+        .to_rgba8();
+    if reference.dimensions() != actual.dimensions() {
+        std::fs::create_dir_all(&output_dir).expect("Failed to create the golden output directory");
+        actual.save(&actual_path).expect("Failed to save the actual image");
+        panic!(
+            "Golden test {}: size mismatch, expected {:?} but got {:?}, actual image saved to {}",
+            name,
+            reference.dimensions(),
+            actual.dimensions(),
+            actual_path.display()
+        );
+    }
+
+    let comparison = compare_images(&reference, &actual, tolerance.pixel_threshold);
+    if comparison.mismatch_ratio() > tolerance.max_mismatch_ratio {
+        std::fs::create_dir_all(&output_dir).expect("Failed to create the golden output directory");
+        let diff_path = output_dir.join(format!("{}_diff.png", name));
+        actual.save(&actual_path).expect("Failed to save the actual image");
+        comparison.diff_image.save(&diff_path).expect("Failed to save the diff image");
+        panic!(
+            "Golden test {}: {} of {} pixels ({:.3}%) differ from {}, which exceeds {:.3}%. See {} and {}",
+            name,
+            comparison.mismatched_pixels,
+            comparison.total_pixels,
+            comparison.mismatch_ratio() * 100.0,
+            reference_path.display(),
+            tolerance.max_mismatch_ratio * 100.0,
+            actual_path.display(),
+            diff_path.display()
+        );
+    }
+}
+
+/// Compare two images of the same size pixel by pixel.
+///
+/// The difference of two pixels is measured in YIQ color space, which is closer to human perception than RGB, and
+/// normalized to [0, 1]. Pixels whose difference exceeds `pixel_threshold` are counted as mismatched.
+pub fn compare_images(reference: &image::RgbaImage, actual: &image::RgbaImage, pixel_threshold: f32) -> ImageComparison {
+    assert_eq!(reference.dimensions(), actual.dimensions(), "Only images of the same size can be compared");
+    // The max YIQ delta, i.e., the delta between black and white.
+    const MAX_DELTA: f32 = 35215.0;
+    let max_delta = MAX_DELTA * pixel_threshold * pixel_threshold;
+    let (width, height) = reference.dimensions();
+    let mut diff_image = image::RgbaImage::new(width, height);
+    let mut mismatched_pixels = 0;
+    for (x, y, expected_pixel) in reference.enumerate_pixels() {
+        let actual_pixel = actual.get_pixel(x, y);
+        let delta = color_delta(expected_pixel, actual_pixel);
+        let diff_pixel = if delta > max_delta {
+            mismatched_pixels += 1;
+            image::Rgba([255, 0, 0, 255])
+        } else {
+            // Fade the reference image so that mismatched pixels stand out.
+            let gray = (255.0 - 0.1 * (255.0 - luminance(expected_pixel))) as u8;
+            image::Rgba([gray, gray, gray, 255])
+        };
+        diff_image.put_pixel(x, y, diff_pixel);
+    }
+    ImageComparison {
+        mismatched_pixels,
+        total_pixels: width * height,
+        diff_image,
+    }
+}
+
+fn lock_golden_tests() -> MutexGuard<'static, ()> {
+    // A failed golden test poisons the lock, which should not fail the others.
+    GOLDEN_TEST_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn render_scene(build_scene: impl FnOnce(&mut Engine)) -> image::RgbaImage {
+    let engine_options = EngineOptions {
+        window_size: WindowSize::new(GOLDEN_IMAGE_SIZE, GOLDEN_IMAGE_SIZE),
+        app_name: "imagic golden test",
+        headless: Some(HeadlessOptions {
+            frame_count: FRAME_COUNT,
+            color_format: TextureFormat::Rgba8UnormSrgb,
+            force_fallback_adapter: true,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let mut engine = Engine::new(engine_options);
+    build_scene(&mut engine);
+    engine.init_headless();
+    for _ in 0..FRAME_COUNT {
+        engine.render_frame();
+    }
+    engine.capture_frame().expect("Failed to capture the frame").to_rgba8()
+}
+
+fn golden_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("golden")
+}
+
+fn output_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("golden")
+}
+
+/// Blend the pixel with white background by its alpha.
+fn blend_with_white(pixel: &image::Rgba<u8>) -> [f32; 3] {
+    let alpha = pixel[3] as f32 / 255.0;
+    [0, 1, 2].map(|i| 255.0 + (pixel[i] as f32 - 255.0) * alpha)
+}
+
+fn luminance(pixel: &image::Rgba<u8>) -> f32 {
+    let [r, g, b] = blend_with_white(pixel);
+    r * 0.29889531 + g * 0.58662247 + b * 0.11448223
+}
+
+/// The squared YIQ distance of two pixels.
+fn color_delta(a: &image::Rgba<u8>, b: &image::Rgba<u8>) -> f32 {
+    if a == b {
+        return 0.0;
+    }
+    let [r1, g1, b1] = blend_with_white(a);
+    let [r2, g2, b2] = blend_with_white(b);
+    let (dr, dg, db) = (r1 - r2, g1 - g2, b1 - b2);
+    let y = dr * 0.29889531 + dg * 0.58662247 + db * 0.11448223;
+    let i = dr * 0.59597799 - dg * 0.27417610 - db * 0.32180189;
+    let q = dr * 0.21147017 - dg * 0.52261711 + db * 0.31114694;
+    0.5053 * y * y + 0.299 * i * i + 0.1957 * q * q
+}
@@ -0,0 +1,67 @@
+//! Golden-image tests which render the scenes of the examples headlessly.
+//!
+//! The scenes are built by `examples/common/scenes.rs`, which the examples use too. The tests which render are
+//! ignored by default, run them on a machine with a fallback adapter, e.g., lavapipe, by
+//! `cargo test -p imagic --test golden_images -- --include-ignored`.
+//!
+//! No reference images are committed under `tests/golden/` yet, so these tests do not guard against regressions
+//! until the references are generated by
+//! `IMAGIC_UPDATE_GOLDEN=1 cargo test -p imagic --test golden_images -- --include-ignored` on the fallback adapter
+//! and committed. Without a reference image, a test which renders fails. Regenerate the references the same way after
+//! intended rendering changes.
+
+mod common;
+#[path = "../examples/common/scenes.rs"]
+mod scenes;
+
+use common::{compare_images, run_golden_test};
+
+const SKYBOX_HDR: &[u8] = include_bytes!("../examples/assets/images/hdri/resting_place_2_1k.hdr");
+const LENA_PNG: &[u8] = include_bytes!("../examples/assets/images/lena.png");
+
+#[test]
+fn identical_images_have_no_mismatch() {
+    let image = image::load_from_memory(LENA_PNG).unwrap().to_rgba8();
+    let comparison = compare_images(&image, &image, 0.1);
+    assert_eq!(comparison.mismatched_pixels, 0);
+
+    let mut changed = image.clone();
+    changed.put_pixel(0, 0, image::Rgba([255, 0, 0, 255]));
+    changed.put_pixel(1, 0, image::Rgba([0, 0, 255, 255]));
+    let comparison = compare_images(&image, &changed, 0.1);
+    assert_eq!(comparison.mismatched_pixels, 2);
+    assert_eq!(*comparison.diff_image.get_pixel(0, 0), image::Rgba([255, 0, 0, 255]));
+}
+
+#[test]
+#[ignore = "renders on a fallback adapter, run with --include-ignored"]
+fn primitives() {
+    run_golden_test("primitives", |engine| {
+        let material = scenes::create_white_pbr_material(engine);
+        scenes::build_primitives_scene(engine, SKYBOX_HDR, material);
+    });
+}
+
+#[test]
+#[ignore = "renders on a fallback adapter, run with --include-ignored"]
+fn unlit() {
+    run_golden_test("unlit", |engine| {
+        scenes::build_unlit_scene(engine, LENA_PNG);
+    });
+}
+
+#[test]
+#[ignore = "renders on a fallback adapter, run with --include-ignored"]
+fn ibl() {
+    run_golden_test("ibl", |engine| {
+        scenes::build_ibl_scene(engine, SKYBOX_HDR);
+    });
+}
+
+#[test]
+#[ignore = "renders on a fallback adapter, run with --include-ignored"]
+fn spot_light() {
+    run_golden_test("spot_light", |engine| {
+        scenes::build_spot_light_scene(engine, SKYBOX_HDR);
+    });
+}
@@ -0,0 +1,98 @@
+//! Drive Behaviors by replaying recorded inputs in headless mode.
+
+use imagic::{graphics::graphics_context::GraphicsContext, prelude::*};
+
+/// A recorded session which drags the mouse to the right with left button held down.
+fn drag_recording() -> InputRecording {
+    let mut frames = vec![RecordedFrame {
+        delta_time: 1.0 / 60.0,
+        events: vec![
+            InputEvent::MouseMoved { x: 100.0, y: 100.0 },
+            InputEvent::MouseButtonPressed(MouseButtonCode::Left),
+        ],
+    }];
+    for i in 1..=10 {
+        frames.push(RecordedFrame {
+            delta_time: 1.0 / 60.0,
+            events: vec![InputEvent::MouseMoved { x: 100.0 + i as f32 * 10.0, y: 100.0 }],
+        });
+    }
+    frames.push(RecordedFrame {
+        delta_time: 1.0 / 60.0,
+        events: vec![InputEvent::MouseButtonReleased(MouseButtonCode::Left)],
+    });
+    InputRecording { frames }
+}
+
+fn replay_camera_drag(recording: InputRecording) -> Vec3 {
+    let mut engine = Engine::new(EngineOptions {
+        window_size: WindowSize::new(64.0, 64.0),
+        headless: Some(HeadlessOptions {
+            force_fallback_adapter: true,
+            ..Default::default()
+        }),
+        ..Default::default()
+    });
+    let camera_node = {
+        let scene = engine.world.current_scene_mut();
+        let camera_node = scene.create_node("Main Camera");
+        scene.add_component(&camera_node, Camera::default());
+        scene.get_node_mut_forcely(&camera_node).transform.set_position(Vec3::new(0.0, 0.0, 5.0));
+        scene.add(camera_node);
+        camera_node
+    };
+    engine.add_camera_controller(camera_node);
+    let frame_count = recording.frames.len();
+    engine.input_manager.start_replay(recording);
+    engine.init_headless();
+    for _ in 0..frame_count {
+        engine.render_frame();
+    }
+    engine.world.current_scene_mut().get_node_mut_forcely(&camera_node).transform.position
+}
+
+#[test]
+fn replay_reproduces_input_states() {
+    let recording = drag_recording();
+    let recording = InputRecording::from_ron_str(&recording.to_ron_string().unwrap()).unwrap();
+    let mut input_manager = InputManager::new();
+    input_manager.action_map.bind_action("drag", ButtonBinding::Mouse(MouseButtonCode::Left));
+    // Inputs from the window are ignored while replaying.
+    input_manager.start_replay(recording.clone());
+    input_manager.handle_event(InputEvent::MouseMoved { x: 0.0, y: 0.0 });
+
+    let mut states = Vec::new();
+    for frame in &recording.frames {
+        input_manager.on_frame_begin(frame.delta_time);
+        let action_state = input_manager.action_state("drag");
+        states.push((
+            input_manager.mouse_data.cur_mouse_pos,
+            input_manager.mouse_data.delta,
+            action_state.pressed,
+            action_state.just_pressed,
+            action_state.just_released,
+        ));
+        input_manager.on_frame_end();
+    }
+    assert_eq!(states[0], (Vec2::new(100.0, 100.0), Vec2::ZERO, true, true, false));
+    for state in &states[1..=10] {
+        assert_eq!((state.1, state.2, state.3), (Vec2::new(10.0, 0.0), true, false));
+    }
+    assert_eq!(states[5].0, Vec2::new(150.0, 100.0));
+    assert_eq!(states[11], (Vec2::new(200.0, 100.0), Vec2::ZERO, false, false, true));
+}
+
+#[test]
+#[ignore = "renders on a fallback adapter, run with --include-ignored"]
+fn replay_drives_camera_controller() {
+    assert!(
+        GraphicsContext::is_headless_adapter_available(true),
+        "No fallback adapter is available, install a software renderer such as lavapipe"
+    );
+    let recording = drag_recording();
+    let recording = InputRecording::from_ron_str(&recording.to_ron_string().unwrap()).unwrap();
+    let first_position = replay_camera_drag(recording.clone());
+    assert_ne!(first_position, Vec3::new(0.0, 0.0, 5.0), "The camera should be rotated by the replayed drag");
+    // The same recording reproduces the same session.
+    assert_eq!(replay_camera_drag(recording), first_position);
+}